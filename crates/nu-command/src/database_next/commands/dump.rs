@@ -0,0 +1,80 @@
+use crate::database_next::values::{dump, DatabaseValue};
+use nu_engine::command_prelude::*;
+
+#[derive(Clone)]
+pub struct DbDump;
+
+impl Command for DbDump {
+    fn name(&self) -> &str {
+        "db dump"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .input_output_types(vec![(
+                Type::Custom("database".into()),
+                Type::List(Box::new(Type::String)),
+            )])
+            .allow_variants_without_examples(true)
+            .category(Category::Database)
+    }
+
+    fn usage(&self) -> &str {
+        "Dump a database as SQL text, one statement per line."
+    }
+
+    fn extra_usage(&self) -> &str {
+        "Equivalent to `.dump` in the sqlite3 shell: schema statements followed by an INSERT per row, wrapped in a transaction. Useful for diffing databases or piping to other tools."
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["database", "sql", "export", "backup"]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        _stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let span = call.head;
+        let database = DatabaseValue::try_from_value(input.into_value(span)?)?;
+
+        let lines = database
+            .with_connection(dump::dump_database)
+            .map_err(|err| err.into_shell_error(span))?;
+
+        Ok(lines
+            .into_iter()
+            .map(move |line| Value::string(line, span))
+            .into_pipeline_data(span, engine_state.ctrlc.clone()))
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![
+            Example {
+                description: "Dump a database as SQL statements",
+                example: "from db my_data.db | db dump",
+                result: None,
+            },
+            Example {
+                description: "Save a SQL dump to a file",
+                example: "from db my_data.db | db dump | save my_data.sql",
+                result: None,
+            },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples() {
+        use crate::test_examples;
+
+        test_examples(DbDump {})
+    }
+}
@@ -0,0 +1,128 @@
+use crate::database_next::values::{remote, DatabaseStorage, DatabaseValue};
+use nu_engine::command_prelude::*;
+
+#[derive(Clone)]
+pub struct FromDb;
+
+impl Command for FromDb {
+    fn name(&self) -> &str {
+        "from db"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .input_output_types(vec![(Type::Nothing, Type::Custom("database".into()))])
+            .allow_variants_without_examples(true)
+            .required(
+                "file-name",
+                SyntaxShape::Filepath,
+                "File path of the database to open.",
+            )
+            .switch(
+                "readonly",
+                "Open the database read-only, failing if the file doesn't exist",
+                Some('r'),
+            )
+            .named(
+                "pool-size",
+                SyntaxShape::Int,
+                "Number of pooled connections for read-only databases (default 4)",
+                None,
+            )
+            .category(Category::Database)
+    }
+
+    fn usage(&self) -> &str {
+        "Open a SQLite database file as a database value."
+    }
+
+    fn extra_usage(&self) -> &str {
+        "The returned value keeps its connection alive, so connection-scoped state like temporary tables survives between commands.
+
+http(s) URLs are downloaded to a local cache (revalidated via ETag on later opens) and opened read-only."
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["open", "database", "sqlite"]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let file_name: Spanned<String> = call.req(engine_state, stack, 0)?;
+        let remote = remote::is_remote_url(&file_name.item);
+        // remote databases are cached locally and always opened read-only
+        let readonly = remote || call.has_flag(engine_state, stack, "readonly")?;
+        let pool_size: Option<Spanned<i64>> = call.get_flag(engine_state, stack, "pool-size")?;
+
+        if let Some(pool_size) = &pool_size {
+            if !readonly {
+                return Err(ShellError::IncompatibleParametersSingle {
+                    msg: "--pool-size only applies to read-only databases".into(),
+                    span: pool_size.span,
+                });
+            }
+            if pool_size.item < 1 {
+                return Err(ShellError::IncorrectValue {
+                    msg: "pool size must be at least 1".into(),
+                    val_span: pool_size.span,
+                    call_span: call.head,
+                });
+            }
+        }
+
+        let storage = if remote {
+            let path = remote::fetch_remote_database(&file_name.item)
+                .map_err(|err| err.into_shell_error(file_name.span))?;
+            DatabaseStorage::ReadonlyFile(path)
+        } else if readonly {
+            DatabaseStorage::ReadonlyFile(file_name.item.into())
+        } else {
+            DatabaseStorage::File(file_name.item.into())
+        };
+
+        let database = match pool_size {
+            Some(pool_size) => DatabaseValue::open_with_pool_size(storage, pool_size.item as usize),
+            None => DatabaseValue::open(storage),
+        }
+        .map_err(|err| err.into_shell_error(file_name.span))?;
+
+        Ok(database.into_value(call.head).into_pipeline_data())
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![
+            Example {
+                description: "Open a database file",
+                example: "from db my_data.db",
+                result: None,
+            },
+            Example {
+                description: "Open a database file read-only and list a table",
+                example: "from db my_data.db --readonly | get my_table",
+                result: None,
+            },
+            Example {
+                description: "Open a remote database, downloading it to a local cache",
+                example: "from db https://example.org/my_data.db",
+                result: None,
+            },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples() {
+        use crate::test_examples;
+
+        test_examples(FromDb {})
+    }
+}
@@ -0,0 +1,101 @@
+use super::to_db;
+use crate::database_next::values::insert::InsertOptions;
+use nu_engine::command_prelude::*;
+
+/// Compatibility shim for `into sqlite` on top of the next-generation
+/// plumbing.
+///
+/// This keeps the old flag surface (`--table-name`) so existing scripts keep
+/// working while the `database-cmd-next` experimental option is enabled, but
+/// routes the actual work through the same code path as `to db`.
+#[derive(Clone)]
+pub struct IntoSqliteDb;
+
+impl Command for IntoSqliteDb {
+    fn name(&self) -> &str {
+        "into sqlite"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .category(Category::Conversions)
+            .input_output_types(vec![
+                (Type::table(), Type::Nothing),
+                (Type::record(), Type::Nothing),
+            ])
+            .allow_variants_without_examples(true)
+            .required(
+                "file-name",
+                SyntaxShape::String,
+                "Specify the filename to save the database to.",
+            )
+            .named(
+                "table-name",
+                SyntaxShape::String,
+                "Specify table name to store the data in",
+                Some('t'),
+            )
+    }
+
+    fn usage(&self) -> &str {
+        "Convert table into a SQLite database."
+    }
+
+    fn extra_usage(&self) -> &str {
+        "This version of the command is a compatibility shim on top of `to db`, enabled by the `database-cmd-next` experimental option."
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["convert", "database"]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let file_name: Spanned<String> = call.req(engine_state, stack, 0)?;
+        let table_name: Option<Spanned<String>> =
+            call.get_flag(engine_state, stack, "table-name")?;
+
+        // the classic command always stored nulls as SQL NULL, keep that
+        to_db::operate(
+            file_name,
+            table_name,
+            InsertOptions::default(),
+            call.head,
+            input,
+        )
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![
+            Example {
+                description:
+                    "Convert ls entries into a SQLite database with 'main' as the table name",
+                example: "ls | into sqlite my_ls.db",
+                result: None,
+            },
+            Example {
+                description:
+                    "Convert ls entries into a SQLite database with 'my_table' as the table name",
+                example: "ls | into sqlite my_ls.db -t my_table",
+                result: None,
+            },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples() {
+        use crate::test_examples;
+
+        test_examples(IntoSqliteDb {})
+    }
+}
@@ -0,0 +1,94 @@
+use crate::database_next::values::DatabaseValue;
+use nu_engine::command_prelude::*;
+
+#[derive(Clone)]
+pub struct DbList;
+
+impl Command for DbList {
+    fn name(&self) -> &str {
+        "db list"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .input_output_types(vec![(
+                Type::Custom("database".into()),
+                Type::Table(
+                    [
+                        ("seq".into(), Type::Int),
+                        ("name".into(), Type::String),
+                        ("file".into(), Type::String),
+                    ]
+                    .into(),
+                ),
+            )])
+            .allow_variants_without_examples(true)
+            .category(Category::Database)
+    }
+
+    fn usage(&self) -> &str {
+        "List the databases attached to a database connection."
+    }
+
+    fn extra_usage(&self) -> &str {
+        "Shows one row per attached schema with its position, name, and backing file. In-memory and temporary schemas have no file."
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["database", "schema", "attach"]
+    }
+
+    fn run(
+        &self,
+        _engine_state: &EngineState,
+        _stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let span = call.head;
+        let database = DatabaseValue::try_from_value(input.into_value(span)?)?;
+
+        let databases = database
+            .with_connection(|conn| conn.database_list())
+            .map_err(|err| err.into_shell_error(span))?;
+
+        let rows = databases
+            .into_iter()
+            .map(|database| {
+                Value::record(
+                    record! {
+                        "seq" => Value::int(database.seq, span),
+                        "name" => Value::string(database.name, span),
+                        "file" => match database.file {
+                            Some(file) => Value::string(file.to_string_lossy(), span),
+                            None => Value::nothing(span),
+                        },
+                    },
+                    span,
+                )
+            })
+            .collect();
+
+        Ok(Value::list(rows, span).into_pipeline_data())
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![Example {
+            description: "See which schemas are attached and where their files live",
+            example: "from db my_data.db | db list",
+            result: None,
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples() {
+        use crate::test_examples;
+
+        test_examples(DbList {})
+    }
+}
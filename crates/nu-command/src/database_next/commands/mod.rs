@@ -0,0 +1,31 @@
+mod db_;
+mod dump;
+mod from_db;
+mod into_sqlite;
+mod list;
+mod query;
+mod temp_table;
+mod to_db;
+
+use db_::Db;
+use dump::DbDump;
+use from_db::FromDb;
+use into_sqlite::IntoSqliteDb;
+use list::DbList;
+use nu_protocol::engine::StateWorkingSet;
+use query::DbQuery;
+use temp_table::DbTempTable;
+use to_db::ToDb;
+
+pub fn add_commands_decls(working_set: &mut StateWorkingSet) {
+    macro_rules! bind_command {
+            ( $command:expr ) => {
+                working_set.add_decl(Box::new($command));
+            };
+            ( $( $command:expr ),* ) => {
+                $( working_set.add_decl(Box::new($command)); )*
+            };
+        }
+
+    bind_command!(Db, DbDump, DbList, DbQuery, DbTempTable, FromDb, IntoSqliteDb, ToDb);
+}
@@ -0,0 +1,96 @@
+use crate::database_next::values::{
+    read::{self, ColumnNaming},
+    DatabaseValue,
+};
+use nu_engine::command_prelude::*;
+
+#[derive(Clone)]
+pub struct DbQuery;
+
+impl Command for DbQuery {
+    fn name(&self) -> &str {
+        "db query"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .input_output_types(vec![(Type::Custom("database".into()), Type::table())])
+            .allow_variants_without_examples(true)
+            .required(
+                "SQL",
+                SyntaxShape::String,
+                "SQL to execute against the database.",
+            )
+            .switch(
+                "raw-columns",
+                "Keep duplicate column names exactly as returned instead of suffixing them",
+                None,
+            )
+            .category(Category::Database)
+    }
+
+    fn usage(&self) -> &str {
+        "Query a database value using SQL."
+    }
+
+    fn extra_usage(&self) -> &str {
+        "Column order always matches the SQL result set. Duplicate column names (e.g. from joins) are deduplicated with `_1`, `_2`, ... suffixes unless --raw-columns is given."
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["database", "sql", "select"]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let span = call.head;
+        let sql: Spanned<String> = call.req(engine_state, stack, 0)?;
+        let naming = if call.has_flag(engine_state, stack, "raw-columns")? {
+            ColumnNaming::Raw
+        } else {
+            ColumnNaming::Deduplicated
+        };
+
+        let database = DatabaseValue::try_from_value(input.into_value(span)?)?;
+        let rows = database
+            .with_connection(|conn| {
+                let stmt = conn.prepare(&sql.item)?;
+                read::read_statement(stmt, naming, span)
+            })
+            .map_err(|err| err.into_shell_error(sql.span))?;
+
+        Ok(rows.into_pipeline_data())
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![
+            Example {
+                description: "Execute SQL against a database value",
+                example: r#"from db foo.db | db query "SELECT * FROM Bar""#,
+                result: None,
+            },
+            Example {
+                description: "Join two tables, keeping duplicate column names as-is",
+                example: r#"from db foo.db | db query "SELECT * FROM a JOIN b ON a.id = b.id" --raw-columns"#,
+                result: None,
+            },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples() {
+        use crate::test_examples;
+
+        test_examples(DbQuery {})
+    }
+}
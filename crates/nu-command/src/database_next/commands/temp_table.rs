@@ -0,0 +1,113 @@
+use crate::database_next::values::{
+    insert::{self, InsertOptions, TableKind},
+    DatabaseTableValue, DatabaseValue,
+};
+use nu_engine::command_prelude::*;
+
+const DEFAULT_TEMP_TABLE_NAME: &str = "nu_temp";
+
+#[derive(Clone)]
+pub struct DbTempTable;
+
+impl Command for DbTempTable {
+    fn name(&self) -> &str {
+        "db temp-table"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .input_output_types(vec![
+                (Type::table(), Type::Custom("database-table".into())),
+                (Type::record(), Type::Custom("database-table".into())),
+            ])
+            .allow_variants_without_examples(true)
+            .required(
+                "database",
+                SyntaxShape::Any,
+                "Database to load the data into.",
+            )
+            .named(
+                "name",
+                SyntaxShape::String,
+                "Name of the temporary table",
+                Some('n'),
+            )
+            .category(Category::Database)
+    }
+
+    fn usage(&self) -> &str {
+        "Load pipeline data into a temporary table on an existing database connection."
+    }
+
+    fn extra_usage(&self) -> &str {
+        "The table is created as a `TEMP` table: it is only visible on the connection of the given database value and doesn't pollute its schema. This makes it easy to join shell data against database data."
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["database", "scratch", "join"]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let database: Value = call.req(engine_state, stack, 0)?;
+        let table_name: Option<Spanned<String>> = call.get_flag(engine_state, stack, "name")?;
+
+        let database = DatabaseValue::try_from_value(database)?;
+        let table_name = match table_name {
+            Some(table_name) => table_name.item,
+            None => DEFAULT_TEMP_TABLE_NAME.to_string(),
+        };
+
+        let connection = database
+            .connection()
+            .map_err(|err| err.into_shell_error(call.head))?;
+        {
+            let mut guard = connection
+                .lock()
+                .expect("no panics while holding the connection lock");
+            insert::insert_pipeline(
+                &mut guard,
+                &table_name,
+                TableKind::TempTable,
+                &InsertOptions::default(),
+                input,
+                call.head,
+            )?;
+        }
+
+        let table = DatabaseTableValue::new(database, table_name);
+        Ok(table.into_value(call.head).into_pipeline_data())
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![
+            Example {
+                description: "Load ls entries into a temporary table",
+                example: "ls | db temp-table (from db my_data.db)",
+                result: None,
+            },
+            Example {
+                description: "Load data into a named temporary table",
+                example: "[[id]; [1] [2]] | db temp-table (from db my_data.db) --name wanted",
+                result: None,
+            },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples() {
+        use crate::test_examples;
+
+        test_examples(DbTempTable {})
+    }
+}
@@ -0,0 +1,141 @@
+use crate::database_next::values::{
+    insert::{self, InsertOptions, NullHandling, TableKind},
+    DatabaseStorage, DatabaseValue,
+};
+use nu_engine::command_prelude::*;
+
+pub const DEFAULT_TABLE_NAME: &str = "main";
+
+#[derive(Clone)]
+pub struct ToDb;
+
+impl Command for ToDb {
+    fn name(&self) -> &str {
+        "to db"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .input_output_types(vec![
+                (Type::table(), Type::Nothing),
+                (Type::record(), Type::Nothing),
+            ])
+            .allow_variants_without_examples(true)
+            .required(
+                "file-name",
+                SyntaxShape::Filepath,
+                "File path of the database to write to.",
+            )
+            .named(
+                "table-name",
+                SyntaxShape::String,
+                "Name of the table to store the data in",
+                Some('t'),
+            )
+            .named(
+                "nulls",
+                SyntaxShape::String,
+                "How null values and missing record keys are stored: 'null' (default), 'error', or 'default'",
+                None,
+            )
+            .switch(
+                "empty-string-null",
+                "Store empty strings as SQL NULL instead of ''",
+                None,
+            )
+            .category(Category::Database)
+    }
+
+    fn usage(&self) -> &str {
+        "Save table data into a SQLite database file."
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["convert", "database", "sqlite"]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let file_name: Spanned<String> = call.req(engine_state, stack, 0)?;
+        let table_name: Option<Spanned<String>> =
+            call.get_flag(engine_state, stack, "table-name")?;
+        let nulls: Option<Spanned<String>> = call.get_flag(engine_state, stack, "nulls")?;
+
+        let options = InsertOptions {
+            null_handling: match &nulls {
+                Some(nulls) => NullHandling::from_flag(nulls)?,
+                None => NullHandling::default(),
+            },
+            empty_string_as_null: call.has_flag(engine_state, stack, "empty-string-null")?,
+        };
+
+        operate(file_name, table_name, options, call.head, input)
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![
+            Example {
+                description: "Save ls entries into a SQLite database with 'main' as the table name",
+                example: "ls | to db my_ls.db",
+                result: None,
+            },
+            Example {
+                description:
+                    "Save ls entries into a SQLite database with 'my_table' as the table name",
+                example: "ls | to db my_ls.db -t my_table",
+                result: None,
+            },
+        ]
+    }
+}
+
+pub(super) fn operate(
+    file_name: Spanned<String>,
+    table_name: Option<Spanned<String>>,
+    options: InsertOptions,
+    span: Span,
+    input: PipelineData,
+) -> Result<PipelineData, ShellError> {
+    let table_name = match table_name {
+        Some(table_name) => table_name.item,
+        None => DEFAULT_TABLE_NAME.to_string(),
+    };
+
+    let database = DatabaseValue::open(DatabaseStorage::File(file_name.item.into()))
+        .map_err(|err| err.into_shell_error(file_name.span))?;
+
+    let connection = database
+        .connection()
+        .map_err(|err| err.into_shell_error(file_name.span))?;
+    let mut guard = connection
+        .lock()
+        .expect("no panics while holding the connection lock");
+
+    insert::insert_pipeline(
+        &mut guard,
+        &table_name,
+        TableKind::Table,
+        &options,
+        input,
+        span,
+    )?;
+
+    Ok(PipelineData::empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples() {
+        use crate::test_examples;
+
+        test_examples(ToDb {})
+    }
+}
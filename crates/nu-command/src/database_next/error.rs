@@ -0,0 +1,72 @@
+use nu_protocol::{ShellError, Span};
+
+/// Errors produced by the next-generation database plumbing.
+///
+/// The plumbing functions in [`values`](super::values) return this instead of
+/// [`ShellError`] so callers can attach the span of whatever the user wrote
+/// when surfacing the error.
+#[derive(Debug)]
+pub enum DatabaseError {
+    /// The underlying SQLite call failed.
+    Sqlite(rusqlite::Error),
+    /// An integer doesn't fit into the `i64` range Nu ints and SQLite
+    /// integers share.
+    ///
+    /// This is raised by the checked conversion paths instead of silently
+    /// wrapping around, e.g. for `u64` rowids near the maximum coming from
+    /// other backends.
+    IntOutOfRange { value: String },
+    /// A file operation around the database failed, e.g. writing the local
+    /// cache of a remote database.
+    Io(std::io::Error),
+    /// Downloading a remote database failed.
+    Download { url: String, msg: String },
+}
+
+impl DatabaseError {
+    /// Convert into a [`ShellError`] pointing at `span`.
+    pub fn into_shell_error(self, span: Span) -> ShellError {
+        match self {
+            DatabaseError::Sqlite(err) => ShellError::GenericError {
+                error: "Database operation failed".into(),
+                msg: err.to_string(),
+                span: Some(span),
+                help: None,
+                inner: vec![],
+            },
+            DatabaseError::IntOutOfRange { value } => ShellError::GenericError {
+                error: "Integer out of range".into(),
+                msg: format!("{value} doesn't fit into the 64-bit integer range"),
+                span: Some(span),
+                help: None,
+                inner: vec![],
+            },
+            DatabaseError::Io(err) => ShellError::GenericError {
+                error: "Database file operation failed".into(),
+                msg: err.to_string(),
+                span: Some(span),
+                help: None,
+                inner: vec![],
+            },
+            DatabaseError::Download { url, msg } => ShellError::GenericError {
+                error: "Failed to download database".into(),
+                msg,
+                span: Some(span),
+                help: Some(format!("tried to download {url}")),
+                inner: vec![],
+            },
+        }
+    }
+}
+
+impl From<rusqlite::Error> for DatabaseError {
+    fn from(err: rusqlite::Error) -> Self {
+        DatabaseError::Sqlite(err)
+    }
+}
+
+impl From<std::io::Error> for DatabaseError {
+    fn from(err: std::io::Error) -> Self {
+        DatabaseError::Io(err)
+    }
+}
@@ -0,0 +1,20 @@
+mod commands;
+mod error;
+mod values;
+
+pub use error::DatabaseError;
+pub use values::{
+    ConnectionPool, DatabaseConnection, DatabaseList, DatabaseStorage, DatabaseTableValue,
+    DatabaseValue, SqlValue, DEFAULT_POOL_SIZE,
+};
+
+use nu_protocol::engine::StateWorkingSet;
+
+/// Register the next-generation database commands.
+///
+/// These are gated behind the `database-cmd-next` experimental option and are
+/// added after the classic database commands, shadowing the ones they
+/// replace.
+pub fn add_database_next_decls(working_set: &mut StateWorkingSet) {
+    commands::add_commands_decls(working_set);
+}
@@ -0,0 +1,118 @@
+use super::super::error::DatabaseError;
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::{ops::Deref, path::PathBuf};
+
+/// Where a database stores its data.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DatabaseStorage {
+    /// A database file on disk, opened read-write.
+    ///
+    /// The file is created if it doesn't exist yet.
+    File(PathBuf),
+    /// A database file on disk, opened read-only.
+    ReadonlyFile(PathBuf),
+    /// An in-memory database that lives as long as its connection.
+    Memory,
+}
+
+/// A live connection to a SQLite database.
+///
+/// This is a thin wrapper around [`rusqlite::Connection`] that adds the
+/// introspection helpers the next-generation database commands are built on.
+/// It derefs to the underlying connection, so all of rusqlite's API stays
+/// available to the plumbing.
+#[derive(Debug)]
+pub struct DatabaseConnection(Connection);
+
+impl DatabaseConnection {
+    /// Open a connection to the given storage.
+    pub fn open(storage: &DatabaseStorage) -> Result<Self, DatabaseError> {
+        let conn = match storage {
+            DatabaseStorage::File(path) => Connection::open(path)?,
+            DatabaseStorage::ReadonlyFile(path) => Connection::open_with_flags(
+                path,
+                OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+            )?,
+            DatabaseStorage::Memory => Connection::open_in_memory()?,
+        };
+
+        Ok(Self(conn))
+    }
+
+    /// The names of all tables on this connection, in `sqlite_master` order.
+    pub fn table_names(&self) -> Result<Vec<String>, DatabaseError> {
+        let mut stmt = self
+            .0
+            .prepare("SELECT name FROM sqlite_master WHERE type = 'table'")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+
+        let mut names = Vec::new();
+        for row in rows {
+            names.push(row?);
+        }
+
+        Ok(names)
+    }
+
+    /// The names of all `TEMP` tables on this connection.
+    pub fn temp_table_names(&self) -> Result<Vec<String>, DatabaseError> {
+        let mut stmt = self
+            .0
+            .prepare("SELECT name FROM sqlite_temp_master WHERE type = 'table'")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+
+        let mut names = Vec::new();
+        for row in rows {
+            names.push(row?);
+        }
+
+        Ok(names)
+    }
+
+    /// The databases attached to this connection, as reported by
+    /// `PRAGMA database_list`.
+    pub fn database_list(&self) -> Result<Vec<DatabaseList>, DatabaseError> {
+        let mut stmt = self.0.prepare("PRAGMA database_list")?;
+        let rows = stmt.query_map([], |row| {
+            Ok(DatabaseList {
+                seq: row.get("seq")?,
+                name: row.get("name")?,
+                file: {
+                    let file: String = row.get("file")?;
+                    if file.is_empty() {
+                        None
+                    } else {
+                        Some(PathBuf::from(file))
+                    }
+                },
+            })
+        })?;
+
+        let mut databases = Vec::new();
+        for row in rows {
+            databases.push(row?);
+        }
+
+        Ok(databases)
+    }
+}
+
+impl Deref for DatabaseConnection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// One row of `PRAGMA database_list`: a database attached to a connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatabaseList {
+    /// The position of the database in the attachment order.
+    pub seq: i64,
+    /// The schema name, `main` for the primary database.
+    pub name: String,
+    /// The backing file, if the database is not in-memory.
+    pub file: Option<PathBuf>,
+}
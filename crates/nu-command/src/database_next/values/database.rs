@@ -0,0 +1,172 @@
+use super::{
+    super::error::DatabaseError,
+    connection::{DatabaseConnection, DatabaseStorage},
+    pool::{ConnectionPool, DEFAULT_POOL_SIZE},
+    read,
+};
+use nu_protocol::{CustomValue, ShellError, Span, Value};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+/// The live connections of a [`DatabaseValue`].
+///
+/// Read-write storages get one shared connection so connection-scoped state
+/// like `TEMP` tables is visible to every user of the value. Read-only
+/// storages can't carry such state and get a pool instead, so concurrent
+/// readers don't serialize on a single mutex.
+#[derive(Debug, Clone)]
+enum Connections {
+    Single(Arc<Mutex<DatabaseConnection>>),
+    Pool(ConnectionPool),
+}
+
+impl Connections {
+    fn open(storage: &DatabaseStorage, pool_size: usize) -> Result<Self, DatabaseError> {
+        Ok(match storage {
+            DatabaseStorage::ReadonlyFile(_) => {
+                Connections::Pool(ConnectionPool::open(storage, pool_size)?)
+            }
+            _ => Connections::Single(Arc::new(Mutex::new(DatabaseConnection::open(storage)?))),
+        })
+    }
+}
+
+/// A database as a value in the pipeline.
+///
+/// Unlike the classic `SQLiteDatabase` this keeps its connection alive for as
+/// long as the value exists, so connection-scoped state like `TEMP` tables and
+/// `ATTACH`ed databases survives between commands.
+/// The connection can't be serialized; a value that crossed a serialization
+/// boundary reconnects to its storage on first use and loses that state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseValue {
+    storage: DatabaseStorage,
+    pool_size: usize,
+    #[serde(skip)]
+    connections: Option<Connections>,
+}
+
+impl DatabaseValue {
+    /// Open a database, eagerly connecting to its storage.
+    pub fn open(storage: DatabaseStorage) -> Result<Self, DatabaseError> {
+        Self::open_with_pool_size(storage, DEFAULT_POOL_SIZE)
+    }
+
+    /// Open a database with an explicit pool size for read-only storages.
+    ///
+    /// The pool size has no effect on read-write storages, which always use
+    /// one shared connection.
+    pub fn open_with_pool_size(
+        storage: DatabaseStorage,
+        pool_size: usize,
+    ) -> Result<Self, DatabaseError> {
+        let connections = Connections::open(&storage, pool_size)?;
+
+        Ok(Self {
+            storage,
+            pool_size,
+            connections: Some(connections),
+        })
+    }
+
+    /// The storage this database lives in.
+    pub fn storage(&self) -> &DatabaseStorage {
+        &self.storage
+    }
+
+    /// A connection to this database.
+    ///
+    /// For read-write storages this is the single shared connection, for
+    /// read-only storages a free connection from the pool.
+    /// Reconnects if the value lost its connections through serialization.
+    pub fn connection(&self) -> Result<Arc<Mutex<DatabaseConnection>>, DatabaseError> {
+        match &self.connections {
+            Some(Connections::Single(connection)) => Ok(connection.clone()),
+            Some(Connections::Pool(pool)) => Ok(pool.acquire()),
+            None => {
+                let connection = DatabaseConnection::open(&self.storage)?;
+                Ok(Arc::new(Mutex::new(connection)))
+            }
+        }
+    }
+
+    /// Run `operation` with the connection locked.
+    pub fn with_connection<T>(
+        &self,
+        operation: impl FnOnce(&DatabaseConnection) -> Result<T, DatabaseError>,
+    ) -> Result<T, DatabaseError> {
+        let connection = self.connection()?;
+        let guard = connection
+            .lock()
+            .expect("no panics while holding the connection lock");
+        operation(&guard)
+    }
+
+    /// Extract a database from a value, failing for any other custom value.
+    pub fn try_from_value(value: Value) -> Result<Self, ShellError> {
+        let span = value.span();
+        match value {
+            Value::Custom { val, .. } => match val.as_any().downcast_ref::<Self>() {
+                Some(db) => Ok(db.clone()),
+                None => Err(ShellError::CantConvert {
+                    to_type: "database".into(),
+                    from_type: "non-database".into(),
+                    span,
+                    help: None,
+                }),
+            },
+            x => Err(ShellError::CantConvert {
+                to_type: "database".into(),
+                from_type: x.get_type().to_string(),
+                span: x.span(),
+                help: None,
+            }),
+        }
+    }
+
+    /// Wrap this database into a [`Value`].
+    pub fn into_value(self, span: Span) -> Value {
+        Value::custom(Box::new(self), span)
+    }
+}
+
+impl CustomValue for DatabaseValue {
+    fn clone_value(&self, span: Span) -> Value {
+        self.clone().into_value(span)
+    }
+
+    fn type_name(&self) -> String {
+        self.typetag_name().to_string()
+    }
+
+    fn to_base_value(&self, span: Span) -> Result<Value, ShellError> {
+        self.with_connection(|conn| read::read_all_tables(conn, span))
+            .map_err(|err| err.into_shell_error(span))
+    }
+
+    fn follow_path_string(
+        &self,
+        _self_span: Span,
+        column_name: String,
+        path_span: Span,
+    ) -> Result<Value, ShellError> {
+        self.with_connection(|conn| read::read_table(conn, &column_name, path_span))
+            .map_err(|err| err.into_shell_error(path_span))
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_mut_any(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+
+    fn typetag_name(&self) -> &'static str {
+        "database"
+    }
+
+    fn typetag_deserialize(&self) {
+        unimplemented!("typetag_deserialize")
+    }
+}
@@ -0,0 +1,325 @@
+use super::{super::error::DatabaseError, connection::DatabaseConnection, sql_value::SqlValue};
+use nu_protocol::{PipelineData, Record, ShellError, Span, Spanned, Value};
+use std::sync::MutexGuard;
+
+/// How a table created by [`insert_pipeline`] is scoped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableKind {
+    /// A regular table, persisted in the database file.
+    Table,
+    /// A `TEMP` table, only visible on this connection and dropped when the
+    /// connection closes.
+    TempTable,
+}
+
+/// How `null` values and missing record keys are treated on insert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NullHandling {
+    /// Insert a SQL `NULL`.
+    #[default]
+    Null,
+    /// Fail the insert with an error pointing at the offending row.
+    Error,
+    /// Omit the column from the `INSERT` so the column default applies.
+    Default,
+}
+
+impl NullHandling {
+    /// Parse the value of a `--nulls` flag.
+    pub fn from_flag(flag: &Spanned<String>) -> Result<Self, ShellError> {
+        match flag.item.as_str() {
+            "null" => Ok(NullHandling::Null),
+            "error" => Ok(NullHandling::Error),
+            "default" => Ok(NullHandling::Default),
+            _ => Err(ShellError::IncorrectValue {
+                msg: "expected 'null', 'error', or 'default'".into(),
+                val_span: flag.span,
+                call_span: flag.span,
+            }),
+        }
+    }
+}
+
+/// Options controlling how [`insert_pipeline`] maps Nu values to SQL.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InsertOptions {
+    /// How `null` values and missing record keys are treated.
+    pub null_handling: NullHandling,
+    /// Whether empty strings are stored as SQL `NULL` instead of `''`.
+    pub empty_string_as_null: bool,
+}
+
+/// Load pipeline data into a table on `conn`, creating the table if needed.
+///
+/// The schema is derived from the first record of the input: column names are
+/// taken verbatim and column types from the SQL representation of the values.
+/// All rows are inserted in a single transaction.
+pub fn insert_pipeline(
+    conn: &mut MutexGuard<DatabaseConnection>,
+    table_name: &str,
+    kind: TableKind,
+    options: &InsertOptions,
+    input: PipelineData,
+    span: Span,
+) -> Result<(), ShellError> {
+    let mut rows = input.into_iter().peekable();
+
+    let first_row = match rows.peek() {
+        None => return Ok(()),
+        Some(value) => value.as_record()?.clone(),
+    };
+
+    if first_row.is_empty() {
+        return Err(ShellError::GenericError {
+            error: "Failed to create table".into(),
+            msg: "cannot create a table without columns".into(),
+            span: Some(span),
+            help: None,
+            inner: vec![],
+        });
+    }
+
+    create_table_if_missing(conn, table_name, kind, &first_row, span)?;
+
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|err| DatabaseError::from(err).into_shell_error(span))?;
+
+    {
+        let columns = first_row.columns().cloned().collect::<Vec<String>>();
+
+        if options.null_handling == NullHandling::Default {
+            // with column defaults in play the set of inserted columns varies
+            // per row, so no statement can be reused across rows
+            for row in rows {
+                insert_row_with_defaults(&tx, table_name, &columns, options, row)?;
+            }
+        } else {
+            let insert_sql = format!(
+                "INSERT INTO [{}] ({}) VALUES ({})",
+                table_name,
+                columns
+                    .iter()
+                    .map(|column| format!("[{column}]"))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                columns
+                    .iter()
+                    .map(|_| "?")
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            );
+
+            let mut stmt = tx
+                .prepare(&insert_sql)
+                .map_err(|err| DatabaseError::from(err).into_shell_error(span))?;
+
+            for row in rows {
+                let row_span = row.span();
+                let record = row.into_record()?;
+                let params = record_to_params(&record, &columns, options, row_span)?;
+
+                stmt.execute(rusqlite::params_from_iter(params))
+                    .map_err(|err| DatabaseError::from(err).into_shell_error(row_span))?;
+            }
+        }
+    }
+
+    tx.commit()
+        .map_err(|err| DatabaseError::from(err).into_shell_error(span))?;
+
+    Ok(())
+}
+
+fn create_table_if_missing(
+    conn: &DatabaseConnection,
+    table_name: &str,
+    kind: TableKind,
+    first_row: &Record,
+    span: Span,
+) -> Result<(), ShellError> {
+    let existing = match kind {
+        TableKind::Table => conn.table_names(),
+        TableKind::TempTable => conn.temp_table_names(),
+    };
+    let table_exists = existing
+        .map_err(|err| err.into_shell_error(span))?
+        .iter()
+        .any(|name| name == table_name);
+
+    if table_exists {
+        return Ok(());
+    }
+
+    let mut columns = Vec::with_capacity(first_row.len());
+    for (column, value) in first_row {
+        let sql_value = SqlValue::try_from_value(value)?;
+        columns.push(format!("[{}] {}", column, sql_value.column_type()));
+    }
+
+    let create_sql = format!(
+        "CREATE {} [{}] ({})",
+        match kind {
+            TableKind::Table => "TABLE",
+            TableKind::TempTable => "TEMP TABLE",
+        },
+        table_name,
+        columns.join(", ")
+    );
+    conn.execute(&create_sql, [])
+        .map_err(|err| DatabaseError::from(err).into_shell_error(span))?;
+
+    Ok(())
+}
+
+fn record_to_params(
+    record: &Record,
+    columns: &[String],
+    options: &InsertOptions,
+    row_span: Span,
+) -> Result<Vec<SqlValue>, ShellError> {
+    columns
+        .iter()
+        .map(|column| {
+            let param = convert_column(record, column, options)?;
+            match param {
+                Some(value) => Ok(value),
+                None => match options.null_handling {
+                    NullHandling::Error => Err(null_error(column, row_span)),
+                    // `Default` is handled by `insert_row_with_defaults`
+                    _ => Ok(SqlValue::Null),
+                },
+            }
+        })
+        .collect()
+}
+
+/// Insert a single row, omitting `NULL` columns so their defaults apply.
+fn insert_row_with_defaults(
+    tx: &rusqlite::Transaction,
+    table_name: &str,
+    columns: &[String],
+    options: &InsertOptions,
+    row: Value,
+) -> Result<(), ShellError> {
+    let row_span = row.span();
+    let record = row.into_record()?;
+
+    let mut present_columns = Vec::with_capacity(columns.len());
+    let mut params = Vec::with_capacity(columns.len());
+    for column in columns {
+        if let Some(value) = convert_column(&record, column, options)? {
+            present_columns.push(format!("[{column}]"));
+            params.push(value);
+        }
+    }
+
+    let insert_sql = if present_columns.is_empty() {
+        format!("INSERT INTO [{table_name}] DEFAULT VALUES")
+    } else {
+        format!(
+            "INSERT INTO [{}] ({}) VALUES ({})",
+            table_name,
+            present_columns.join(", "),
+            params.iter().map(|_| "?").collect::<Vec<_>>().join(", "),
+        )
+    };
+
+    tx.execute(&insert_sql, rusqlite::params_from_iter(params))
+        .map_err(|err| DatabaseError::from(err).into_shell_error(row_span))?;
+
+    Ok(())
+}
+
+/// Convert one column of a record, applying the insert options.
+///
+/// Returns `None` when the column has no value to insert, i.e. the key is
+/// missing, the value is `null`, or the value maps to `NULL` through
+/// `empty_string_as_null`.
+fn convert_column(
+    record: &Record,
+    column: &str,
+    options: &InsertOptions,
+) -> Result<Option<SqlValue>, ShellError> {
+    let value = match record.get(column) {
+        Some(value) => SqlValue::try_from_value(value)?,
+        None => return Ok(None),
+    };
+
+    Ok(match value {
+        SqlValue::Null => None,
+        SqlValue::Text(text) if text.is_empty() && options.empty_string_as_null => None,
+        value => Some(value),
+    })
+}
+
+fn null_error(column: &str, row_span: Span) -> ShellError {
+    ShellError::GenericError {
+        error: "Null value in insert".into(),
+        msg: format!("column '{column}' has no value in this row"),
+        span: Some(row_span),
+        help: Some("use `--nulls null` to insert SQL NULL or `--nulls default` to use the column default".into()),
+        inner: vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database_next::values::{read, DatabaseStorage};
+    use nu_protocol::record;
+    use std::sync::Mutex;
+
+    fn insert_rows(options: &InsertOptions, rows: Vec<Value>) -> Result<Value, ShellError> {
+        let span = Span::test_data();
+        let conn = DatabaseConnection::open(&DatabaseStorage::Memory)
+            .map_err(|err| err.into_shell_error(span))?;
+        let conn = Mutex::new(conn);
+        let mut guard = conn.lock().expect("fresh mutex");
+
+        let input = PipelineData::Value(Value::test_list(rows), None);
+        insert_pipeline(&mut guard, "data", TableKind::Table, options, input, span)?;
+
+        read::read_table(&guard, "data", span).map_err(|err| err.into_shell_error(span))
+    }
+
+    #[test]
+    fn nulls_are_inserted_as_null() {
+        let rows = vec![Value::test_record(record! {
+            "name" => Value::test_string("ellie"),
+            "age" => Value::test_nothing(),
+        })];
+        let read_back = insert_rows(&InsertOptions::default(), rows.clone()).unwrap();
+        assert_eq!(read_back, Value::test_list(rows));
+    }
+
+    #[test]
+    fn nulls_can_error() {
+        let options = InsertOptions {
+            null_handling: NullHandling::Error,
+            ..Default::default()
+        };
+        let rows = vec![Value::test_record(record! {
+            "name" => Value::test_nothing(),
+        })];
+        assert!(insert_rows(&options, rows).is_err());
+    }
+
+    #[test]
+    fn empty_strings_can_map_to_null() {
+        let options = InsertOptions {
+            empty_string_as_null: true,
+            ..Default::default()
+        };
+        let rows = vec![Value::test_record(record! {
+            "name" => Value::test_string(""),
+        })];
+        let read_back = insert_rows(&options, rows).unwrap();
+        assert_eq!(
+            read_back,
+            Value::test_list(vec![Value::test_record(record! {
+                "name" => Value::test_nothing(),
+            })])
+        );
+    }
+}
@@ -0,0 +1,15 @@
+mod connection;
+mod database;
+pub mod dump;
+pub mod insert;
+mod pool;
+pub mod read;
+pub mod remote;
+mod sql_value;
+mod table;
+
+pub use connection::{DatabaseConnection, DatabaseList, DatabaseStorage};
+pub use pool::{ConnectionPool, DEFAULT_POOL_SIZE};
+pub use database::DatabaseValue;
+pub use sql_value::SqlValue;
+pub use table::DatabaseTableValue;
@@ -0,0 +1,52 @@
+use super::{
+    super::error::DatabaseError,
+    connection::{DatabaseConnection, DatabaseStorage},
+};
+use std::sync::{Arc, Mutex};
+
+/// The pool size used when none is configured.
+pub const DEFAULT_POOL_SIZE: usize = 4;
+
+/// A fixed-size pool of connections to the same read-only storage.
+///
+/// Read-only connections can safely coexist, so parallel pipelines (e.g.
+/// `par-each` querying the same database) don't have to serialize on a single
+/// `Mutex<DatabaseConnection>`.
+#[derive(Debug, Clone)]
+pub struct ConnectionPool {
+    connections: Arc<Vec<Arc<Mutex<DatabaseConnection>>>>,
+}
+
+impl ConnectionPool {
+    /// Open `size` connections to the given storage.
+    pub fn open(storage: &DatabaseStorage, size: usize) -> Result<Self, DatabaseError> {
+        let size = size.max(1);
+        let mut connections = Vec::with_capacity(size);
+        for _ in 0..size {
+            connections.push(Arc::new(Mutex::new(DatabaseConnection::open(storage)?)));
+        }
+
+        Ok(Self {
+            connections: Arc::new(connections),
+        })
+    }
+
+    /// Hand out a connection, preferring one that isn't currently locked.
+    ///
+    /// When all connections are busy this falls back to the first one, so the
+    /// caller blocks on its mutex like with a single shared connection.
+    pub fn acquire(&self) -> Arc<Mutex<DatabaseConnection>> {
+        for connection in self.connections.iter() {
+            if connection.try_lock().is_ok() {
+                return connection.clone();
+            }
+        }
+
+        self.connections[0].clone()
+    }
+
+    /// The number of connections in this pool.
+    pub fn size(&self) -> usize {
+        self.connections.len()
+    }
+}
@@ -0,0 +1,130 @@
+use super::{super::error::DatabaseError, connection::DatabaseConnection, sql_value::SqlValue};
+use nu_protocol::{Record, Span, Value};
+use rusqlite::Statement;
+
+/// How result columns are named when rows are turned into records.
+///
+/// Column order is always kept identical to the SQL result set, only the
+/// record keys differ.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColumnNaming {
+    /// Deduplicate repeated column names (e.g. from joins) by suffixing later
+    /// occurrences with `_1`, `_2`, ...
+    #[default]
+    Deduplicated,
+    /// Keep column names exactly as returned by SQLite.
+    ///
+    /// With repeated names, later columns shadow earlier ones during cell
+    /// path access.
+    Raw,
+}
+
+/// Read a full table into a list of records.
+pub fn read_table(
+    conn: &DatabaseConnection,
+    table_name: &str,
+    span: Span,
+) -> Result<Value, DatabaseError> {
+    let stmt = conn.prepare(&format!("SELECT * FROM [{table_name}]"))?;
+    read_statement(stmt, ColumnNaming::default(), span)
+}
+
+/// Read every table of a database into a record of tables.
+pub fn read_all_tables(conn: &DatabaseConnection, span: Span) -> Result<Value, DatabaseError> {
+    let mut tables = Record::new();
+
+    for table_name in conn.table_names()? {
+        let rows = read_table(conn, &table_name, span)?;
+        tables.push(table_name, rows);
+    }
+
+    Ok(Value::record(tables, span))
+}
+
+/// Run a prepared statement and collect its rows into a list of records.
+pub fn read_statement(
+    mut stmt: Statement,
+    naming: ColumnNaming,
+    span: Span,
+) -> Result<Value, DatabaseError> {
+    let column_names = stmt
+        .column_names()
+        .into_iter()
+        .map(String::from)
+        .collect::<Vec<String>>();
+    let column_names = match naming {
+        ColumnNaming::Deduplicated => deduplicate_column_names(column_names),
+        ColumnNaming::Raw => column_names,
+    };
+
+    let mut rows = stmt.query([])?;
+    let mut row_values = Vec::new();
+
+    while let Some(row) = rows.next()? {
+        let mut record = Record::with_capacity(column_names.len());
+
+        for (index, column_name) in column_names.iter().enumerate() {
+            let value = match SqlValue::from_value_ref(row.get_ref(index)?) {
+                Ok(value) => value.into_value(span),
+                Err(err) => Value::error(err, span),
+            };
+            record.push(column_name.clone(), value);
+        }
+
+        row_values.push(Value::record(record, span));
+    }
+
+    Ok(Value::list(row_values, span))
+}
+
+/// Make repeated column names unique while keeping the result set order.
+///
+/// The first occurrence keeps its name, later ones get `_1`, `_2`, ...
+/// appended, skipping suffixes that would collide with another column.
+fn deduplicate_column_names(column_names: Vec<String>) -> Vec<String> {
+    let mut deduplicated: Vec<String> = Vec::with_capacity(column_names.len());
+
+    for name in &column_names {
+        if !deduplicated.contains(name) {
+            deduplicated.push(name.clone());
+            continue;
+        }
+
+        let mut counter = 1;
+        loop {
+            let candidate = format!("{name}_{counter}");
+            let collides =
+                deduplicated.contains(&candidate) || column_names.contains(&candidate);
+            if !collides {
+                deduplicated.push(candidate);
+                break;
+            }
+            counter += 1;
+        }
+    }
+
+    deduplicated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unique_names_are_untouched() {
+        let names = vec!["a".to_string(), "b".to_string()];
+        assert_eq!(deduplicate_column_names(names.clone()), names);
+    }
+
+    #[test]
+    fn repeated_names_get_suffixes() {
+        let names = vec!["id".to_string(), "id".to_string(), "id".to_string()];
+        assert_eq!(deduplicate_column_names(names), vec!["id", "id_1", "id_2"]);
+    }
+
+    #[test]
+    fn suffixes_skip_existing_columns() {
+        let names = vec!["id".to_string(), "id_1".to_string(), "id".to_string()];
+        assert_eq!(deduplicate_column_names(names), vec!["id", "id_1", "id_2"]);
+    }
+}
@@ -0,0 +1,95 @@
+use super::super::error::DatabaseError;
+use std::{
+    fs::File,
+    io,
+    path::PathBuf,
+};
+
+/// Whether a database path points at a remote http(s) URL.
+pub fn is_remote_url(path: &str) -> bool {
+    path.starts_with("http://") || path.starts_with("https://")
+}
+
+/// Fetch a remote database into the local cache and return its file path.
+///
+/// Downloads are cached in the temp directory, keyed on the URL. When the
+/// server sent an `ETag` on the last download, it is replayed as
+/// `If-None-Match` so an unchanged database is not transferred again.
+/// The returned file is meant to be opened via read-only storage; local
+/// changes would be lost on the next revalidation.
+pub fn fetch_remote_database(url: &str) -> Result<PathBuf, DatabaseError> {
+    let cache_dir = std::env::temp_dir().join("nu-database-cache");
+    std::fs::create_dir_all(&cache_dir)?;
+
+    let key = cache_key(url);
+    let db_path = cache_dir.join(format!("{key}.sqlite3"));
+    let etag_path = cache_dir.join(format!("{key}.etag"));
+
+    let mut request = ureq::agent().get(url);
+    if db_path.exists() {
+        if let Ok(etag) = std::fs::read_to_string(&etag_path) {
+            request = request.set("If-None-Match", etag.trim());
+        }
+    }
+
+    let response = match request.call() {
+        Ok(response) => response,
+        // the cached copy is still current
+        Err(ureq::Error::Status(304, _)) if db_path.exists() => return Ok(db_path),
+        Err(err) => {
+            return Err(DatabaseError::Download {
+                url: url.to_string(),
+                msg: err.to_string(),
+            })
+        }
+    };
+
+    let etag = response.header("etag").map(str::to_string);
+
+    let mut file = File::create(&db_path)?;
+    io::copy(&mut response.into_reader(), &mut file)?;
+
+    match etag {
+        Some(etag) => std::fs::write(&etag_path, etag)?,
+        None => {
+            // stale tags must not validate a future download
+            let _ = std::fs::remove_file(&etag_path);
+        }
+    }
+
+    Ok(db_path)
+}
+
+fn cache_key(url: &str) -> String {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    hasher.update(url.as_bytes());
+    let hash = hasher.finalize();
+
+    // the first half of the hash is plenty for a cache file name
+    hash.iter()
+        .take(16)
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remote_urls_are_detected() {
+        assert!(is_remote_url("https://example.org/data.db"));
+        assert!(is_remote_url("http://example.org/data.db"));
+        assert!(!is_remote_url("data.db"));
+        assert!(!is_remote_url("/tmp/data.db"));
+    }
+
+    #[test]
+    fn cache_keys_are_stable_and_distinct() {
+        let a = cache_key("https://example.org/a.db");
+        assert_eq!(a, cache_key("https://example.org/a.db"));
+        assert_ne!(a, cache_key("https://example.org/b.db"));
+    }
+}
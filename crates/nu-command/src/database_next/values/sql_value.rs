@@ -0,0 +1,184 @@
+use super::super::error::DatabaseError;
+use nu_protocol::{ShellError, Span, Value};
+use rusqlite::{
+    types::{ToSqlOutput, ValueRef},
+    ToSql,
+};
+
+/// A Nu [`Value`] converted into one of SQLite's storage classes.
+///
+/// This is the single place where Nu values and SQL values meet, both on the
+/// way into a statement (via [`try_from_value`](Self::try_from_value) and
+/// [`ToSql`]) and on the way out of a result row (via
+/// [`from_value_ref`](Self::from_value_ref) and [`into_value`](Self::into_value)).
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlValue {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+impl SqlValue {
+    /// Convert a Nu value into its SQL representation.
+    ///
+    /// Values without a sensible SQL representation (lists, records,
+    /// closures, ...) are rejected.
+    pub fn try_from_value(value: &Value) -> Result<Self, ShellError> {
+        Ok(match value {
+            Value::Bool { val, .. } => SqlValue::Integer(*val as i64),
+            Value::Int { val, .. } => SqlValue::Integer(*val),
+            Value::Float { val, .. } => SqlValue::Real(*val),
+            Value::Filesize { val, .. } => SqlValue::Integer(*val),
+            Value::Duration { val, .. } => SqlValue::Integer(*val),
+            Value::Date { val, .. } => SqlValue::Text(val.to_rfc3339()),
+            Value::String { val, .. } => {
+                // don't store ansi escape sequences in the database
+                SqlValue::Text(nu_utils::strip_ansi_unlikely(val).into_owned())
+            }
+            Value::Binary { val, .. } => SqlValue::Blob(val.clone()),
+            Value::Nothing { .. } => SqlValue::Null,
+            val => {
+                return Err(ShellError::OnlySupportsThisInputType {
+                    exp_input_type:
+                        "bool, int, float, filesize, duration, date, string, nothing, binary"
+                            .into(),
+                    wrong_type: val.get_type().to_string(),
+                    dst_span: Span::unknown(),
+                    src_span: val.span(),
+                })
+            }
+        })
+    }
+
+    /// Convert an integer, checking that it fits SQLite's `i64` range.
+    ///
+    /// SQLite and Nu ints are both `i64`, but other backends hand out wider
+    /// integers (e.g. `u64` rowids or `i128` aggregation results). This
+    /// rejects values outside the shared range instead of wrapping around.
+    pub fn try_from_i128(value: i128) -> Result<Self, DatabaseError> {
+        match i64::try_from(value) {
+            Ok(value) => Ok(SqlValue::Integer(value)),
+            Err(_) => Err(DatabaseError::IntOutOfRange {
+                value: value.to_string(),
+            }),
+        }
+    }
+
+    /// Convert an unsigned integer, checking that it fits SQLite's `i64`
+    /// range.
+    ///
+    /// See [`try_from_i128`](Self::try_from_i128).
+    pub fn try_from_u64(value: u64) -> Result<Self, DatabaseError> {
+        match i64::try_from(value) {
+            Ok(value) => Ok(SqlValue::Integer(value)),
+            Err(_) => Err(DatabaseError::IntOutOfRange {
+                value: value.to_string(),
+            }),
+        }
+    }
+
+    /// Convert a SQL value from a result row back into a Nu value.
+    pub fn from_value_ref(value: ValueRef) -> Result<Self, ShellError> {
+        Ok(match value {
+            ValueRef::Null => SqlValue::Null,
+            ValueRef::Integer(i) => SqlValue::Integer(i),
+            ValueRef::Real(f) => SqlValue::Real(f),
+            ValueRef::Text(buf) => match std::str::from_utf8(buf) {
+                Ok(text) => SqlValue::Text(text.to_string()),
+                Err(_) => {
+                    return Err(ShellError::NonUtf8 {
+                        span: Span::unknown(),
+                    })
+                }
+            },
+            ValueRef::Blob(buf) => SqlValue::Blob(buf.to_vec()),
+        })
+    }
+
+    /// Turn this SQL value into a Nu value.
+    pub fn into_value(self, span: Span) -> Value {
+        match self {
+            SqlValue::Null => Value::nothing(span),
+            SqlValue::Integer(i) => Value::int(i, span),
+            SqlValue::Real(f) => Value::float(f, span),
+            SqlValue::Text(text) => Value::string(text, span),
+            SqlValue::Blob(buf) => Value::binary(buf, span),
+        }
+    }
+
+    /// The SQLite column type used when creating a table for this value.
+    pub fn column_type(&self) -> &'static str {
+        match self {
+            // we have no type information for NULL, TEXT is the least
+            // surprising fallback for a schema
+            SqlValue::Null => "TEXT",
+            SqlValue::Integer(_) => "INTEGER",
+            SqlValue::Real(_) => "REAL",
+            SqlValue::Text(_) => "TEXT",
+            SqlValue::Blob(_) => "BLOB",
+        }
+    }
+}
+
+impl ToSql for SqlValue {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(match self {
+            SqlValue::Null => ToSqlOutput::Borrowed(ValueRef::Null),
+            SqlValue::Integer(i) => ToSqlOutput::Borrowed(ValueRef::Integer(*i)),
+            SqlValue::Real(f) => ToSqlOutput::Borrowed(ValueRef::Real(*f)),
+            SqlValue::Text(text) => ToSqlOutput::Borrowed(ValueRef::Text(text.as_bytes())),
+            SqlValue::Blob(buf) => ToSqlOutput::Borrowed(ValueRef::Blob(buf)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nothing_becomes_null() {
+        let value = SqlValue::try_from_value(&Value::test_nothing()).unwrap();
+        assert_eq!(value, SqlValue::Null);
+    }
+
+    #[test]
+    fn string_round_trips() {
+        let value = SqlValue::try_from_value(&Value::test_string("hello")).unwrap();
+        assert_eq!(value, SqlValue::Text("hello".into()));
+        assert_eq!(
+            value.into_value(Span::test_data()),
+            Value::test_string("hello")
+        );
+    }
+
+    #[test]
+    fn in_range_integers_convert() {
+        assert_eq!(
+            SqlValue::try_from_u64(42).unwrap(),
+            SqlValue::Integer(42)
+        );
+        assert_eq!(
+            SqlValue::try_from_i128(-42).unwrap(),
+            SqlValue::Integer(-42)
+        );
+    }
+
+    #[test]
+    fn out_of_range_integers_are_rejected() {
+        assert!(SqlValue::try_from_u64(u64::MAX).is_err());
+        assert!(SqlValue::try_from_i128(i128::from(i64::MAX) + 1).is_err());
+        assert!(SqlValue::try_from_i128(i128::from(i64::MIN) - 1).is_err());
+    }
+
+    #[test]
+    fn closure_is_rejected() {
+        let value = Value::test_closure(nu_protocol::engine::Closure {
+            block_id: 0,
+            captures: vec![],
+        });
+        assert!(SqlValue::try_from_value(&value).is_err());
+    }
+}
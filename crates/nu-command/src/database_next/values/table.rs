@@ -0,0 +1,95 @@
+use super::{database::DatabaseValue, read};
+use nu_protocol::{CustomValue, ShellError, Span, Value};
+use serde::{Deserialize, Serialize};
+
+/// A single table of a database as a value in the pipeline.
+///
+/// This keeps a handle on the database it came from, so the table contents
+/// are only read once the value is materialized.
+/// Temporary tables are connection-scoped: a table value whose database
+/// crossed a serialization boundary reconnects and won't see them anymore.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseTableValue {
+    database: DatabaseValue,
+    table_name: String,
+}
+
+impl DatabaseTableValue {
+    /// Create a handle for `table_name` on `database`.
+    pub fn new(database: DatabaseValue, table_name: impl Into<String>) -> Self {
+        Self {
+            database,
+            table_name: table_name.into(),
+        }
+    }
+
+    /// The database this table lives in.
+    pub fn database(&self) -> &DatabaseValue {
+        &self.database
+    }
+
+    /// The name of the table.
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
+    /// Extract a table handle from a value, failing for any other custom
+    /// value.
+    pub fn try_from_value(value: Value) -> Result<Self, ShellError> {
+        let span = value.span();
+        match value {
+            Value::Custom { val, .. } => match val.as_any().downcast_ref::<Self>() {
+                Some(table) => Ok(table.clone()),
+                None => Err(ShellError::CantConvert {
+                    to_type: "database table".into(),
+                    from_type: "non-table".into(),
+                    span,
+                    help: None,
+                }),
+            },
+            x => Err(ShellError::CantConvert {
+                to_type: "database table".into(),
+                from_type: x.get_type().to_string(),
+                span: x.span(),
+                help: None,
+            }),
+        }
+    }
+
+    /// Wrap this table handle into a [`Value`].
+    pub fn into_value(self, span: Span) -> Value {
+        Value::custom(Box::new(self), span)
+    }
+}
+
+impl CustomValue for DatabaseTableValue {
+    fn clone_value(&self, span: Span) -> Value {
+        self.clone().into_value(span)
+    }
+
+    fn type_name(&self) -> String {
+        self.typetag_name().to_string()
+    }
+
+    fn to_base_value(&self, span: Span) -> Result<Value, ShellError> {
+        self.database
+            .with_connection(|conn| read::read_table(conn, &self.table_name, span))
+            .map_err(|err| err.into_shell_error(span))
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_mut_any(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+
+    fn typetag_name(&self) -> &'static str {
+        "database-table"
+    }
+
+    fn typetag_deserialize(&self) {
+        unimplemented!("typetag_deserialize")
+    }
+}
@@ -1,4 +1,4 @@
-use chrono::Local;
+use chrono::{DateTime, FixedOffset, Local};
 use nu_engine::command_prelude::*;
 
 #[derive(Clone)]
@@ -25,12 +25,15 @@ impl Command for SubCommand {
 
     fn run(
         &self,
-        _engine_state: &EngineState,
-        _stack: &mut Stack,
+        engine_state: &EngineState,
+        stack: &mut Stack,
         call: &Call,
         _input: PipelineData,
     ) -> Result<PipelineData, ShellError> {
         let head = call.head;
+        if let Some(dt) = fixed_now(engine_state, stack, head)? {
+            return Ok(Value::date(dt, head).into_pipeline_data());
+        }
         let dt = Local::now();
         Ok(Value::date(dt.with_timezone(dt.offset()), head).into_pipeline_data())
     }
@@ -60,3 +63,35 @@ impl Command for SubCommand {
         ]
     }
 }
+
+/// The `$env.NU_TEST_FIXED_NOW` override, if set.
+///
+/// Only honored in debug builds, so tests can freeze the clock (see
+/// `nu_utils::deterministic`); release builds always use the real time.
+#[cfg(debug_assertions)]
+fn fixed_now(
+    engine_state: &EngineState,
+    stack: &Stack,
+    head: Span,
+) -> Result<Option<DateTime<FixedOffset>>, ShellError> {
+    let Some(fixed) = stack.get_env_var(engine_state, nu_utils::deterministic::FIXED_NOW_ENV_VAR)
+    else {
+        return Ok(None);
+    };
+    let fixed = fixed.coerce_string()?;
+    DateTime::parse_from_rfc3339(&fixed)
+        .map(Some)
+        .map_err(|err| ShellError::DatetimeParseError {
+            msg: format!("invalid RFC 3339 timestamp in $env.NU_TEST_FIXED_NOW: {err}"),
+            span: head,
+        })
+}
+
+#[cfg(not(debug_assertions))]
+fn fixed_now(
+    _engine_state: &EngineState,
+    _stack: &Stack,
+    _head: Span,
+) -> Result<Option<DateTime<FixedOffset>>, ShellError> {
+    Ok(None)
+}
@@ -21,6 +21,13 @@ pub fn add_shell_command_context(mut engine_state: EngineState) -> EngineState {
         #[cfg(feature = "sqlite")]
         add_database_decls(&mut working_set);
 
+        // With the experimental option enabled, the next-generation database
+        // commands shadow the classic ones they replace
+        #[cfg(feature = "sqlite")]
+        if nu_experimental::DATABASE_CMD_NEXT.get() {
+            add_database_next_decls(&mut working_set);
+        }
+
         // Charts
         bind_command! {
             Histogram
@@ -1,6 +1,6 @@
 use nu_engine::command_prelude::*;
 
-use rand::prelude::{thread_rng, Rng};
+use rand::prelude::Rng;
 
 #[derive(Clone)]
 pub struct SubCommand;
@@ -77,7 +77,7 @@ fn bool(
         }
     }
 
-    let mut rng = thread_rng();
+    let mut rng = super::random_rng(engine_state, stack);
     let bool_result: bool = rng.gen_bool(probability);
 
     Ok(PipelineData::Value(Value::bool(bool_result, span), None))
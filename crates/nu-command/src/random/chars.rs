@@ -1,9 +1,6 @@
 use nu_engine::command_prelude::*;
 
-use rand::{
-    distributions::{Alphanumeric, Distribution},
-    thread_rng,
-};
+use rand::distributions::{Alphanumeric, Distribution};
 
 const DEFAULT_CHARS_LENGTH: usize = 25;
 
@@ -66,7 +63,7 @@ fn chars(
     let length: Option<usize> = call.get_flag(engine_state, stack, "length")?;
 
     let chars_length = length.unwrap_or(DEFAULT_CHARS_LENGTH);
-    let mut rng = thread_rng();
+    let mut rng = super::random_rng(engine_state, stack);
 
     let random_string = Alphanumeric
         .sample_iter(&mut rng)
@@ -1,6 +1,6 @@
 use nu_engine::command_prelude::*;
 use nu_protocol::ListStream;
-use rand::prelude::{thread_rng, Rng};
+use rand::prelude::Rng;
 
 #[derive(Clone)]
 pub struct SubCommand;
@@ -73,10 +73,9 @@ fn dice(
     let dice: usize = call.get_flag(engine_state, stack, "dice")?.unwrap_or(1);
     let sides: usize = call.get_flag(engine_state, stack, "sides")?.unwrap_or(6);
 
-    let iter = (0..dice).map(move |_| {
-        let mut thread_rng = thread_rng();
-        Value::int(thread_rng.gen_range(1..sides + 1) as i64, span)
-    });
+    let mut thread_rng = super::random_rng(engine_state, stack);
+    let iter = (0..dice)
+        .map(move |_| Value::int(thread_rng.gen_range(1..sides + 1) as i64, span));
 
     Ok(ListStream::new(iter, span, engine_state.ctrlc.clone()).into())
 }
@@ -1,6 +1,6 @@
 use nu_engine::command_prelude::*;
 use nu_protocol::{FloatRange, Range};
-use rand::prelude::{thread_rng, Rng};
+use rand::prelude::Rng;
 use std::ops::Bound;
 
 #[derive(Clone)]
@@ -71,7 +71,7 @@ fn float(
     let span = call.head;
     let range: Option<Spanned<Range>> = call.opt(engine_state, stack, 0)?;
 
-    let mut thread_rng = thread_rng();
+    let mut thread_rng = super::random_rng(engine_state, stack);
 
     match range {
         Some(range) => {
@@ -6,6 +6,31 @@ mod int;
 mod random_;
 mod uuid;
 
+use nu_protocol::engine::{EngineState, Stack};
+use rand::{rngs::StdRng, SeedableRng};
+
+/// The RNG a `random` command draws from.
+///
+/// In debug builds a numeric `$env.NU_TEST_RNG_SEED` yields a
+/// deterministically seeded RNG, so tests can pin random output (every
+/// invocation re-seeds, so a fixed seed gives a fixed result). Without the
+/// override — and always in release builds — the RNG is seeded from OS
+/// entropy. `random uuid` is not covered; UUIDv4 generation draws from the
+/// OS directly.
+fn random_rng(engine_state: &EngineState, stack: &Stack) -> StdRng {
+    #[cfg(debug_assertions)]
+    if let Some(seed) = stack
+        .get_env_var(engine_state, nu_utils::deterministic::RNG_SEED_ENV_VAR)
+        .and_then(|value| value.coerce_string().ok())
+        .and_then(|seed| seed.parse::<u64>().ok())
+    {
+        return StdRng::seed_from_u64(seed);
+    }
+    #[cfg(not(debug_assertions))]
+    let _ = (engine_state, stack);
+    StdRng::from_entropy()
+}
+
 pub use self::bool::SubCommand as RandomBool;
 pub use self::chars::SubCommand as RandomChars;
 pub use self::dice::SubCommand as RandomDice;
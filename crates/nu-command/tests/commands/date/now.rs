@@ -0,0 +1,19 @@
+use nu_test_support::nu;
+
+#[cfg(debug_assertions)]
+#[test]
+fn fixed_now_freezes_the_clock() {
+    let actual = nu!(
+        r#"$env.NU_TEST_FIXED_NOW = '2020-01-02T03:04:05+06:00'; date now | format date "%Y-%m-%d %H:%M:%S %z""#
+    );
+
+    assert_eq!(actual.out, "2020-01-02 03:04:05 +0600");
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn fixed_now_rejects_invalid_timestamps() {
+    let actual = nu!("$env.NU_TEST_FIXED_NOW = 'not a timestamp'; date now");
+
+    assert!(actual.err.contains("NU_TEST_FIXED_NOW"));
+}
@@ -20,3 +20,11 @@ fn generates_0() {
 
     assert!(actual.out.contains('0'));
 }
+
+#[cfg(debug_assertions)]
+#[test]
+fn seeded_generation_is_reproducible() {
+    let actual = nu!("$env.NU_TEST_RNG_SEED = 7; (random int) == (random int)");
+
+    assert_eq!(actual.out, "true");
+}
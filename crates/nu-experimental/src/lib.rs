@@ -0,0 +1,47 @@
+//! Experimental options for Nushell.
+//!
+//! This crate holds the definitions of all experimental options as well as the
+//! machinery to toggle them at startup.
+//! Experimental options allow us to ship bigger changes behind a switch so
+//! users can opt in (or out) before the behavior becomes the default.
+//!
+//! Options are represented as statics (see [`ALL`] for the full list) and are
+//! expected to be set once early in `main`, before any threads are spawned.
+//! The rest of the codebase reads them through cheap atomic loads via
+//! [`ExperimentalOption::get`].
+
+mod metadata;
+mod option;
+mod options;
+mod overrides;
+mod parse;
+mod snapshot;
+mod storage;
+#[cfg(feature = "test-support")]
+pub mod test_support;
+mod value;
+
+pub use metadata::{as_record_data, metadata, OptionMetadata};
+pub use option::{
+    freeze, is_frozen, revision, Category, DeprecationNotice, ExperimentalOption,
+    ExperimentalOptionMarker, SetError, Status, ValueSource,
+};
+pub use overrides::with_overrides;
+pub use snapshot::{apply_snapshot, snapshot};
+pub use options::*;
+
+// Re-exported so downstream crates can register options in `ALL` without
+// depending on linkme themselves.
+pub use linkme;
+pub use parse::{
+    individual_env_var, parse_config, parse_env, parse_individual_env, parse_list, parse_record,
+    ExpiredOption, InvalidValue, ParseReport, ParseWarning, RenamedOption, UnknownIdentifier,
+    ENV_VAR,
+};
+pub use value::ExperimentalValue;
+
+#[cfg(test)]
+pub(crate) mod test_lock {
+    // Tests mutate the shared option statics, so they serialize on this lock.
+    pub static LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+}
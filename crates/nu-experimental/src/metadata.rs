@@ -0,0 +1,87 @@
+use crate::{Category, Status, ValueSource, ALL};
+use serde::{Deserialize, Serialize};
+
+/// Machine-readable description of one experimental option.
+///
+/// This is what `debug experimental-options --json` and external tooling
+/// consume, so they don't have to scrape `Debug` output. Produced by
+/// [`metadata`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OptionMetadata {
+    /// The identifier users refer to the option by.
+    pub identifier: String,
+    /// A short description of what the option changes.
+    pub description: String,
+    /// The stabilization status of the option.
+    pub status: Status,
+    /// The broad area of Nushell the option belongs to.
+    pub category: Category,
+    /// The version the option was introduced in, if recorded.
+    pub since: Option<String>,
+    /// The tracking issue for the option, if any.
+    pub issue_url: Option<String>,
+    /// The effective value, with defaults resolved.
+    pub value: bool,
+    /// The payload of typed options, e.g. from `some-option=compact`.
+    pub payload: Option<String>,
+    /// Where the current value came from.
+    pub source: ValueSource,
+}
+
+/// Describe every known experimental option in a machine-readable way.
+pub fn metadata() -> Vec<OptionMetadata> {
+    ALL.iter()
+        .map(|option| OptionMetadata {
+            identifier: option.identifier().to_string(),
+            description: option.description().to_string(),
+            status: option.status(),
+            category: option.category(),
+            since: option.since().map(String::from),
+            issue_url: option.issue_url().map(String::from),
+            value: option.get(),
+            payload: option
+                .value()
+                .and_then(|value| value.as_str().map(String::from)),
+            source: option.source(),
+        })
+        .collect()
+}
+
+/// The option table as plain data for a shell-facing record.
+///
+/// Returns `(identifier, value, status, description)` per option, with the
+/// status in its kebab-case form. The engine embeds this into the `$nu`
+/// constant at startup, so the shape is deliberately free of any engine
+/// types; use [`metadata`] when the full detail is wanted instead.
+pub fn as_record_data() -> Vec<(&'static str, bool, &'static str, &'static str)> {
+    ALL.iter()
+        .map(|option| {
+            (
+                option.identifier(),
+                option.get(),
+                option.status().as_str(),
+                option.description(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_lock::LOCK;
+
+    #[test]
+    fn metadata_covers_all_options() {
+        let _guard = LOCK.lock().unwrap();
+        let metadata = metadata();
+        assert_eq!(metadata.len(), ALL.len());
+
+        let database = metadata
+            .iter()
+            .find(|option| option.identifier == "database-cmd-next")
+            .expect("database-cmd-next is known");
+        assert_eq!(database.status, Status::OptIn);
+        assert_eq!(database.source, ValueSource::Default);
+    }
+}
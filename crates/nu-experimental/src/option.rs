@@ -0,0 +1,712 @@
+use crate::{
+    storage::{CounterCell, FlagCell, StateCell},
+    ExperimentalValue,
+};
+use serde::{Deserialize, Serialize};
+use std::{fmt, sync::RwLock};
+
+// Internal representation of an option's state.
+// `UNSET` falls back to the default implied by the option's status.
+const UNSET: u8 = 0;
+const ENABLED: u8 = 1;
+const DISABLED: u8 = 2;
+
+// Whether the option state has been frozen via `freeze`.
+static SEALED: FlagCell = FlagCell::new(false);
+
+// Bumped on every state change so caches can detect stale derived state.
+static REVISION: CounterCell = CounterCell::new(0);
+
+/// A counter that is bumped whenever any experimental option changes.
+///
+/// Code deriving state from multiple options can remember the revision it
+/// computed for and recompute when this moves, e.g. across
+/// [`with_overrides`](crate::with_overrides) scopes.
+pub fn revision() -> u64 {
+    REVISION.load()
+}
+
+/// Freeze the state of all experimental options.
+///
+/// Call this once startup is done: any later [`set`](ExperimentalOption::set)
+/// or [`unset`](ExperimentalOption::unset) panics. This turns the "options are
+/// only set during init" contract from a doc comment into something the
+/// runtime enforces.
+pub fn freeze() {
+    SEALED.store(true);
+}
+
+/// Whether [`freeze`] has been called.
+pub fn is_frozen() -> bool {
+    SEALED.load()
+}
+
+#[cfg(test)]
+pub(crate) fn thaw() {
+    SEALED.store(false);
+}
+
+/// A single experimental option.
+///
+/// Values of this type are expected to live as statics in this crate, one per
+/// option (see [`ALL`](crate::ALL)).
+/// Reading the current value via [`get`](Self::get) is a cheap atomic load, so
+/// call sites don't need to cache it. Payloads of typed options go through a
+/// lock, so call sites that need them on a hot path should read them once.
+pub struct ExperimentalOption {
+    marker: &'static (dyn ExperimentalOptionMarker + Send + Sync),
+    state: StateCell,
+    source: StateCell,
+    payload: RwLock<Option<String>>,
+    callbacks: RwLock<Vec<fn(bool)>>,
+    // Cached result of the marker's default policy, see `default_value`.
+    policy_cache: StateCell,
+}
+
+impl ExperimentalOption {
+    /// Construct a new experimental option from its marker.
+    ///
+    /// This is meant for defining option statics, either in
+    /// [`options`](crate::options) or in downstream crates that register their
+    /// options in [`ALL`](crate::ALL).
+    pub const fn new(marker: &'static (dyn ExperimentalOptionMarker + Send + Sync)) -> Self {
+        Self {
+            marker,
+            state: StateCell::new(UNSET),
+            source: StateCell::new(ValueSource::Default as u8),
+            payload: RwLock::new(None),
+            callbacks: RwLock::new(Vec::new()),
+            policy_cache: StateCell::new(UNSET),
+        }
+    }
+
+    /// The identifier users refer to this option by, e.g. in
+    /// `$env.NU_EXPERIMENTAL_OPTIONS`.
+    pub fn identifier(&self) -> &'static str {
+        self.marker.identifier()
+    }
+
+    /// A short description of what the option changes, shown in help output.
+    pub fn description(&self) -> &'static str {
+        self.marker.description()
+    }
+
+    /// The stabilization status of this option.
+    pub fn status(&self) -> Status {
+        self.marker.status()
+    }
+
+    /// The broad area of Nushell this option belongs to.
+    pub fn category(&self) -> Category {
+        self.marker.category()
+    }
+
+    /// The version this option was introduced in, if recorded.
+    pub fn since(&self) -> Option<&'static str> {
+        self.marker.since()
+    }
+
+    /// The tracking issue for this option, if any.
+    pub fn issue_url(&self) -> Option<&'static str> {
+        self.marker.issue_url()
+    }
+
+    /// Former identifiers this option still answers to.
+    pub fn aliases(&self) -> &'static [&'static str] {
+        self.marker.aliases()
+    }
+
+    /// The version this option should be stabilized or removed by, if set.
+    pub fn expires(&self) -> Option<&'static str> {
+        self.marker.expires()
+    }
+
+    /// Whether this option outlived its [`expires`](Self::expires) target.
+    pub fn is_expired(&self) -> bool {
+        match (
+            self.expires().and_then(version_triple),
+            version_triple(env!("CARGO_PKG_VERSION")),
+        ) {
+            (Some(expires), Some(current)) => current >= expires,
+            _ => false,
+        }
+    }
+
+    /// The version this option is planned to be removed in, if known.
+    pub fn removal_version(&self) -> Option<&'static str> {
+        self.marker.removal_version()
+    }
+
+    /// What users should do instead once this option is gone, if anything.
+    pub fn migration_hint(&self) -> Option<&'static str> {
+        self.marker.migration_hint()
+    }
+
+    /// A renderable deprecation warning, if this option is deprecated.
+    ///
+    /// The CLI layer emits this whenever a deprecated option is explicitly
+    /// set, so all frontends warn consistently.
+    pub fn deprecation_notice(&self) -> Option<DeprecationNotice> {
+        match self.status() {
+            Status::DeprecatedDiscard => Some(DeprecationNotice {
+                identifier: self.identifier(),
+                removal_version: self.removal_version(),
+                migration_hint: self.migration_hint(),
+            }),
+            Status::OptIn | Status::OptOut => None,
+        }
+    }
+
+    /// Whether the option is currently active.
+    ///
+    /// Unset options fall back to the default implied by their
+    /// [`Status`].
+    pub fn get(&self) -> bool {
+        #[cfg(feature = "test-support")]
+        if let Some(value) = crate::test_support::local_value(self.identifier()) {
+            return value.as_bool();
+        }
+
+        match self.state.load() {
+            ENABLED => true,
+            DISABLED => false,
+            _ => self.default_value(),
+        }
+    }
+
+    /// The default value of an unset option.
+    ///
+    /// A default policy on the marker wins over the status default; it is
+    /// evaluated lazily on the first read and cached, so policies may do
+    /// non-trivial work like probing the environment.
+    fn default_value(&self) -> bool {
+        if let Some(policy) = self.marker.default_policy() {
+            return match self.policy_cache.load() {
+                ENABLED => true,
+                DISABLED => false,
+                _ => {
+                    let value = policy();
+                    let state = if value { ENABLED } else { DISABLED };
+                    self.policy_cache.store(state);
+                    value
+                }
+            };
+        }
+
+        match self.status() {
+            Status::OptIn => false,
+            Status::OptOut => true,
+            Status::DeprecatedDiscard => false,
+        }
+    }
+
+    /// The currently set value of this option, or `None` if it is unset.
+    ///
+    /// Unlike [`get`](Self::get) this doesn't resolve the status default, and
+    /// it exposes the payload of typed options like `table-mode=compact`.
+    pub fn value(&self) -> Option<ExperimentalValue> {
+        #[cfg(feature = "test-support")]
+        if let Some(value) = crate::test_support::local_value(self.identifier()) {
+            return Some(value);
+        }
+
+        match self.state.load() {
+            ENABLED => Some(
+                match self
+                    .payload
+                    .read()
+                    .expect("no panics while holding the payload lock")
+                    .clone()
+                {
+                    Some(payload) => ExperimentalValue::String(payload),
+                    None => ExperimentalValue::Bool(true),
+                },
+            ),
+            DISABLED => Some(ExperimentalValue::Bool(false)),
+            _ => None,
+        }
+    }
+
+    /// Where the current value of this option came from.
+    ///
+    /// Unset options report [`ValueSource::Default`].
+    pub fn source(&self) -> ValueSource {
+        match self.source.load() {
+            x if x == ValueSource::Config as u8 => ValueSource::Config,
+            x if x == ValueSource::Env as u8 => ValueSource::Env,
+            x if x == ValueSource::Cli as u8 => ValueSource::Cli,
+            x if x == ValueSource::Api as u8 => ValueSource::Api,
+            _ => ValueSource::Default,
+        }
+    }
+
+    /// Set the option, refusing changes that shouldn't happen.
+    ///
+    /// Unlike [`set`](Self::set) this doesn't panic: it reports frozen state
+    /// (see [`freeze`]) and deprecated options as errors and records the given
+    /// [`ValueSource`]. Embedders should prefer this over [`set`](Self::set).
+    pub fn try_set(
+        &self,
+        value: impl Into<ExperimentalValue>,
+        source: ValueSource,
+    ) -> Result<(), SetError> {
+        if is_frozen() {
+            return Err(SetError::Sealed);
+        }
+        if let Some(notice) = self.deprecation_notice() {
+            return Err(SetError::Deprecated(notice));
+        }
+
+        self.set_value_from(value.into(), source);
+        Ok(())
+    }
+
+    /// Set the option explicitly.
+    ///
+    /// This should only happen during startup, before the engine state is
+    /// built, as call sites are allowed to read the value once and act on it.
+    pub fn set(&self, value: bool) {
+        self.set_value(ExperimentalValue::Bool(value));
+    }
+
+    /// Set the option to a value, possibly carrying a payload.
+    ///
+    /// The same startup-only expectations as for [`set`](Self::set) apply.
+    pub fn set_value(&self, value: ExperimentalValue) {
+        self.set_value_from(value, ValueSource::Api);
+    }
+
+    /// Set the option to a value, recording where the value came from.
+    pub(crate) fn set_value_from(&self, value: ExperimentalValue, source: ValueSource) {
+        assert!(
+            !is_frozen(),
+            "experimental options are frozen, they may only be set during startup"
+        );
+
+        let (state, payload) = match value {
+            ExperimentalValue::Bool(true) => (ENABLED, None),
+            ExperimentalValue::Bool(false) => (DISABLED, None),
+            ExperimentalValue::String(payload) => (ENABLED, Some(payload)),
+        };
+
+        *self
+            .payload
+            .write()
+            .expect("no panics while holding the payload lock") = payload;
+        self.state.store(state);
+        self.source.store(source as u8);
+        REVISION.increment();
+        self.notify();
+    }
+
+    /// Reset the option back to its default.
+    pub fn unset(&self) {
+        assert!(
+            !is_frozen(),
+            "experimental options are frozen, they may only be set during startup"
+        );
+
+        self.state.store(UNSET);
+        self.source.store(ValueSource::Default as u8);
+        *self
+            .payload
+            .write()
+            .expect("no panics while holding the payload lock") = None;
+        REVISION.increment();
+        self.notify();
+    }
+
+    /// Register a callback that runs whenever this option's value changes.
+    ///
+    /// The callback receives the new effective value as [`get`](Self::get)
+    /// would report it. This lets subsystems cache derived state and get
+    /// invalidated if an embedder toggles options during initialization,
+    /// instead of polling [`get`](Self::get) on hot paths.
+    pub fn on_change(&self, callback: fn(bool)) {
+        self.callbacks
+            .write()
+            .expect("no panics while holding the callbacks lock")
+            .push(callback);
+    }
+
+    fn notify(&self) {
+        let value = self.get();
+        for callback in self
+            .callbacks
+            .read()
+            .expect("no panics while holding the callbacks lock")
+            .iter()
+        {
+            callback(value);
+        }
+    }
+}
+
+impl fmt::Debug for ExperimentalOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExperimentalOption")
+            .field("identifier", &self.identifier())
+            .field("status", &self.status())
+            .field("value", &self.get())
+            .field("source", &self.source())
+            .finish()
+    }
+}
+
+/// The stabilization status of an [`ExperimentalOption`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Status {
+    /// The option is disabled by default and users need to enable it
+    /// explicitly.
+    OptIn,
+    /// The option is enabled by default, usually shortly before the behavior
+    /// becomes the only one, and users may still switch back.
+    OptOut,
+    /// The option is deprecated and its behavior will be discarded.
+    ///
+    /// Setting it still works but triggers a [`DeprecationNotice`] so users
+    /// can migrate before the option disappears.
+    DeprecatedDiscard,
+}
+
+impl Status {
+    /// The kebab-case name of the status, matching its serde representation.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Status::OptIn => "opt-in",
+            Status::OptOut => "opt-out",
+            Status::DeprecatedDiscard => "deprecated-discard",
+        }
+    }
+}
+
+/// The broad area of Nushell an [`ExperimentalOption`] belongs to.
+///
+/// `version` and `debug experimental-options` group related flags by this as
+/// the list grows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Category {
+    /// Options changing how source code is parsed.
+    Parser,
+    /// Options changing the behavior of built-in commands.
+    Commands,
+    /// Options changing how output is rendered.
+    Ui,
+    /// Everything that doesn't fit the other categories.
+    Other,
+}
+
+/// Parse a `major.minor.patch` version into a comparable triple.
+///
+/// Pre-release or build suffixes aren't needed for the crate's own versions,
+/// so anything that isn't three plain numbers is `None`.
+fn version_triple(version: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    let patch = parts.next()?.parse().ok()?;
+    parts.next().is_none().then_some((major, minor, patch))
+}
+
+/// Why a [`try_set`](ExperimentalOption::try_set) call was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetError {
+    /// The option state was frozen via [`freeze`].
+    Sealed,
+    /// The option is deprecated and shouldn't be newly set.
+    Deprecated(DeprecationNotice),
+}
+
+impl fmt::Display for SetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SetError::Sealed => {
+                write!(f, "experimental options are frozen and can no longer change")
+            }
+            SetError::Deprecated(notice) => notice.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for SetError {}
+
+/// A renderable warning about a deprecated experimental option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeprecationNotice {
+    /// The identifier of the deprecated option.
+    pub identifier: &'static str,
+    /// The version the option is planned to be removed in, if known.
+    pub removal_version: Option<&'static str>,
+    /// What users should do instead, if anything.
+    pub migration_hint: Option<&'static str>,
+}
+
+impl fmt::Display for DeprecationNotice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "experimental option {:?} is deprecated", self.identifier)?;
+        if let Some(version) = self.removal_version {
+            write!(f, " and will be removed in {version}")?;
+        }
+        if let Some(hint) = self.migration_hint {
+            write!(f, ": {hint}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Where the current value of an [`ExperimentalOption`] came from.
+///
+/// Later sources in this list take precedence over earlier ones, see
+/// [`parse_config`](crate::parse_config).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[repr(u8)]
+pub enum ValueSource {
+    /// The option is unset and falls back to its status default.
+    Default,
+    /// The option was set from the `[experimental]` config section.
+    Config,
+    /// The option was set via the [`ENV_VAR`](crate::ENV_VAR) environment
+    /// variable.
+    Env,
+    /// The option was set via the CLI flag.
+    Cli,
+    /// The option was set through the Rust API, e.g. by an embedder.
+    Api,
+}
+
+/// Compile-time data of an experimental option.
+///
+/// Implementors of this trait are zero-sized marker types, one per
+/// experimental option, see [`options`](crate::options) for examples.
+pub trait ExperimentalOptionMarker {
+    fn identifier(&self) -> &'static str;
+    fn description(&self) -> &'static str;
+    fn status(&self) -> Status;
+
+    /// The broad area of Nushell this option belongs to.
+    ///
+    /// This would be an associated const if the markers weren't used as trait
+    /// objects.
+    fn category(&self) -> Category {
+        Category::Other
+    }
+
+    /// A policy deciding the default value of an unset option.
+    ///
+    /// This allows staged rollouts, e.g. enabling by default only on certain
+    /// platforms or when a companion environment variable exists, without
+    /// scattering `cfg!` checks through option consumers. The policy is
+    /// evaluated lazily on the first read and cached for the process.
+    fn default_policy(&self) -> Option<fn() -> bool> {
+        None
+    }
+
+    /// The version this option was introduced in.
+    fn since(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// The tracking issue where feedback on this option is collected.
+    fn issue_url(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Former identifiers this option still answers to.
+    ///
+    /// Renamed options keep their old identifier here for a release cycle;
+    /// the parser accepts it and emits a rename warning.
+    fn aliases(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// The version this option should be stabilized or removed by.
+    ///
+    /// Once the crate version reaches this, parsing warns whenever the option
+    /// is set, and a unit test fails, so the flag set doesn't rot.
+    fn expires(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// The version this option is planned to be removed in.
+    ///
+    /// Only meaningful for [`Status::DeprecatedDiscard`] options.
+    fn removal_version(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// What users should do instead once this option is gone.
+    ///
+    /// Only meaningful for [`Status::DeprecatedDiscard`] options.
+    fn migration_hint(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_lock::LOCK;
+    use std::sync::atomic::{AtomicU8, Ordering};
+
+    struct DeprecatedMarker;
+
+    impl ExperimentalOptionMarker for DeprecatedMarker {
+        fn identifier(&self) -> &'static str {
+            "deprecated-test"
+        }
+
+        fn description(&self) -> &'static str {
+            "A deprecated option, only used in tests."
+        }
+
+        fn status(&self) -> Status {
+            Status::DeprecatedDiscard
+        }
+
+        fn removal_version(&self) -> Option<&'static str> {
+            Some("0.99.0")
+        }
+
+        fn migration_hint(&self) -> Option<&'static str> {
+            Some("remove it from your startup configuration")
+        }
+    }
+
+    #[test]
+    fn deprecation_notice_renders_metadata() {
+        static DEPRECATED: ExperimentalOption = ExperimentalOption::new(&DeprecatedMarker);
+
+        let notice = DEPRECATED
+            .deprecation_notice()
+            .expect("deprecated option has a notice");
+        assert_eq!(
+            notice.to_string(),
+            "experimental option \"deprecated-test\" is deprecated and will be removed in \
+             0.99.0: remove it from your startup configuration"
+        );
+        assert!(crate::DATABASE_CMD_NEXT.deprecation_notice().is_none());
+    }
+
+    #[test]
+    fn callbacks_see_changes() {
+        static LAST: AtomicU8 = AtomicU8::new(u8::MAX);
+
+        let _guard = LOCK.lock().unwrap();
+        crate::DATABASE_CMD_NEXT.on_change(|value| LAST.store(value as u8, Ordering::Relaxed));
+
+        crate::DATABASE_CMD_NEXT.set(true);
+        assert_eq!(LAST.load(Ordering::Relaxed), 1);
+
+        crate::DATABASE_CMD_NEXT.unset();
+        assert_eq!(LAST.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn frozen_options_reject_set() {
+        let _guard = LOCK.lock().unwrap();
+        freeze();
+        let result = std::panic::catch_unwind(|| crate::DATABASE_CMD_NEXT.set(true));
+        thaw();
+        assert!(result.is_err());
+        assert!(!crate::DATABASE_CMD_NEXT.get());
+    }
+
+    struct PolicyMarker;
+
+    impl ExperimentalOptionMarker for PolicyMarker {
+        fn identifier(&self) -> &'static str {
+            "policy-test"
+        }
+
+        fn description(&self) -> &'static str {
+            "An option with a default policy, only used in tests."
+        }
+
+        fn status(&self) -> Status {
+            Status::OptIn
+        }
+
+        fn default_policy(&self) -> Option<fn() -> bool> {
+            Some(|| {
+                POLICY_EVALUATIONS.fetch_add(1, Ordering::Relaxed);
+                true
+            })
+        }
+    }
+
+    static POLICY_EVALUATIONS: AtomicU8 = AtomicU8::new(0);
+
+    struct ExpiredMarker;
+
+    impl ExperimentalOptionMarker for ExpiredMarker {
+        fn identifier(&self) -> &'static str {
+            "expired-test"
+        }
+
+        fn description(&self) -> &'static str {
+            "An expired option, only used in tests."
+        }
+
+        fn status(&self) -> Status {
+            Status::OptIn
+        }
+
+        fn expires(&self) -> Option<&'static str> {
+            Some("0.1.0")
+        }
+    }
+
+    #[test]
+    fn expiry_compares_against_the_crate_version() {
+        static EXPIRED: ExperimentalOption = ExperimentalOption::new(&ExpiredMarker);
+
+        assert!(EXPIRED.is_expired());
+        assert!(!crate::DATABASE_CMD_NEXT.is_expired());
+
+        assert_eq!(version_triple("0.95.1"), Some((0, 95, 1)));
+        assert_eq!(version_triple("0.95"), None);
+        assert_eq!(version_triple("0.95.1-rc.1"), None);
+    }
+
+    #[test]
+    fn default_policy_is_evaluated_once() {
+        static POLICED: ExperimentalOption = ExperimentalOption::new(&PolicyMarker);
+
+        let _guard = LOCK.lock().unwrap();
+        assert!(POLICED.get());
+        assert!(POLICED.get());
+        assert_eq!(POLICY_EVALUATIONS.load(Ordering::Relaxed), 1);
+
+        // Explicit values still win over the policy.
+        POLICED.set(false);
+        assert!(!POLICED.get());
+        POLICED.unset();
+    }
+
+    #[test]
+    fn try_set_reports_refusals() {
+        static DEPRECATED: ExperimentalOption = ExperimentalOption::new(&DeprecatedMarker);
+
+        let _guard = LOCK.lock().unwrap();
+
+        freeze();
+        let sealed = crate::DATABASE_CMD_NEXT.try_set(true, ValueSource::Api);
+        thaw();
+        assert_eq!(sealed, Err(SetError::Sealed));
+
+        assert!(matches!(
+            DEPRECATED.try_set(true, ValueSource::Api),
+            Err(SetError::Deprecated(_))
+        ));
+
+        assert!(crate::DATABASE_CMD_NEXT
+            .try_set(true, ValueSource::Api)
+            .is_ok());
+        assert!(crate::DATABASE_CMD_NEXT.get());
+        assert_eq!(crate::DATABASE_CMD_NEXT.source(), ValueSource::Api);
+        crate::DATABASE_CMD_NEXT.unset();
+    }
+}
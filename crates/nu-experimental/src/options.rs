@@ -0,0 +1,229 @@
+//! Definitions of the experimental options living in this crate.
+//!
+//! To add a new option, invoke [`experimental_option!`](crate::experimental_option)
+//! with its identifier, description and status; the macro expands to the
+//! marker type, the option static and the registration in [`ALL`].
+//! Downstream crates can do the same next to the code they gate, see [`ALL`].
+
+use crate::{Category, ExperimentalOption, Status};
+use linkme::distributed_slice;
+
+/// All experimental options known to this build.
+///
+/// This is a [`linkme::distributed_slice`], so options don't have to live in
+/// this crate: downstream crates can declare their own next to the code they
+/// gate and register them with `#[distributed_slice(nu_experimental::ALL)]`
+/// (using the [`linkme`] re-export of this crate).
+#[distributed_slice]
+pub static ALL: [&'static ExperimentalOption];
+
+/// Declare an experimental option in one place.
+///
+/// Expands to the marker struct, its
+/// [`ExperimentalOptionMarker`](crate::ExperimentalOptionMarker) impl, the
+/// option static and the registration in [`ALL`], replacing the four manual
+/// steps otherwise needed per option. The required marker methods are given
+/// as fields; any of the optional ones follow as plain `fn` items:
+///
+/// ```
+/// # use nu_experimental::{experimental_option, Category, Status};
+/// experimental_option! {
+///     /// What the option toggles.
+///     pub static MY_OPTION: MyOption = {
+///         identifier: "my-option",
+///         description: "Shown in `$nu` and parse warnings.",
+///         status: Status::OptIn,
+///         fn category(&self) -> Category {
+///             Category::Commands
+///         }
+///     };
+/// }
+/// ```
+#[macro_export]
+macro_rules! experimental_option {
+    (
+        $(#[$attr:meta])*
+        $vis:vis static $name:ident: $marker:ident = {
+            identifier: $identifier:literal,
+            description: $description:literal,
+            status: $status:expr,
+            $($extra:item)*
+        };
+    ) => {
+        $(#[$attr])*
+        $vis static $name: $crate::ExperimentalOption =
+            $crate::ExperimentalOption::new(&$marker);
+
+        $vis struct $marker;
+
+        impl $crate::ExperimentalOptionMarker for $marker {
+            fn identifier(&self) -> &'static str {
+                $identifier
+            }
+
+            fn description(&self) -> &'static str {
+                $description
+            }
+
+            fn status(&self) -> $crate::Status {
+                $status
+            }
+
+            $($extra)*
+        }
+
+        const _: () = {
+            #[$crate::linkme::distributed_slice($crate::ALL)]
+            static ENTRY: &$crate::ExperimentalOption = &$name;
+        };
+    };
+}
+
+experimental_option! {
+    /// Replace the SQLite database commands with the next-generation
+    /// implementations.
+    pub static DATABASE_CMD_NEXT: DatabaseCmdNext = {
+        identifier: "database-cmd-next",
+        description: "Use the next-generation database commands and plumbing.",
+        status: Status::OptIn,
+        fn category(&self) -> Category {
+            Category::Commands
+        }
+        fn since(&self) -> Option<&'static str> {
+            Some("0.95.1")
+        }
+    };
+}
+
+/// Reset every option back to its unset default.
+///
+/// Embedders that spin up sequential engine instances in one process call
+/// this between runs to guarantee a clean slate without looping over [`ALL`]
+/// themselves. Like [`unset`](ExperimentalOption::unset), this panics after
+/// [`freeze`](crate::freeze).
+pub fn reset_all() {
+    for option in ALL.iter() {
+        option.unset();
+    }
+}
+
+/// All options that are currently active, defaults resolved.
+pub fn enabled() -> impl Iterator<Item = &'static ExperimentalOption> {
+    ALL.iter().copied().filter(|option| option.get())
+}
+
+/// All options that were explicitly set, regardless of their value.
+pub fn non_default() -> impl Iterator<Item = &'static ExperimentalOption> {
+    ALL.iter()
+        .copied()
+        .filter(|option| option.value().is_some())
+}
+
+/// All options that are deprecated.
+pub fn deprecated() -> impl Iterator<Item = &'static ExperimentalOption> {
+    ALL.iter()
+        .copied()
+        .filter(|option| matches!(option.status(), Status::DeprecatedDiscard))
+}
+
+/// Group all known options by their [`Category`], in category order.
+///
+/// Categories without options are omitted; options keep their registration
+/// order within a category.
+pub fn by_category() -> Vec<(Category, Vec<&'static ExperimentalOption>)> {
+    let mut groups: Vec<(Category, Vec<&'static ExperimentalOption>)> = Vec::new();
+
+    for option in ALL.iter().copied() {
+        match groups
+            .iter_mut()
+            .find(|(category, _)| *category == option.category())
+        {
+            Some((_, options)) => options.push(option),
+            None => groups.push((option.category(), vec![option])),
+        }
+    }
+
+    groups.sort_by_key(|(category, _)| *category);
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_lock::LOCK;
+
+    #[test]
+    fn reset_all_clears_explicit_values() {
+        let _guard = LOCK.lock().unwrap();
+        DATABASE_CMD_NEXT.set(true);
+        reset_all();
+        assert_eq!(DATABASE_CMD_NEXT.value(), None);
+    }
+
+    #[test]
+    fn filtering_helpers_respect_state() {
+        let _guard = LOCK.lock().unwrap();
+        assert_eq!(enabled().count(), 0);
+        assert_eq!(non_default().count(), 0);
+        assert_eq!(deprecated().count(), 0);
+
+        DATABASE_CMD_NEXT.set(true);
+        assert!(enabled().any(|option| option.identifier() == "database-cmd-next"));
+        assert_eq!(non_default().count(), 1);
+
+        DATABASE_CMD_NEXT.set(false);
+        assert_eq!(enabled().count(), 0);
+        assert_eq!(non_default().count(), 1);
+        DATABASE_CMD_NEXT.unset();
+    }
+
+    #[test]
+    fn identifiers_are_unique_and_kebab_case() {
+        let mut seen = std::collections::HashSet::new();
+        for option in ALL.iter() {
+            let identifier = option.identifier();
+            assert!(
+                seen.insert(identifier),
+                "duplicate experimental option identifier {identifier:?}",
+            );
+            assert!(
+                !identifier.is_empty()
+                    && !identifier.starts_with('-')
+                    && !identifier.ends_with('-')
+                    && !identifier.contains("--")
+                    && identifier
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-'),
+                "experimental option identifier {identifier:?} is not kebab-case",
+            );
+        }
+    }
+
+    #[test]
+    fn no_option_outlives_its_expiry() {
+        for option in ALL.iter() {
+            assert!(
+                !option.is_expired(),
+                "experimental option {:?} should have been stabilized or removed by {}",
+                option.identifier(),
+                option.expires().unwrap_or_default(),
+            );
+        }
+    }
+
+    #[test]
+    fn grouping_covers_all_options() {
+        let groups = by_category();
+        let total: usize = groups.iter().map(|(_, options)| options.len()).sum();
+        assert_eq!(total, ALL.len());
+
+        let commands = groups
+            .iter()
+            .find(|(category, _)| *category == Category::Commands)
+            .expect("database-cmd-next is a command option");
+        assert!(commands
+            .1
+            .iter()
+            .any(|option| option.identifier() == "database-cmd-next"));
+    }
+}
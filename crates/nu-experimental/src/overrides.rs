@@ -0,0 +1,86 @@
+use crate::{ExperimentalOption, ExperimentalValue, ValueSource};
+
+/// Run a closure with some experimental options temporarily overridden.
+///
+/// The previous values (including their [`ValueSource`]) are restored when the
+/// closure returns, also on panic. This is meant for embedders running
+/// multiple engine instances with differing options in one process; since
+/// option state is global, such instances still have to be serialized against
+/// each other.
+///
+/// Each change bumps [`revision`](crate::revision), so caches derived from
+/// option state can detect that they span an override scope.
+pub fn with_overrides<T>(
+    overrides: &[(&'static ExperimentalOption, bool)],
+    f: impl FnOnce() -> T,
+) -> T {
+    let _guard = OverrideGuard::apply(overrides);
+    f()
+}
+
+/// Restores the values shadowed by [`with_overrides`] on drop.
+struct OverrideGuard {
+    previous: Vec<(
+        &'static ExperimentalOption,
+        Option<ExperimentalValue>,
+        ValueSource,
+    )>,
+}
+
+impl OverrideGuard {
+    fn apply(overrides: &[(&'static ExperimentalOption, bool)]) -> Self {
+        let mut previous = Vec::with_capacity(overrides.len());
+
+        for (option, value) in overrides {
+            previous.push((*option, option.value(), option.source()));
+            option.set_value_from(ExperimentalValue::Bool(*value), ValueSource::Api);
+        }
+
+        Self { previous }
+    }
+}
+
+impl Drop for OverrideGuard {
+    fn drop(&mut self) {
+        // Restore in reverse so overlapping guards unwind like a stack.
+        for (option, value, source) in self.previous.drain(..).rev() {
+            match value {
+                Some(value) => option.set_value_from(value, source),
+                None => option.unset(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_lock::LOCK;
+
+    #[test]
+    fn overrides_are_restored() {
+        let _guard = LOCK.lock().unwrap();
+        crate::DATABASE_CMD_NEXT.set(false);
+
+        with_overrides(&[(&crate::DATABASE_CMD_NEXT, true)], || {
+            assert!(crate::DATABASE_CMD_NEXT.get());
+        });
+
+        assert!(!crate::DATABASE_CMD_NEXT.get());
+        assert_eq!(crate::DATABASE_CMD_NEXT.source(), ValueSource::Api);
+        crate::DATABASE_CMD_NEXT.unset();
+    }
+
+    #[test]
+    fn overrides_restore_unset_options() {
+        let _guard = LOCK.lock().unwrap();
+        let before = crate::revision();
+
+        with_overrides(&[(&crate::DATABASE_CMD_NEXT, true)], || {
+            assert!(crate::DATABASE_CMD_NEXT.get());
+        });
+
+        assert_eq!(crate::DATABASE_CMD_NEXT.value(), None);
+        assert!(crate::revision() > before);
+    }
+}
@@ -0,0 +1,771 @@
+use crate::{DeprecationNotice, ExperimentalOption, ExperimentalValue, ValueSource, ALL};
+use std::fmt;
+
+/// The environment variable experimental options are read from at startup.
+///
+/// The value is a comma-separated list of option identifiers, e.g.
+/// `NU_EXPERIMENTAL_OPTIONS=database-cmd-next`. An entry may carry a value
+/// after an equals sign, e.g. `some-option=false` or `some-option=compact`.
+pub const ENV_VAR: &str = "NU_EXPERIMENTAL_OPTIONS";
+
+/// Parse experimental options from the [`ENV_VAR`] environment variable.
+///
+/// Problematic entries don't abort parsing, they are collected into a
+/// [`ParseReport`] so the caller can report them and continue with the
+/// options that did parse.
+pub fn parse_env() -> ParseReport {
+    match std::env::var(ENV_VAR) {
+        Ok(value) => parse_iter(value.split(','), ValueSource::Env),
+        Err(_) => ParseReport::default(),
+    }
+}
+
+/// Parse experimental options from their individual environment variables.
+///
+/// Every option can also be set via `NU_EXPERIMENTAL_<IDENT>`, where
+/// `<IDENT>` is the shouty snake case version of its identifier, e.g.
+/// `NU_EXPERIMENTAL_DATABASE_CMD_NEXT=true`. The more specific variable wins:
+/// call this after [`parse_env`] so individual variables override entries of
+/// the [`ENV_VAR`] list.
+pub fn parse_individual_env() -> ParseReport {
+    let mut report = ParseReport::default();
+
+    for option in ALL {
+        let var = individual_env_var(option.identifier());
+        let Ok(value) = std::env::var(&var) else {
+            continue;
+        };
+
+        if value.is_empty() {
+            report.invalid_values.push(InvalidValue {
+                identifier: option.identifier().to_string(),
+                value,
+                span: None,
+            });
+            continue;
+        }
+
+        option.set_value_from(crate::value::parse_value(&value), ValueSource::Env);
+        note_set_option(&mut report, option);
+    }
+
+    report
+}
+
+/// The individual environment variable for an option identifier, e.g.
+/// `NU_EXPERIMENTAL_DATABASE_CMD_NEXT` for `database-cmd-next`.
+pub fn individual_env_var(identifier: &str) -> String {
+    let ident = identifier
+        .chars()
+        .map(|c| match c {
+            '-' => '_',
+            c => c.to_ascii_uppercase(),
+        })
+        .collect::<String>();
+    format!("NU_EXPERIMENTAL_{ident}")
+}
+
+/// Parse experimental option identifiers from an iterator of entries.
+///
+/// This is the backend of [`parse_env`] and is split out so other frontends
+/// (like the CLI flag) can feed entries directly, recording their own
+/// [`ValueSource`].
+pub fn parse_iter<'a>(
+    entries: impl Iterator<Item = &'a str>,
+    source: ValueSource,
+) -> ParseReport {
+    let mut report = ParseReport::default();
+
+    for entry in entries {
+        let entry = entry.trim();
+        if entry.is_empty() {
+            continue;
+        }
+
+        let (identifier, value) = match entry.split_once('=') {
+            Some((identifier, "")) => {
+                report.invalid_values.push(InvalidValue {
+                    identifier: identifier.trim().to_string(),
+                    value: String::new(),
+                    span: None,
+                });
+                continue;
+            }
+            Some((identifier, value)) => (identifier.trim(), crate::value::parse_value(value)),
+            None => (entry, ExperimentalValue::Bool(true)),
+        };
+
+        match lookup_option(identifier) {
+            Some((option, via_alias)) => {
+                option.set_value_from(value, source);
+                if via_alias {
+                    report.renamed.push(RenamedOption {
+                        used: identifier.to_string(),
+                        canonical: option.identifier(),
+                    });
+                }
+                note_set_option(&mut report, option);
+            }
+            None => report.unknown.push(UnknownIdentifier {
+                identifier: identifier.to_string(),
+                suggestion: suggest_option(identifier),
+                span: None,
+            }),
+        }
+    }
+
+    report
+}
+
+/// Parse experimental options from a CLI-style list.
+///
+/// This accepts what `--experimental-options` receives: a comma-separated
+/// list, optionally wrapped in brackets, with arbitrary whitespace and
+/// optionally quoted identifiers, e.g. `[example=false, "other"]`. Unlike
+/// [`parse_iter`], issues in the returned [`ParseReport`] carry byte spans
+/// into `input` pointing at the offending element, so the CLI can underline
+/// it instead of blaming the whole string.
+pub fn parse_list(input: &str, source: ValueSource) -> ParseReport {
+    let mut report = ParseReport::default();
+
+    // Strip a bracket pair, keeping offsets into the original input.
+    let trimmed = input.trim();
+    let base = trimmed.as_ptr() as usize - input.as_ptr() as usize;
+    let (inner, base) = match trimmed.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+        Some(inner) => (inner, base + 1),
+        None => (trimmed, base),
+    };
+
+    for (offset, element) in split_list_elements(inner) {
+        let (offset, element) = trim_spanned(base + offset, element);
+        if element.is_empty() {
+            continue;
+        }
+        let span = offset..offset + element.len();
+
+        let (identifier, value) = match split_assignment(element) {
+            Some((identifier, "")) => {
+                report.invalid_values.push(InvalidValue {
+                    identifier: unquote(identifier.trim()).to_string(),
+                    value: String::new(),
+                    span: Some(span),
+                });
+                continue;
+            }
+            Some((identifier, value)) => (
+                unquote(identifier.trim()),
+                crate::value::parse_value(unquote(value.trim())),
+            ),
+            None => (unquote(element), ExperimentalValue::Bool(true)),
+        };
+
+        match lookup_option(identifier) {
+            Some((option, via_alias)) => {
+                option.set_value_from(value, source);
+                if via_alias {
+                    report.renamed.push(RenamedOption {
+                        used: identifier.to_string(),
+                        canonical: option.identifier(),
+                    });
+                }
+                note_set_option(&mut report, option);
+            }
+            None => report.unknown.push(UnknownIdentifier {
+                identifier: identifier.to_string(),
+                suggestion: suggest_option(identifier),
+                span: Some(span),
+            }),
+        }
+    }
+
+    report
+}
+
+/// Split a list body on commas that aren't inside quotes, with byte offsets.
+fn split_list_elements(input: &str) -> Vec<(usize, &str)> {
+    let mut elements = Vec::new();
+    let mut start = 0;
+    let mut quote: Option<char> = None;
+
+    for (index, c) in input.char_indices() {
+        match (c, quote) {
+            ('\'' | '"', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            (',', None) => {
+                elements.push((start, &input[start..index]));
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+
+    elements.push((start, &input[start..]));
+    elements
+}
+
+/// Split an element on the first `=` that isn't inside quotes.
+fn split_assignment(element: &str) -> Option<(&str, &str)> {
+    let mut quote: Option<char> = None;
+
+    for (index, c) in element.char_indices() {
+        match (c, quote) {
+            ('\'' | '"', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            ('=', None) => return Some((&element[..index], &element[index + 1..])),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Trim a string slice, adjusting its byte offset for the leading cut.
+fn trim_spanned(offset: usize, s: &str) -> (usize, &str) {
+    let trimmed = s.trim_start();
+    (offset + s.len() - trimmed.len(), trimmed.trim_end())
+}
+
+/// Strip one pair of matching single or double quotes, if present.
+fn unquote(s: &str) -> &str {
+    for quote in ['\'', '"'] {
+        if let Some(inner) = s
+            .strip_prefix(quote)
+            .and_then(|s| s.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    s
+}
+
+/// Parse experimental options from key-value pairs, e.g. a config record.
+///
+/// Unlike the entries of [`parse_iter`], the values are already typed, so
+/// embedders can feed whatever their config format parsed into.
+pub fn parse_record<'a>(
+    entries: impl Iterator<Item = (&'a str, ExperimentalValue)>,
+    source: ValueSource,
+) -> ParseReport {
+    let mut report = ParseReport::default();
+
+    for (identifier, value) in entries {
+        match lookup_option(identifier.trim()) {
+            Some((option, via_alias)) => {
+                option.set_value_from(value, source);
+                if via_alias {
+                    report.renamed.push(RenamedOption {
+                        used: identifier.trim().to_string(),
+                        canonical: option.identifier(),
+                    });
+                }
+                note_set_option(&mut report, option);
+            }
+            None => report.unknown.push(UnknownIdentifier {
+                identifier: identifier.trim().to_string(),
+                suggestion: suggest_option(identifier.trim()),
+                span: None,
+            }),
+        }
+    }
+
+    report
+}
+
+/// Parse experimental options from the `[experimental]` section of a config.
+///
+/// Config has the lowest precedence: entries for options that were already
+/// set, e.g. via [`ENV_VAR`] or the CLI flag, are ignored. Call this after the
+/// higher-precedence sources have been applied.
+pub fn parse_config<'a>(
+    entries: impl Iterator<Item = (&'a str, ExperimentalValue)>,
+) -> ParseReport {
+    parse_record(
+        entries.filter(|(identifier, _)| {
+            !matches!(
+                find_option(identifier.trim()),
+                Some(option) if option.value().is_some()
+            )
+        }),
+        ValueSource::Config,
+    )
+}
+
+/// Collect the warnings an explicitly set option triggers.
+fn note_set_option(report: &mut ParseReport, option: &'static ExperimentalOption) {
+    report.deprecated.extend(option.deprecation_notice());
+    if option.is_expired() {
+        report.expired.push(ExpiredOption {
+            identifier: option.identifier(),
+            expires: option.expires().expect("expired options have a target"),
+        });
+    }
+}
+
+fn find_option(identifier: &str) -> Option<&'static ExperimentalOption> {
+    lookup_option(identifier).map(|(option, _)| option)
+}
+
+/// Find an option by its identifier or one of its aliases.
+///
+/// The boolean reports whether the lookup went through an alias, so callers
+/// can emit a rename warning.
+fn lookup_option(identifier: &str) -> Option<(&'static ExperimentalOption, bool)> {
+    if let Some(option) = ALL
+        .iter()
+        .find(|option| option.identifier() == identifier)
+    {
+        return Some((option, false));
+    }
+
+    ALL.iter()
+        .find(|option| option.aliases().contains(&identifier))
+        .map(|option| (*option, true))
+}
+
+/// Find a known identifier close to `identifier`, if one exists.
+///
+/// This mirrors `nu_protocol::did_you_mean`, which we can't use here without
+/// pulling the whole protocol crate into this one: the closest identifier
+/// within a third of the input's length wins.
+fn suggest_option(identifier: &str) -> Option<&'static str> {
+    let threshold = (identifier.len() / 3).max(1);
+
+    ALL.iter()
+        .map(|option| {
+            (
+                option.identifier(),
+                levenshtein_distance(identifier, option.identifier()),
+            )
+        })
+        .filter(|(_, distance)| *distance <= threshold)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(identifier, _)| identifier)
+}
+
+/// The Levenshtein distance between two strings, by characters.
+fn levenshtein_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut distances: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut previous_diagonal = distances[0];
+        distances[0] = i + 1;
+
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous_diagonal + usize::from(a_char != *b_char);
+            previous_diagonal = distances[j + 1];
+            distances[j + 1] = substitution
+                .min(distances[j + 1] + 1)
+                .min(distances[j] + 1);
+        }
+    }
+
+    distances[b.len()]
+}
+
+/// Everything non-fatal that went wrong while parsing experimental options.
+///
+/// The report groups issues by kind so callers can render rich diagnostics;
+/// [`warnings`](Self::warnings) flattens it back into displayable
+/// [`ParseWarning`]s for callers that just print them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseReport {
+    /// Identifiers that don't match any known experimental option.
+    pub unknown: Vec<UnknownIdentifier>,
+    /// Entries whose value couldn't be parsed, e.g. `some-option=`.
+    pub invalid_values: Vec<InvalidValue>,
+    /// Deprecated options that were explicitly set.
+    pub deprecated: Vec<DeprecationNotice>,
+    /// Options that were set although they outlived their expiry target.
+    pub expired: Vec<ExpiredOption>,
+    /// Options that were referred to by an old identifier.
+    pub renamed: Vec<RenamedOption>,
+}
+
+impl ParseReport {
+    /// Whether parsing went through without any issues.
+    pub fn is_empty(&self) -> bool {
+        self.unknown.is_empty()
+            && self.invalid_values.is_empty()
+            && self.deprecated.is_empty()
+            && self.expired.is_empty()
+            && self.renamed.is_empty()
+    }
+
+    /// Flatten the report into displayable warnings, in report order.
+    pub fn warnings(&self) -> Vec<ParseWarning> {
+        let mut warnings = Vec::new();
+        for unknown in &self.unknown {
+            warnings.push(ParseWarning::Unknown {
+                identifier: unknown.identifier.clone(),
+                suggestion: unknown.suggestion,
+            });
+        }
+        for invalid in &self.invalid_values {
+            warnings.push(ParseWarning::InvalidValue {
+                identifier: invalid.identifier.clone(),
+                value: invalid.value.clone(),
+            });
+        }
+        for notice in &self.deprecated {
+            warnings.push(ParseWarning::Deprecated { notice: *notice });
+        }
+        for expired in &self.expired {
+            warnings.push(ParseWarning::Expired {
+                identifier: expired.identifier,
+                expires: expired.expires,
+            });
+        }
+        for renamed in &self.renamed {
+            warnings.push(ParseWarning::Renamed {
+                used: renamed.used.clone(),
+                canonical: renamed.canonical,
+            });
+        }
+        warnings
+    }
+
+    /// Merge another report into this one, e.g. from a second source.
+    pub fn merge(&mut self, other: ParseReport) {
+        self.unknown.extend(other.unknown);
+        self.invalid_values.extend(other.invalid_values);
+        self.deprecated.extend(other.deprecated);
+        self.expired.extend(other.expired);
+        self.renamed.extend(other.renamed);
+    }
+}
+
+/// An identifier that doesn't match any known experimental option.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownIdentifier {
+    /// The identifier as the user wrote it.
+    pub identifier: String,
+    /// A close match from [`ALL`], if one exists.
+    pub suggestion: Option<&'static str>,
+    /// Byte span of the offending element in the parsed input, if tracked.
+    pub span: Option<std::ops::Range<usize>>,
+}
+
+/// An entry whose value couldn't be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidValue {
+    /// The identifier the value was given for.
+    pub identifier: String,
+    /// The value as the user wrote it.
+    pub value: String,
+    /// Byte span of the offending element in the parsed input, if tracked.
+    pub span: Option<std::ops::Range<usize>>,
+}
+
+/// An option that was set although it outlived its expiry target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExpiredOption {
+    /// The identifier of the expired option.
+    pub identifier: &'static str,
+    /// The version the option should have been stabilized or removed by.
+    pub expires: &'static str,
+}
+
+/// An option that was referred to by an old identifier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenamedOption {
+    /// The identifier as the user wrote it.
+    pub used: String,
+    /// The identifier the option goes by now.
+    pub canonical: &'static str,
+}
+
+/// A non-fatal issue encountered while parsing experimental options.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseWarning {
+    /// The identifier doesn't match any known experimental option.
+    Unknown {
+        identifier: String,
+        suggestion: Option<&'static str>,
+    },
+    /// The value given for an option couldn't be parsed.
+    InvalidValue { identifier: String, value: String },
+    /// The option is deprecated and will be removed.
+    Deprecated { notice: DeprecationNotice },
+    /// The option outlived the version it should have been resolved by.
+    Expired {
+        identifier: &'static str,
+        expires: &'static str,
+    },
+    /// The option was referred to by an old identifier.
+    Renamed {
+        used: String,
+        canonical: &'static str,
+    },
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseWarning::Unknown {
+                identifier,
+                suggestion: None,
+            } => {
+                write!(f, "unknown experimental option: {identifier:?}")
+            }
+            ParseWarning::Unknown {
+                identifier,
+                suggestion: Some(suggestion),
+            } => {
+                write!(
+                    f,
+                    "unknown experimental option: {identifier:?}, did you mean {suggestion:?}?"
+                )
+            }
+            ParseWarning::InvalidValue { identifier, value } => {
+                write!(
+                    f,
+                    "invalid value {value:?} for experimental option {identifier:?}"
+                )
+            }
+            ParseWarning::Deprecated { notice } => notice.fmt(f),
+            ParseWarning::Expired {
+                identifier,
+                expires,
+            } => {
+                write!(
+                    f,
+                    "experimental option {identifier:?} should have been stabilized or removed \
+                     by {expires}"
+                )
+            }
+            ParseWarning::Renamed { used, canonical } => {
+                write!(
+                    f,
+                    "experimental option {used:?} was renamed to {canonical:?}, \
+                     the old identifier will stop working in a future release"
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_lock::LOCK, ExperimentalOptionMarker, Status};
+    use linkme::distributed_slice;
+
+    static RENAMED_TEST: ExperimentalOption = ExperimentalOption::new(&RenamedMarker);
+
+    #[distributed_slice(ALL)]
+    static RENAMED_TEST_ENTRY: &ExperimentalOption = &RENAMED_TEST;
+
+    struct RenamedMarker;
+
+    impl ExperimentalOptionMarker for RenamedMarker {
+        fn identifier(&self) -> &'static str {
+            "renamed-test"
+        }
+
+        fn description(&self) -> &'static str {
+            "A renamed option, only used in tests."
+        }
+
+        fn status(&self) -> Status {
+            Status::OptIn
+        }
+
+        fn aliases(&self) -> &'static [&'static str] {
+            &["old-test"]
+        }
+    }
+
+    #[test]
+    fn aliases_set_the_option_with_a_rename_warning() {
+        let _guard = LOCK.lock().unwrap();
+        let report = parse_iter("old-test".split(','), ValueSource::Env);
+        assert!(RENAMED_TEST.get());
+        assert_eq!(
+            report.renamed,
+            vec![RenamedOption {
+                used: "old-test".to_string(),
+                canonical: "renamed-test",
+            }]
+        );
+        RENAMED_TEST.unset();
+    }
+
+    #[test]
+    fn parse_known_identifier() {
+        let _guard = LOCK.lock().unwrap();
+        let warnings = parse_iter("database-cmd-next".split(','), ValueSource::Env);
+        assert!(warnings.is_empty());
+        assert!(crate::DATABASE_CMD_NEXT.get());
+        crate::DATABASE_CMD_NEXT.unset();
+    }
+
+    #[test]
+    fn parse_unknown_identifier() {
+        let report = parse_iter("definitely-not-an-option".split(','), ValueSource::Env);
+        assert_eq!(
+            report.unknown,
+            vec![UnknownIdentifier {
+                identifier: "definitely-not-an-option".to_string(),
+                suggestion: None,
+                span: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_suggests_close_identifier() {
+        let _guard = LOCK.lock().unwrap();
+        let report = parse_iter("database-cmd-nxt".split(','), ValueSource::Env);
+        assert_eq!(
+            report.unknown,
+            vec![UnknownIdentifier {
+                identifier: "database-cmd-nxt".to_string(),
+                suggestion: Some("database-cmd-next"),
+                span: None,
+            }]
+        );
+        assert!(!crate::DATABASE_CMD_NEXT.get());
+    }
+
+    #[test]
+    fn list_syntax_with_brackets_and_quotes() {
+        let _guard = LOCK.lock().unwrap();
+        let report = parse_list("[ 'database-cmd-next' = fancy ]", ValueSource::Cli);
+        assert!(report.is_empty());
+        assert_eq!(
+            crate::DATABASE_CMD_NEXT.value(),
+            Some(ExperimentalValue::String("fancy".to_string()))
+        );
+        crate::DATABASE_CMD_NEXT.unset();
+    }
+
+    #[test]
+    fn list_errors_point_at_the_element() {
+        let _guard = LOCK.lock().unwrap();
+        let input = "[database-cmd-next=false, nope]";
+        let report = parse_list(input, ValueSource::Cli);
+        assert_eq!(report.unknown.len(), 1);
+        let span = report.unknown[0].span.clone().expect("list spans are set");
+        assert_eq!(&input[span], "nope");
+        crate::DATABASE_CMD_NEXT.unset();
+    }
+
+    #[test]
+    fn individual_env_var_naming() {
+        assert_eq!(
+            individual_env_var("database-cmd-next"),
+            "NU_EXPERIMENTAL_DATABASE_CMD_NEXT"
+        );
+    }
+
+    #[test]
+    fn individual_env_overrides_list() {
+        let _guard = LOCK.lock().unwrap();
+        parse_iter("database-cmd-next".split(','), ValueSource::Env);
+        std::env::set_var("NU_EXPERIMENTAL_DATABASE_CMD_NEXT", "false");
+        let report = parse_individual_env();
+        std::env::remove_var("NU_EXPERIMENTAL_DATABASE_CMD_NEXT");
+        assert!(report.is_empty());
+        assert!(!crate::DATABASE_CMD_NEXT.get());
+        crate::DATABASE_CMD_NEXT.unset();
+    }
+
+    #[test]
+    fn levenshtein_basics() {
+        assert_eq!(levenshtein_distance("", "abc"), 3);
+        assert_eq!(levenshtein_distance("abc", "abc"), 0);
+        assert_eq!(levenshtein_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn parse_empty_value() {
+        let report = parse_iter("database-cmd-next=".split(','), ValueSource::Env);
+        assert_eq!(
+            report.invalid_values,
+            vec![InvalidValue {
+                identifier: "database-cmd-next".to_string(),
+                value: String::new(),
+                span: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_skips_empty_entries() {
+        let warnings = parse_iter(", ,".split(','), ValueSource::Env);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn parse_explicit_false() {
+        let _guard = LOCK.lock().unwrap();
+        let warnings = parse_iter("database-cmd-next=false".split(','), ValueSource::Env);
+        assert!(warnings.is_empty());
+        assert_eq!(
+            crate::DATABASE_CMD_NEXT.value(),
+            Some(ExperimentalValue::Bool(false))
+        );
+        crate::DATABASE_CMD_NEXT.unset();
+    }
+
+    #[test]
+    fn record_sets_options() {
+        let _guard = LOCK.lock().unwrap();
+        let entries = [("database-cmd-next", ExperimentalValue::Bool(true))];
+        let warnings = parse_record(entries.into_iter(), ValueSource::Api);
+        assert!(warnings.is_empty());
+        assert!(crate::DATABASE_CMD_NEXT.get());
+        crate::DATABASE_CMD_NEXT.unset();
+    }
+
+    #[test]
+    fn sources_are_recorded() {
+        let _guard = LOCK.lock().unwrap();
+        assert_eq!(crate::DATABASE_CMD_NEXT.source(), ValueSource::Default);
+
+        parse_iter("database-cmd-next".split(','), ValueSource::Env);
+        assert_eq!(crate::DATABASE_CMD_NEXT.source(), ValueSource::Env);
+
+        let entries = [("database-cmd-next", ExperimentalValue::Bool(false))];
+        parse_record(entries.into_iter(), ValueSource::Cli);
+        assert_eq!(crate::DATABASE_CMD_NEXT.source(), ValueSource::Cli);
+
+        crate::DATABASE_CMD_NEXT.unset();
+        assert_eq!(crate::DATABASE_CMD_NEXT.source(), ValueSource::Default);
+    }
+
+    #[test]
+    fn config_doesnt_override_earlier_sources() {
+        let _guard = LOCK.lock().unwrap();
+        crate::DATABASE_CMD_NEXT.set(false);
+        let entries = [("database-cmd-next", ExperimentalValue::Bool(true))];
+        let warnings = parse_config(entries.into_iter());
+        assert!(warnings.is_empty());
+        assert!(!crate::DATABASE_CMD_NEXT.get());
+        crate::DATABASE_CMD_NEXT.unset();
+    }
+
+    #[test]
+    fn config_sets_unset_options() {
+        let _guard = LOCK.lock().unwrap();
+        let entries = [("database-cmd-next", ExperimentalValue::Bool(true))];
+        let warnings = parse_config(entries.into_iter());
+        assert!(warnings.is_empty());
+        assert!(crate::DATABASE_CMD_NEXT.get());
+        crate::DATABASE_CMD_NEXT.unset();
+    }
+
+    #[test]
+    fn parse_payload_value() {
+        let _guard = LOCK.lock().unwrap();
+        let warnings = parse_iter("database-cmd-next=fancy".split(','), ValueSource::Env);
+        assert!(warnings.is_empty());
+        assert!(crate::DATABASE_CMD_NEXT.get());
+        assert_eq!(
+            crate::DATABASE_CMD_NEXT.value(),
+            Some(ExperimentalValue::String("fancy".to_string()))
+        );
+        crate::DATABASE_CMD_NEXT.unset();
+    }
+}
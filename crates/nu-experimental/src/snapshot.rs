@@ -0,0 +1,64 @@
+use crate::{ExperimentalValue, ParseReport, UnknownIdentifier, ValueSource, ALL};
+
+/// Capture the effective state of every experimental option.
+///
+/// The engine ships this to plugins at handshake time so plugins can mirror
+/// behavior gated by the same flags; the plugin side feeds it into
+/// [`apply_snapshot`]. Every option is included with its effective value, so
+/// applying a snapshot also propagates defaults.
+pub fn snapshot() -> Vec<(String, bool)> {
+    ALL.iter()
+        .map(|option| (option.identifier().to_string(), option.get()))
+        .collect()
+}
+
+/// Apply a snapshot taken by [`snapshot`], usually in another process.
+///
+/// Identifiers unknown on this side don't abort, they are collected into the
+/// returned [`ParseReport`]; this keeps engines and plugins built from
+/// slightly different versions compatible.
+pub fn apply_snapshot(snapshot: &[(String, bool)]) -> ParseReport {
+    let mut report = ParseReport::default();
+
+    for (identifier, value) in snapshot {
+        match ALL.iter().find(|option| option.identifier() == identifier) {
+            Some(option) => {
+                option.set_value_from(ExperimentalValue::Bool(*value), ValueSource::Api)
+            }
+            None => report.unknown.push(UnknownIdentifier {
+                identifier: identifier.clone(),
+                suggestion: None,
+                span: None,
+            }),
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_lock::LOCK;
+
+    #[test]
+    fn snapshot_round_trips() {
+        let _guard = LOCK.lock().unwrap();
+        crate::DATABASE_CMD_NEXT.set(true);
+        let state = snapshot();
+        crate::DATABASE_CMD_NEXT.unset();
+
+        let report = apply_snapshot(&state);
+        assert!(report.is_empty());
+        assert!(crate::DATABASE_CMD_NEXT.get());
+        crate::DATABASE_CMD_NEXT.unset();
+    }
+
+    #[test]
+    fn unknown_snapshot_entries_are_reported() {
+        let _guard = LOCK.lock().unwrap();
+        let report = apply_snapshot(&[("from-the-future".to_string(), true)]);
+        assert_eq!(report.unknown.len(), 1);
+        assert_eq!(report.unknown[0].identifier, "from-the-future");
+    }
+}
@@ -0,0 +1,130 @@
+//! Storage primitives behind the option state.
+//!
+//! On most targets these are plain atomics. On wasm targets built without the
+//! `atomics` proposal there is only one thread, so the same API is backed by
+//! [`Cell`](std::cell::Cell)s with an `unsafe` single-thread `Sync`
+//! assumption; this keeps the crate compiling for `wasm32-unknown-unknown`
+//! (e.g. the browser demo) without scattering `cfg` through the option code.
+
+#[cfg(not(all(target_family = "wasm", not(target_feature = "atomics"))))]
+mod imp {
+    use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
+
+    /// A small state byte, e.g. an option's set/unset state.
+    pub struct StateCell(AtomicU8);
+
+    impl StateCell {
+        pub const fn new(value: u8) -> Self {
+            Self(AtomicU8::new(value))
+        }
+
+        pub fn load(&self) -> u8 {
+            self.0.load(Ordering::Relaxed)
+        }
+
+        pub fn store(&self, value: u8) {
+            self.0.store(value, Ordering::Relaxed);
+        }
+    }
+
+    /// A boolean flag, e.g. the global freeze state.
+    pub struct FlagCell(AtomicBool);
+
+    impl FlagCell {
+        pub const fn new(value: bool) -> Self {
+            Self(AtomicBool::new(value))
+        }
+
+        pub fn load(&self) -> bool {
+            self.0.load(Ordering::Relaxed)
+        }
+
+        pub fn store(&self, value: bool) {
+            self.0.store(value, Ordering::Relaxed);
+        }
+    }
+
+    /// A monotonically increasing counter, e.g. the revision counter.
+    pub struct CounterCell(AtomicU64);
+
+    impl CounterCell {
+        pub const fn new(value: u64) -> Self {
+            Self(AtomicU64::new(value))
+        }
+
+        pub fn load(&self) -> u64 {
+            self.0.load(Ordering::Relaxed)
+        }
+
+        pub fn increment(&self) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(all(target_family = "wasm", not(target_feature = "atomics")))]
+mod imp {
+    use std::cell::Cell;
+
+    /// A small state byte, e.g. an option's set/unset state.
+    pub struct StateCell(Cell<u8>);
+
+    // SAFETY: without the `atomics` target feature, wasm runs single-threaded.
+    unsafe impl Sync for StateCell {}
+
+    impl StateCell {
+        pub const fn new(value: u8) -> Self {
+            Self(Cell::new(value))
+        }
+
+        pub fn load(&self) -> u8 {
+            self.0.get()
+        }
+
+        pub fn store(&self, value: u8) {
+            self.0.set(value);
+        }
+    }
+
+    /// A boolean flag, e.g. the global freeze state.
+    pub struct FlagCell(Cell<bool>);
+
+    // SAFETY: without the `atomics` target feature, wasm runs single-threaded.
+    unsafe impl Sync for FlagCell {}
+
+    impl FlagCell {
+        pub const fn new(value: bool) -> Self {
+            Self(Cell::new(value))
+        }
+
+        pub fn load(&self) -> bool {
+            self.0.get()
+        }
+
+        pub fn store(&self, value: bool) {
+            self.0.set(value);
+        }
+    }
+
+    /// A monotonically increasing counter, e.g. the revision counter.
+    pub struct CounterCell(Cell<u64>);
+
+    // SAFETY: without the `atomics` target feature, wasm runs single-threaded.
+    unsafe impl Sync for CounterCell {}
+
+    impl CounterCell {
+        pub const fn new(value: u64) -> Self {
+            Self(Cell::new(value))
+        }
+
+        pub fn load(&self) -> u64 {
+            self.0.get()
+        }
+
+        pub fn increment(&self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+}
+
+pub(crate) use imp::{CounterCell, FlagCell, StateCell};
@@ -0,0 +1,181 @@
+//! Test helpers for code gated behind experimental options.
+//!
+//! With the `test-support` feature enabled, option reads first consult a
+//! thread-local override map, so parallel tests can run with differing
+//! options without racing on the global state. Overrides are installed with
+//! [`ExperimentalOptionsGuard`] and last until the guard drops.
+
+use crate::{ExperimentalOption, ExperimentalValue};
+use std::{cell::RefCell, collections::HashMap, marker::PhantomData};
+
+thread_local! {
+    static OVERRIDES: RefCell<HashMap<&'static str, ExperimentalValue>> =
+        RefCell::new(HashMap::new());
+}
+
+/// The thread-local override for an option, if one is installed.
+pub(crate) fn local_value(identifier: &str) -> Option<ExperimentalValue> {
+    OVERRIDES.with(|overrides| overrides.borrow().get(identifier).cloned())
+}
+
+/// Overrides experimental options on the current thread until dropped.
+///
+/// Guards stack: each guard remembers only the values it shadowed and
+/// restores exactly those on drop, so helper functions can set options within
+/// an outer guarded test without wiping the outer guard's state.
+pub struct ExperimentalOptionsGuard {
+    shadowed: Vec<(&'static str, Option<ExperimentalValue>)>,
+    // Thread-local state must be restored on the thread that shadowed it.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl ExperimentalOptionsGuard {
+    /// Override options on the current thread for the guard's lifetime.
+    ///
+    /// Takes anything iterable over pairs, so bulk setup is one call:
+    /// `ExperimentalOptionsGuard::with([(&OPT, true), (&OTHER, false)])`.
+    pub fn with(overrides: impl IntoIterator<Item = (&'static ExperimentalOption, bool)>) -> Self {
+        Self::with_values(
+            overrides
+                .into_iter()
+                .map(|(option, value)| (option, ExperimentalValue::Bool(value))),
+        )
+    }
+
+    /// Like [`with`](Self::with), but for typed values.
+    pub fn with_values(
+        overrides: impl IntoIterator<Item = (&'static ExperimentalOption, ExperimentalValue)>,
+    ) -> Self {
+        Self::install(
+            overrides
+                .into_iter()
+                .map(|(option, value)| (option.identifier(), value)),
+        )
+    }
+
+    fn install(overrides: impl IntoIterator<Item = (&'static str, ExperimentalValue)>) -> Self {
+        let mut shadowed = Vec::new();
+
+        OVERRIDES.with(|current| {
+            let mut current = current.borrow_mut();
+            for (identifier, value) in overrides {
+                shadowed.push((identifier, current.get(identifier).cloned()));
+                current.insert(identifier, value);
+            }
+        });
+
+        Self {
+            shadowed,
+            _not_send: PhantomData,
+        }
+    }
+}
+
+impl Drop for ExperimentalOptionsGuard {
+    fn drop(&mut self) {
+        OVERRIDES.with(|current| {
+            let mut current = current.borrow_mut();
+            // Restore in reverse so shadowing the same option twice in one
+            // guard unwinds correctly.
+            for (identifier, value) in self.shadowed.drain(..).rev() {
+                match value {
+                    Some(value) => current.insert(identifier, value),
+                    None => current.remove(identifier),
+                };
+            }
+        });
+    }
+}
+
+/// Run a closure with options overridden on the current thread.
+///
+/// A shorthand for creating an [`ExperimentalOptionsGuard`] around a block,
+/// for tests that don't need to hold on to the guard.
+pub fn scoped<T>(
+    overrides: impl IntoIterator<Item = (&'static ExperimentalOption, bool)>,
+    f: impl FnOnce() -> T,
+) -> T {
+    let _guard = ExperimentalOptionsGuard::with(overrides);
+    f()
+}
+
+/// Capture the current thread's overrides for use in a child thread.
+///
+/// Overrides are thread-local, so a worker thread spawned inside a guarded
+/// test would otherwise silently revert to the defaults. The returned token
+/// is `Send`; the child thread installs it with [`PropagatedOptions::apply`].
+pub fn propagate() -> PropagatedOptions {
+    PropagatedOptions {
+        overrides: OVERRIDES.with(|overrides| overrides.borrow().clone()),
+    }
+}
+
+/// A snapshot of one thread's overrides, created by [`propagate`].
+#[derive(Debug, Clone)]
+pub struct PropagatedOptions {
+    overrides: HashMap<&'static str, ExperimentalValue>,
+}
+
+impl PropagatedOptions {
+    /// Install the captured overrides on the current thread.
+    ///
+    /// Returns a stacking [`ExperimentalOptionsGuard`] like any other
+    /// override, so the thread reverts once the guard drops.
+    pub fn apply(self) -> ExperimentalOptionsGuard {
+        ExperimentalOptionsGuard::install(self.overrides)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DATABASE_CMD_NEXT;
+
+    #[test]
+    fn guards_override_thread_locally() {
+        assert!(!DATABASE_CMD_NEXT.get());
+        let guard = ExperimentalOptionsGuard::with([(&DATABASE_CMD_NEXT, true)]);
+        assert!(DATABASE_CMD_NEXT.get());
+        drop(guard);
+        assert!(!DATABASE_CMD_NEXT.get());
+    }
+
+    #[test]
+    fn scoped_reverts_after_the_closure() {
+        let seen = scoped([(&DATABASE_CMD_NEXT, true)], || DATABASE_CMD_NEXT.get());
+        assert!(seen);
+        assert!(!DATABASE_CMD_NEXT.get());
+    }
+
+    #[test]
+    fn propagated_options_reach_child_threads() {
+        let _guard = ExperimentalOptionsGuard::with([(&DATABASE_CMD_NEXT, true)]);
+        let token = propagate();
+
+        let seen = std::thread::spawn(move || {
+            let _guard = token.apply();
+            DATABASE_CMD_NEXT.get()
+        })
+        .join()
+        .expect("child thread doesn't panic");
+
+        assert!(seen);
+    }
+
+    #[test]
+    fn nested_guards_restore_what_they_shadowed() {
+        let outer = ExperimentalOptionsGuard::with([(&DATABASE_CMD_NEXT, true)]);
+        assert!(DATABASE_CMD_NEXT.get());
+
+        {
+            let _inner = ExperimentalOptionsGuard::with([(&DATABASE_CMD_NEXT, false)]);
+            assert!(!DATABASE_CMD_NEXT.get());
+        }
+
+        // The inner guard only restores the value it shadowed, the outer
+        // override stays in place.
+        assert!(DATABASE_CMD_NEXT.get());
+        drop(outer);
+        assert!(!DATABASE_CMD_NEXT.get());
+    }
+}
@@ -0,0 +1,66 @@
+use std::fmt;
+
+/// A value carried by an [`ExperimentalOption`](crate::ExperimentalOption).
+///
+/// Most options are plain switches, but some need a small payload to pick
+/// between behaviors, e.g. `table-mode=compact`. A payload implies the option
+/// is enabled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExperimentalValue {
+    /// The option is plainly enabled or disabled.
+    Bool(bool),
+    /// The option is enabled and carries a payload.
+    String(String),
+}
+
+impl ExperimentalValue {
+    /// The boolean this value resolves to.
+    ///
+    /// Options carrying a payload count as enabled.
+    pub fn as_bool(&self) -> bool {
+        match self {
+            ExperimentalValue::Bool(value) => *value,
+            ExperimentalValue::String(_) => true,
+        }
+    }
+
+    /// The payload of this value, if it carries one.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            ExperimentalValue::Bool(_) => None,
+            ExperimentalValue::String(value) => Some(value),
+        }
+    }
+}
+
+impl fmt::Display for ExperimentalValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExperimentalValue::Bool(value) => write!(f, "{value}"),
+            ExperimentalValue::String(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+impl From<bool> for ExperimentalValue {
+    fn from(value: bool) -> Self {
+        ExperimentalValue::Bool(value)
+    }
+}
+
+impl From<String> for ExperimentalValue {
+    fn from(value: String) -> Self {
+        ExperimentalValue::String(value)
+    }
+}
+
+/// Parse the right-hand side of an `identifier=value` entry.
+///
+/// `true` and `false` toggle the option, everything else becomes a payload.
+pub(crate) fn parse_value(value: &str) -> ExperimentalValue {
+    match value {
+        "true" => ExperimentalValue::Bool(true),
+        "false" => ExperimentalValue::Bool(false),
+        payload => ExperimentalValue::String(payload.to_string()),
+    }
+}
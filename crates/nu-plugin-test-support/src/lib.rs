@@ -107,4 +107,5 @@ mod fake_register;
 mod plugin_test;
 mod spawn_fake_plugin;
 
+pub use fake_register::fake_register;
 pub use plugin_test::PluginTest;
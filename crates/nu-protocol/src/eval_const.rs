@@ -238,6 +238,24 @@ pub(crate) fn create_nu_constant(engine_state: &EngineState, span: Span) -> Valu
         Value::bool(engine_state.history_enabled, span),
     );
 
+    record.push("experimental-options", {
+        let options = nu_experimental::as_record_data()
+            .into_iter()
+            .map(|(identifier, value, status, description)| {
+                Value::record(
+                    record! {
+                        "identifier" => Value::string(identifier, span),
+                        "value" => Value::bool(value, span),
+                        "status" => Value::string(status, span),
+                        "description" => Value::string(description, span),
+                    },
+                    span,
+                )
+            })
+            .collect();
+        Value::list(options, span)
+    });
+
     record.push(
         "current-exe",
         if let Ok(current_exe) = std::env::current_exe() {
@@ -0,0 +1,70 @@
+use nu_protocol::TrimStrategy;
+use nu_table::{NuTable, NuTableConfig, TableTheme};
+use tabled::grid::records::vec_records::CellInfo;
+
+use std::hint::black_box;
+
+use tango_bench::{benchmark_fn, tango_benchmarks, tango_main, IntoBenchmarks};
+
+// generate table data with `row_cnt` rows, `col_cnt` columns.
+fn table_test_data(row_cnt: usize, col_cnt: usize, cell: impl Fn(usize, usize) -> String) -> NuTable {
+    let mut data = Vec::with_capacity(row_cnt + 1);
+    data.push(
+        (0..col_cnt)
+            .map(|col| CellInfo::new(format!("col_{col}")))
+            .collect(),
+    );
+    for row in 0..row_cnt {
+        data.push((0..col_cnt).map(|col| CellInfo::new(cell(row, col))).collect());
+    }
+
+    NuTable::from(data)
+}
+
+fn table_config() -> NuTableConfig {
+    NuTableConfig {
+        theme: TableTheme::rounded(),
+        with_header: true,
+        trim: TrimStrategy::wrap(false),
+        ..Default::default()
+    }
+}
+
+fn draw_table(name: &str, table: NuTable, termwidth: usize) -> impl IntoBenchmarks {
+    let name = name.to_owned();
+    [benchmark_fn(name, move |b| {
+        let table = table.clone();
+        b.iter(move || black_box(table.clone().draw(table_config(), termwidth)))
+    })]
+}
+
+fn draw_wide(row_cnt: usize, col_cnt: usize) -> impl IntoBenchmarks {
+    let table = table_test_data(row_cnt, col_cnt, |row, col| format!("value {row} {col}"));
+    draw_table(&format!("draw_wide_{row_cnt}_{col_cnt}"), table, 120)
+}
+
+fn draw_tall(row_cnt: usize, col_cnt: usize) -> impl IntoBenchmarks {
+    let table = table_test_data(row_cnt, col_cnt, |row, col| format!("value {row} {col}"));
+    draw_table(&format!("draw_tall_{row_cnt}_{col_cnt}"), table, 80)
+}
+
+fn draw_ansi(row_cnt: usize, col_cnt: usize) -> impl IntoBenchmarks {
+    let table = table_test_data(row_cnt, col_cnt, |row, col| {
+        format!("\u{1b}[31mvalue\u{1b}[39m \u{1b}[1m{row} {col}\u{1b}[0m some long colored text")
+    });
+    draw_table(&format!("draw_ansi_{row_cnt}_{col_cnt}"), table, 80)
+}
+
+tango_benchmarks!(
+    // Wide
+    draw_wide(100, 10),
+    draw_wide(100, 50),
+    // Tall
+    draw_tall(1_000, 5),
+    draw_tall(10_000, 5),
+    // Ansi
+    draw_ansi(100, 5),
+    draw_ansi(1_000, 5)
+);
+
+tango_main!();
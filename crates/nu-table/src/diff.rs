@@ -0,0 +1,159 @@
+use crate::{NuTable, NuTableCell, NuTableConfig};
+use nu_color_config::TextStyle;
+use tabled::grid::records::vec_records::CellInfo;
+
+/// TableDiff renders two tables sharing the same columns as one combined
+/// view, marking added (`+`), removed (`-`) and changed (`~`) rows in a
+/// leading column and changed cells as `old -> new`.
+///
+/// Rows are matched by a longest common subsequence over their values, so
+/// an insertion in the middle doesn't mark everything below it as changed;
+/// a removed row directly followed by an added one is shown as a change.
+pub struct TableDiff {
+    columns: Vec<String>,
+    old: Vec<Vec<String>>,
+    new: Vec<Vec<String>>,
+    added_style: TextStyle,
+    removed_style: TextStyle,
+    changed_style: TextStyle,
+}
+
+impl TableDiff {
+    pub fn new(columns: Vec<String>, old: Vec<Vec<String>>, new: Vec<Vec<String>>) -> Self {
+        Self {
+            columns,
+            old,
+            new,
+            added_style: TextStyle::default(),
+            removed_style: TextStyle::default(),
+            changed_style: TextStyle::default(),
+        }
+    }
+
+    /// Sets the style for added rows; only its color is applied.
+    pub fn set_added_style(&mut self, style: TextStyle) {
+        self.added_style = style;
+    }
+
+    /// Sets the style for removed rows; only its color is applied.
+    pub fn set_removed_style(&mut self, style: TextStyle) {
+        self.removed_style = style;
+    }
+
+    /// Sets the style for changed cells; only its color is applied.
+    pub fn set_changed_style(&mut self, style: TextStyle) {
+        self.changed_style = style;
+    }
+
+    /// Renders the combined view; the config's header flag is overridden as
+    /// the view always carries the column names.
+    pub fn draw(self, mut config: NuTableConfig, termwidth: usize) -> Option<String> {
+        let count_columns = self.columns.len();
+
+        let mut header = Vec::with_capacity(count_columns + 1);
+        header.push(CellInfo::new(String::new()));
+        header.extend(self.columns.iter().cloned().map(CellInfo::new));
+
+        let mut data = vec![header];
+        for row in diff_rows(&self.old, &self.new) {
+            data.push(match row {
+                RowDiff::Equal(row) => diff_row(" ", row, &TextStyle::default()),
+                RowDiff::Added(row) => diff_row("+", row, &self.added_style),
+                RowDiff::Removed(row) => diff_row("-", row, &self.removed_style),
+                RowDiff::Changed(old, new) => {
+                    let mut cells = Vec::with_capacity(count_columns + 1);
+                    cells.push(CellInfo::new(paint("~", &self.changed_style)));
+                    for (old, new) in old.iter().zip(new.iter()) {
+                        let cell = if old == new {
+                            old.clone()
+                        } else {
+                            paint(&format!("{old} -> {new}"), &self.changed_style)
+                        };
+                        cells.push(CellInfo::new(cell));
+                    }
+
+                    cells
+                }
+            });
+        }
+
+        config.with_header = true;
+
+        NuTable::from(data).draw(config, termwidth)
+    }
+}
+
+enum RowDiff<'a> {
+    Equal(&'a [String]),
+    Added(&'a [String]),
+    Removed(&'a [String]),
+    Changed(&'a [String], &'a [String]),
+}
+
+fn diff_row(marker: &str, row: &[String], style: &TextStyle) -> Vec<NuTableCell> {
+    let mut cells = Vec::with_capacity(row.len() + 1);
+    cells.push(CellInfo::new(paint(marker, style)));
+    cells.extend(row.iter().map(|cell| CellInfo::new(paint(cell, style))));
+
+    cells
+}
+
+fn paint(text: &str, style: &TextStyle) -> String {
+    match style.color_style {
+        Some(color) => color.paint(text).to_string(),
+        None => text.to_owned(),
+    }
+}
+
+/// Aligns `old` and `new` by a longest common subsequence over whole rows
+/// and pairs every removed run with the added run following it, cell-level
+/// changes being more useful than a remove/add pair.
+fn diff_rows<'a>(old: &'a [Vec<String>], new: &'a [Vec<String>]) -> Vec<RowDiff<'a>> {
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = Vec::new();
+    let mut removed: Vec<&[String]> = Vec::new();
+    let mut added: Vec<&[String]> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    loop {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            flush_changes(&mut out, &mut removed, &mut added);
+            out.push(RowDiff::Equal(&old[i]));
+            i += 1;
+            j += 1;
+        } else if j < new.len() && (i == old.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            added.push(&new[j]);
+            j += 1;
+        } else if i < old.len() {
+            removed.push(&old[i]);
+            i += 1;
+        } else {
+            break;
+        }
+    }
+    flush_changes(&mut out, &mut removed, &mut added);
+
+    out
+}
+
+fn flush_changes<'a>(
+    out: &mut Vec<RowDiff<'a>>,
+    removed: &mut Vec<&'a [String]>,
+    added: &mut Vec<&'a [String]>,
+) {
+    let changes = removed.len().min(added.len());
+    for (old, new) in removed.drain(..changes).zip(added.drain(..changes)) {
+        out.push(RowDiff::Changed(old, new));
+    }
+    out.extend(removed.drain(..).map(RowDiff::Removed));
+    out.extend(added.drain(..).map(RowDiff::Added));
+}
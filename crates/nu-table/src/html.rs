@@ -0,0 +1,158 @@
+use crate::NuTableCell;
+use nu_color_config::{Alignment, TextStyle};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// An HTML renderer which produces a semantic `<table>` from the same cell
+/// data the terminal renderer uses, mapping [`TextStyle`]s to inline CSS.
+#[derive(Debug, Clone)]
+pub struct HtmlTable {
+    rows: Vec<Vec<NuTableCell>>,
+    with_header: bool,
+    header_style: Option<TextStyle>,
+    column_styles: HashMap<usize, TextStyle>,
+    cell_styles: HashMap<(usize, usize), TextStyle>,
+}
+
+impl HtmlTable {
+    /// Creates a renderer over the given rows; when `with_header` is set the
+    /// first row is emitted as `<thead>` with `<th>` cells.
+    pub fn new(rows: Vec<Vec<NuTableCell>>, with_header: bool) -> Self {
+        Self {
+            rows,
+            with_header,
+            header_style: None,
+            column_styles: HashMap::new(),
+            cell_styles: HashMap::new(),
+        }
+    }
+
+    pub fn set_header_style(&mut self, style: TextStyle) {
+        self.header_style = Some(style);
+    }
+
+    pub fn set_column_style(&mut self, column: usize, style: TextStyle) {
+        self.column_styles.insert(column, style);
+    }
+
+    pub fn insert_style(&mut self, pos: (usize, usize), style: TextStyle) {
+        self.cell_styles.insert(pos, style);
+    }
+
+    /// Renders the table as `<table>/<thead>/<tbody>` markup.
+    pub fn draw(&self) -> String {
+        let mut out = String::from("<table>");
+        let mut rows = self.rows.iter().enumerate();
+
+        if self.with_header {
+            if let Some((_, header)) = rows.next() {
+                out.push_str("<thead><tr>");
+                for cell in header {
+                    push_cell(&mut out, "th", cell.as_ref(), self.header_style.as_ref());
+                }
+                out.push_str("</tr></thead>");
+            }
+        }
+
+        out.push_str("<tbody>");
+        for (row, columns) in rows {
+            out.push_str("<tr>");
+            for (col, cell) in columns.iter().enumerate() {
+                let style = self
+                    .cell_styles
+                    .get(&(row, col))
+                    .or_else(|| self.column_styles.get(&col));
+                push_cell(&mut out, "td", cell.as_ref(), style);
+            }
+            out.push_str("</tr>");
+        }
+        out.push_str("</tbody></table>");
+
+        out
+    }
+}
+
+fn push_cell(out: &mut String, tag: &str, text: &str, style: Option<&TextStyle>) {
+    let css = style.map(style_to_css).unwrap_or_default();
+    if css.is_empty() {
+        let _ = write!(out, "<{tag}>{}</{tag}>", escape_html(text));
+    } else {
+        let _ = write!(out, "<{tag} style=\"{css}\">{}</{tag}>", escape_html(text));
+    }
+}
+
+/// Maps a [`TextStyle`] to a CSS declaration list for a `style` attribute.
+pub fn style_to_css(style: &TextStyle) -> String {
+    let mut css = Vec::new();
+
+    match style.alignment {
+        Alignment::Left => {}
+        Alignment::Center => css.push(String::from("text-align:center")),
+        Alignment::Right => css.push(String::from("text-align:right")),
+    }
+
+    if let Some(style) = style.color_style {
+        if let Some(color) = style.foreground.and_then(color_to_css) {
+            css.push(format!("color:{color}"));
+        }
+        if let Some(color) = style.background.and_then(color_to_css) {
+            css.push(format!("background-color:{color}"));
+        }
+        if style.is_bold {
+            css.push(String::from("font-weight:bold"));
+        }
+        if style.is_italic {
+            css.push(String::from("font-style:italic"));
+        }
+        if style.is_underline {
+            css.push(String::from("text-decoration:underline"));
+        }
+    }
+
+    css.join(";")
+}
+
+fn color_to_css(color: nu_ansi_term::Color) -> Option<String> {
+    use nu_ansi_term::Color;
+
+    let css = match color {
+        Color::Black => "black",
+        Color::DarkGray => "dimgray",
+        Color::Red => "red",
+        Color::LightRed => "lightcoral",
+        Color::Green => "green",
+        Color::LightGreen => "lightgreen",
+        Color::Yellow => "yellow",
+        Color::LightYellow => "lightyellow",
+        Color::Blue => "blue",
+        Color::LightBlue => "lightblue",
+        Color::Purple => "purple",
+        Color::LightPurple => "mediumpurple",
+        Color::Magenta => "magenta",
+        Color::LightMagenta => "violet",
+        Color::Cyan => "cyan",
+        Color::LightCyan => "lightcyan",
+        Color::White => "white",
+        Color::LightGray => "lightgray",
+        Color::Rgb(r, g, b) => return Some(format!("#{r:02x}{g:02x}{b:02x}")),
+        // 256-color palette indices have no direct CSS counterpart
+        _ => return None,
+    };
+
+    Some(css.to_string())
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
@@ -1,15 +1,26 @@
+mod diff;
+mod html;
+pub mod perf;
+mod streaming;
+mod style_rules;
 mod table;
 mod table_theme;
 mod types;
 mod unstructured_table;
 mod util;
+mod vertical;
 
 pub mod common;
 
 pub use common::{StringResult, TableResult};
+pub use diff::TableDiff;
 pub use nu_color_config::TextStyle;
-pub use table::{NuTable, NuTableCell, NuTableConfig};
+pub use html::{style_to_css, HtmlTable};
+pub use streaming::StreamingTable;
+pub use style_rules::StyleRule;
+pub use table::{ColumnAlignment, NuTable, NuTableCell, NuTableConfig, WidthProfile};
 pub use table_theme::TableTheme;
 pub use types::{CollapsedTable, ExpandedTable, JustTable, TableOpts, TableOutput};
 pub use unstructured_table::UnstructuredTable;
 pub use util::*;
+pub use vertical::VerticalTable;
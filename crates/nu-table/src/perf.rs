@@ -0,0 +1,114 @@
+//! Internal instrumentation for the table layout, enabled with the `perf`
+//! feature; the counters are meant to guide the "todo: optimize" spots in
+//! `util.rs` instead of guessing.
+//!
+//! With the feature disabled every hook is an empty inline function, so the
+//! layout code carries no overhead.
+
+#[cfg(feature = "perf")]
+pub use enabled::{report, reset, PerfReport};
+
+#[cfg(feature = "perf")]
+pub(crate) use enabled::{count_cells, count_truncate, count_wrap, record_draw, record_layout, start};
+
+#[cfg(not(feature = "perf"))]
+pub(crate) use disabled::{count_cells, count_truncate, count_wrap, record_draw, record_layout, start};
+
+#[cfg(feature = "perf")]
+mod enabled {
+    use std::{
+        sync::atomic::{AtomicU64, Ordering},
+        time::{Duration, Instant},
+    };
+
+    static CELLS_MEASURED: AtomicU64 = AtomicU64::new(0);
+    static WRAPS_PERFORMED: AtomicU64 = AtomicU64::new(0);
+    static TRUNCATIONS_PERFORMED: AtomicU64 = AtomicU64::new(0);
+    static LAYOUT_NANOS: AtomicU64 = AtomicU64::new(0);
+    static DRAW_NANOS: AtomicU64 = AtomicU64::new(0);
+
+    /// A snapshot of the layout counters; see [`report`].
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct PerfReport {
+        /// Cells whose text width was measured.
+        pub cells_measured: u64,
+        /// Strings wrapped to a width.
+        pub wraps_performed: u64,
+        /// Strings cut to a width.
+        pub truncations_performed: u64,
+        /// Time spent computing column widths.
+        pub layout_time: Duration,
+        /// Time spent in [`NuTable::draw`](crate::NuTable::draw) overall.
+        pub draw_time: Duration,
+    }
+
+    /// The counters accumulated since the start (or the last [`reset`]).
+    pub fn report() -> PerfReport {
+        PerfReport {
+            cells_measured: CELLS_MEASURED.load(Ordering::Relaxed),
+            wraps_performed: WRAPS_PERFORMED.load(Ordering::Relaxed),
+            truncations_performed: TRUNCATIONS_PERFORMED.load(Ordering::Relaxed),
+            layout_time: Duration::from_nanos(LAYOUT_NANOS.load(Ordering::Relaxed)),
+            draw_time: Duration::from_nanos(DRAW_NANOS.load(Ordering::Relaxed)),
+        }
+    }
+
+    /// Zeroes all counters, e.g. between measured scenarios.
+    pub fn reset() {
+        CELLS_MEASURED.store(0, Ordering::Relaxed);
+        WRAPS_PERFORMED.store(0, Ordering::Relaxed);
+        TRUNCATIONS_PERFORMED.store(0, Ordering::Relaxed);
+        LAYOUT_NANOS.store(0, Ordering::Relaxed);
+        DRAW_NANOS.store(0, Ordering::Relaxed);
+    }
+
+    pub(crate) fn count_cells(count: usize) {
+        CELLS_MEASURED.fetch_add(count as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn count_wrap() {
+        WRAPS_PERFORMED.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn count_truncate() {
+        TRUNCATIONS_PERFORMED.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn start() -> Instant {
+        Instant::now()
+    }
+
+    pub(crate) fn record_layout(start: Instant) {
+        LAYOUT_NANOS.fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_draw(start: Instant) {
+        DRAW_NANOS.fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+    }
+}
+
+#[cfg(not(feature = "perf"))]
+mod disabled {
+    /// A zero-sized stand-in for [`std::time::Instant`].
+    pub(crate) struct Timer;
+
+    #[inline(always)]
+    pub(crate) fn count_cells(_: usize) {}
+
+    #[inline(always)]
+    pub(crate) fn count_wrap() {}
+
+    #[inline(always)]
+    pub(crate) fn count_truncate() {}
+
+    #[inline(always)]
+    pub(crate) fn start() -> Timer {
+        Timer
+    }
+
+    #[inline(always)]
+    pub(crate) fn record_layout(_: Timer) {}
+
+    #[inline(always)]
+    pub(crate) fn record_draw(_: Timer) {}
+}
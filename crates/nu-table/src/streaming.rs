@@ -0,0 +1,178 @@
+use crate::{
+    table::{build_width, get_total_width2, SetDimensions},
+    NuTable, NuTableCell, NuTableConfig,
+};
+use tabled::{
+    builder::Builder,
+    grid::{config::ColoredConfig, records::vec_records::VecRecords},
+    settings::{object::Columns, Modify, Padding, Width},
+    Table,
+};
+
+/// A table renderer which emits output incrementally, so huge streams start
+/// printing before they are fully collected.
+///
+/// Column widths are computed once from a sampled prefix of the data (see
+/// [`start`](Self::start)); every row pushed afterwards is rendered against
+/// those fixed widths, with longer cells truncated. The produced blocks
+/// concatenate to the same table the collected renderer would draw, except
+/// that rows arriving after the sample cannot widen the columns anymore.
+#[derive(Debug, Clone)]
+pub struct StreamingTable {
+    config: NuTableConfig,
+    termwidth: usize,
+    indent: (usize, usize),
+    widths: Vec<usize>,
+    numbering: Option<usize>,
+}
+
+impl StreamingTable {
+    /// Creates a renderer; nothing is emitted until [`start`](Self::start).
+    pub fn new(config: NuTableConfig, termwidth: usize) -> Self {
+        Self {
+            config,
+            termwidth,
+            indent: (1, 1),
+            widths: Vec::new(),
+            numbering: None,
+        }
+    }
+
+    pub fn set_indent(&mut self, left: usize, right: usize) {
+        self.indent = (left, right);
+    }
+
+    /// Numbers the rows continuously from `offset` on, prepending a `#`
+    /// index column; rows pushed after the sample continue counting where
+    /// the sample stopped.
+    pub fn set_row_numbering(&mut self, offset: usize) {
+        self.numbering = Some(offset);
+    }
+
+    /// Renders the head of the table: the top border, the header (when
+    /// given) and the sampled rows, fixing the column widths for the rest of
+    /// the stream.
+    ///
+    /// Returns `None` when there is nothing to sample widths from or the
+    /// sampled widths don't fit `termwidth`; the caller is expected to fall
+    /// back to the collected renderer and its column truncation then.
+    pub fn start(
+        &mut self,
+        mut header: Option<Vec<NuTableCell>>,
+        mut sample: Vec<Vec<NuTableCell>>,
+    ) -> Option<String> {
+        if let Some(next) = &mut self.numbering {
+            if let Some(header) = &mut header {
+                header.insert(0, NuTableCell::new(String::from("#")));
+            }
+            for row in &mut sample {
+                row.insert(0, NuTableCell::new(next.to_string()));
+                *next += 1;
+            }
+        }
+
+        let mut rows = Vec::with_capacity(sample.len() + 1);
+        if let Some(header) = header {
+            rows.push(header);
+        }
+        rows.extend(sample);
+        if rows.is_empty() {
+            return None;
+        }
+
+        let pad = self.indent.0 + self.indent.1;
+        let widths = build_width(&VecRecords::new(rows.clone()), pad);
+        if get_total_width2(&widths, &self.border_config()) > self.termwidth {
+            return None;
+        }
+        self.widths = widths;
+
+        let mut table = NuTable::from(rows);
+        table.set_indent(self.indent.0, self.indent.1);
+        let rendered = table.draw(self.config.clone(), self.termwidth)?;
+
+        match self.has_bottom_border() {
+            // The bottom border moves to the end of the stream; `finish`
+            // redraws it.
+            true => rendered.rsplit_once('\n').map(|(head, _)| head.to_string()),
+            false => Some(rendered),
+        }
+    }
+
+    /// Renders one row against the widths fixed by [`start`](Self::start),
+    /// truncating cells which outgrow their sampled column.
+    pub fn push(&mut self, mut row: Vec<NuTableCell>) -> String {
+        if let Some(next) = &mut self.numbering {
+            row.insert(0, NuTableCell::new(next.to_string()));
+            *next += 1;
+        }
+
+        let pad = self.indent.0 + self.indent.1;
+
+        let mut table = Builder::from(vec![row]).build();
+        table.with(Padding::new(self.indent.0, self.indent.1, 0, 0));
+        let mut theme = self.config.theme.get_theme();
+        theme.set_horizontals(std::collections::HashMap::new());
+        table.with(theme);
+        for (col, width) in self.widths.iter().enumerate() {
+            table.with(
+                Modify::new(Columns::single(col)).with(Width::truncate(width.saturating_sub(pad))),
+            );
+        }
+        table.with(SetDimensions(self.widths.clone()));
+
+        // A single-row table carries its own top and bottom border; inside
+        // the stream only the content lines belong to the output.
+        let rendered = table.to_string();
+        let borders = self.border_config();
+        let borders = borders.get_borders();
+        let mut lines: Vec<&str> = rendered.lines().collect();
+        if borders.has_top() && !lines.is_empty() {
+            lines.remove(0);
+        }
+        if borders.has_bottom() && !lines.is_empty() {
+            lines.pop();
+        }
+        lines.join("\n")
+    }
+
+    /// Renders the bottom border; an empty string for themes without one.
+    pub fn finish(&self) -> String {
+        let config = self.border_config();
+        let borders = config.get_borders();
+        if !borders.has_bottom() {
+            return String::new();
+        }
+
+        let mut line = String::new();
+        if let Some(c) = borders.bottom_left {
+            line.push(c);
+        }
+        for (col, width) in self.widths.iter().enumerate() {
+            if col > 0 {
+                if let Some(c) = borders.bottom_intersection {
+                    line.push(c);
+                }
+            }
+            if let Some(c) = borders.bottom {
+                line.push_str(&c.to_string().repeat(*width));
+            }
+        }
+        if let Some(c) = borders.bottom_right {
+            line.push(c);
+        }
+
+        line
+    }
+
+    /// The border configuration of the configured theme.
+    fn border_config(&self) -> ColoredConfig {
+        let mut table = Table::new([[""]]);
+        table.with(self.config.theme.get_theme());
+        table.get_config().clone()
+    }
+
+    fn has_bottom_border(&self) -> bool {
+        self.border_config().get_borders().has_bottom()
+    }
+}
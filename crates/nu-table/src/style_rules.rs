@@ -0,0 +1,63 @@
+use fancy_regex::Regex;
+use nu_color_config::TextStyle;
+use nu_protocol::ShellError;
+
+/// A conditional styling rule: cells of a named column get a style applied
+/// when their rendered value matches the rule's condition, enabling e.g.
+/// heatmap-like coloring of numeric columns (see
+/// [`NuTable::add_style_rule`](crate::NuTable::add_style_rule)).
+#[derive(Debug, Clone)]
+pub struct StyleRule {
+    pub(crate) column: String,
+    pub(crate) style: TextStyle,
+    condition: StyleCondition,
+}
+
+#[derive(Debug, Clone)]
+enum StyleCondition {
+    Range(f64, f64),
+    Matches(Regex),
+}
+
+impl StyleRule {
+    /// Styles values which parse as a number within the inclusive range.
+    pub fn range(column: impl Into<String>, min: f64, max: f64, style: TextStyle) -> Self {
+        Self {
+            column: column.into(),
+            style,
+            condition: StyleCondition::Range(min, max),
+        }
+    }
+
+    /// Styles values matching the regex.
+    pub fn matches(
+        column: impl Into<String>,
+        pattern: &str,
+        style: TextStyle,
+    ) -> Result<Self, ShellError> {
+        let regex = Regex::new(pattern).map_err(|err| ShellError::GenericError {
+            error: format!("invalid style rule pattern '{pattern}'"),
+            msg: err.to_string(),
+            span: None,
+            help: None,
+            inner: vec![],
+        })?;
+
+        Ok(Self {
+            column: column.into(),
+            style,
+            condition: StyleCondition::Matches(regex),
+        })
+    }
+
+    pub(crate) fn applies_to(&self, value: &str) -> bool {
+        match &self.condition {
+            StyleCondition::Range(min, max) => value
+                .trim()
+                .parse::<f64>()
+                .map(|value| value >= *min && value <= *max)
+                .unwrap_or(false),
+            StyleCondition::Matches(regex) => regex.is_match(value).unwrap_or(false),
+        }
+    }
+}
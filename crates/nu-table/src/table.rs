@@ -1,9 +1,21 @@
-use crate::{convert_style, table_theme::TableTheme};
+use crate::{
+    convert_style,
+    style_rules::StyleRule,
+    table_theme::TableTheme,
+    util::{
+        line_width_graphemes, string_truncate, string_truncate_with_policy,
+        string_width_graphemes, TruncationPolicy,
+    },
+};
 use nu_ansi_term::Style;
 use nu_color_config::TextStyle;
 use nu_protocol::TrimStrategy;
 use nu_utils::strip_ansi_unlikely;
-use std::{cmp::min, collections::HashMap};
+use std::{
+    cmp::min,
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 use tabled::{
     builder::Builder,
     grid::{
@@ -12,13 +24,16 @@ use tabled::{
         config::{AlignmentHorizontal, ColoredConfig, Entity, EntityMap, Position},
         dimension::CompleteDimensionVecRecords,
         records::{
-            vec_records::{CellInfo, VecRecords},
+            vec_records::{Cell, CellInfo, StrWithWidth, VecRecords},
             ExactRecords, PeekableRecords, Records, Resizable,
         },
     },
     settings::{
-        formatting::AlignmentStrategy, object::Segment, peaker::Peaker, themes::ColumnNames, Color,
-        Modify, Padding, Settings, TableOption, Width,
+        formatting::AlignmentStrategy,
+        object::{Columns, Segment},
+        peaker::Peaker,
+        themes::ColumnNames,
+        Color, Modify, Padding, Settings, TableOption, Width,
     },
     Table,
 };
@@ -29,6 +44,20 @@ pub struct NuTable {
     data: NuRecords,
     styles: Styles,
     alignments: Alignments,
+    priorities: HashMap<usize, usize>,
+    summary: Option<Vec<NuTableCell>>,
+    formats: ColumnFormats,
+    decimals: HashSet<usize>,
+    style_rules: Vec<StyleRule>,
+    grapheme_widths: bool,
+    abbreviation: Option<usize>,
+    header_every: Option<usize>,
+    width_profile: Option<WidthProfile>,
+    bidi_isolation: bool,
+    collapse_multiline: Option<TextStyle>,
+    min_widths: HashMap<usize, usize>,
+    max_widths: HashMap<usize, usize>,
+    truncation: TruncationPolicy,
     indent: (usize, usize),
 }
 
@@ -43,6 +72,35 @@ struct Styles {
     data_is_set: bool,
 }
 
+type FormatFn = Arc<dyn Fn(&str) -> String + Send + Sync>;
+
+#[derive(Default, Clone)]
+struct ColumnFormats(HashMap<usize, FormatFn>);
+
+impl std::fmt::Debug for ColumnFormats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ColumnFormats")
+            .field(&self.0.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// A column width profile captured from one table and reusable by later
+/// renders of the same structural table; see [`NuTable::width_profile`].
+#[derive(Debug, Clone)]
+pub struct WidthProfile(Vec<usize>);
+
+/// A horizontal alignment for a whole column; see
+/// [`NuTable::set_column_alignment`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnAlignment {
+    Left,
+    Center,
+    Right,
+    /// Right alignment with the values padded so their decimal points line up.
+    Decimal,
+}
+
 #[derive(Debug, Clone)]
 struct Alignments {
     data: AlignmentHorizontal,
@@ -58,6 +116,20 @@ impl NuTable {
         Self {
             data: VecRecords::new(vec![vec![CellInfo::default(); count_columns]; count_rows]),
             styles: Styles::default(),
+            priorities: HashMap::default(),
+            summary: None,
+            formats: ColumnFormats::default(),
+            decimals: HashSet::default(),
+            style_rules: Vec::new(),
+            grapheme_widths: false,
+            abbreviation: None,
+            header_every: None,
+            width_profile: None,
+            bidi_isolation: false,
+            collapse_multiline: None,
+            min_widths: HashMap::default(),
+            max_widths: HashMap::default(),
+            truncation: TruncationPolicy::default(),
             indent: (1, 1),
             alignments: Alignments {
                 data: AlignmentHorizontal::Left,
@@ -137,6 +209,197 @@ impl NuTable {
         self.alignments.data = convert_alignment(style.alignment);
     }
 
+    /// Sets the horizontal alignment for a whole column.
+    ///
+    /// Unlike [`set_column_style`](Self::set_column_style) this also offers
+    /// [`ColumnAlignment::Decimal`] for numeric columns.
+    pub fn set_column_alignment(&mut self, column: usize, alignment: ColumnAlignment) {
+        let alignment = match alignment {
+            ColumnAlignment::Left => AlignmentHorizontal::Left,
+            ColumnAlignment::Center => AlignmentHorizontal::Center,
+            ColumnAlignment::Right => AlignmentHorizontal::Right,
+            ColumnAlignment::Decimal => {
+                self.decimals.insert(column);
+                AlignmentHorizontal::Right
+            }
+        };
+
+        self.alignments.columns.insert(column, alignment);
+    }
+
+    /// Sets a hook which reformats every data value of a column before the
+    /// table is laid out, e.g. to round numbers or append a unit.
+    pub fn set_column_format<F>(&mut self, column: usize, format: F)
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        self.formats.0.insert(column, Arc::new(format));
+    }
+
+    /// Prepends an index column numbering the rows from `offset` on, so
+    /// callers don't have to assemble the column manually and pages of a
+    /// streamed table can keep continuous numbering.
+    ///
+    /// Call it once the data is complete and before per-column styling, as
+    /// the index becomes column `0`. With `with_header` the first row gets a
+    /// `#` heading instead of a number; the column is styled via
+    /// [`set_index_style`](Self::set_index_style) together with `with_index`
+    /// in [`NuTableConfig`].
+    pub fn enumerate_rows(&mut self, offset: usize, with_header: bool) {
+        let records = std::mem::take(&mut self.data);
+        let mut inner: Vec<Vec<_>> = records.into();
+
+        for (row, columns) in inner.iter_mut().enumerate() {
+            let text = if with_header && row == 0 {
+                String::from("#")
+            } else {
+                (offset + row - with_header as usize).to_string()
+            };
+            columns.insert(0, CellInfo::new(text));
+        }
+
+        self.data = VecRecords::new(inner);
+    }
+
+    /// Sets how cells which don't fit their column are cut: the truncation
+    /// indicator and whether the start or the end of the string survives.
+    ///
+    /// A custom policy replaces the `TrimStrategy` of [`NuTableConfig`] when
+    /// the table doesn't fit the terminal.
+    pub fn set_truncation_policy(&mut self, policy: TruncationPolicy) {
+        self.truncation = policy;
+    }
+
+    /// Sets a minimum width (in content characters, excluding the padding)
+    /// below which a column is not shrunken when the table doesn't fit the
+    /// terminal; other columns are cut further instead.
+    pub fn set_minimum_column_width(&mut self, column: usize, width: usize) {
+        self.min_widths.insert(column, width);
+    }
+
+    /// Caps a column at `width` content characters even when a cell is
+    /// wider, so a single long value (a hash, a URL) doesn't reflow the
+    /// whole table; outgrowing cells are cut by the truncation policy.
+    pub fn set_maximum_column_width(&mut self, column: usize, width: usize) {
+        self.max_widths.insert(column, width);
+    }
+
+    /// Pins a column to exactly `width` content characters, regardless of
+    /// its data; a shorthand for an equal minimum and maximum.
+    pub fn set_fixed_column_width(&mut self, column: usize, width: usize) {
+        self.min_widths.insert(column, width);
+        self.max_widths.insert(column, width);
+    }
+
+    /// Measures the column widths of the current data as a reusable profile.
+    ///
+    /// When the same structural table is rendered repeatedly (watch loops,
+    /// streaming batches), the profile captured from one batch can be passed
+    /// to the next via [`set_width_profile`](Self::set_width_profile), which
+    /// skips re-measuring every cell and keeps the columns from jumping
+    /// between batches.
+    pub fn width_profile(&self) -> WidthProfile {
+        WidthProfile(build_width(&self.data, self.indent.0 + self.indent.1))
+    }
+
+    /// Lays the table out against a previously captured [`WidthProfile`]
+    /// instead of measuring the data again; cells outgrowing their profiled
+    /// column are truncated.
+    ///
+    /// The profile is ignored when it doesn't match the column count, when
+    /// it doesn't fit the terminal, or when column priorities are set.
+    pub fn set_width_profile(&mut self, profile: WidthProfile) {
+        self.width_profile = Some(profile);
+    }
+
+    /// Re-emits the header row after every `every` data rows, so the columns
+    /// stay readable when a long table scrolls past in a plain terminal
+    /// without a pager.
+    ///
+    /// Repeated headers keep the header's style and get their own separator
+    /// lines; the option only takes effect for tables drawn with a header.
+    pub fn set_header_repeat(&mut self, every: usize) {
+        self.header_every = Some(every);
+    }
+
+    /// Abbreviates the table to the first and last `rows` data rows with a
+    /// `...` row in between, as a head/tail preview of long data.
+    ///
+    /// The dropped middle rows still belong to the table, so column widths
+    /// are derived from both ends instead of whatever slice the caller
+    /// happened to keep. Tables with at most `2 * rows + 1` data rows are
+    /// rendered unchanged.
+    pub fn set_abbreviation(&mut self, rows: usize) {
+        self.abbreviation = Some(rows);
+    }
+
+    /// Makes [`draw`](Self::draw) measure cells by grapheme clusters instead
+    /// of single codepoints, so emoji sequences (ZWJ, flags, skin tones)
+    /// count as one glyph and don't misalign the borders.
+    pub fn set_grapheme_width_mode(&mut self, on: bool) {
+        self.grapheme_widths = on;
+    }
+
+    /// Makes [`draw`](Self::draw) wrap cells containing right-to-left text
+    /// in bidi isolates (U+2068/U+2069), so Hebrew or Arabic content is
+    /// reordered within its own cell and doesn't shift the borders around.
+    ///
+    /// The isolate characters are invisible and don't count against the
+    /// cell's width.
+    pub fn set_bidi_isolation(&mut self, on: bool) {
+        self.bidi_isolation = on;
+    }
+
+    /// Collapses multi-line cells to their first line plus a `(+3 lines)`
+    /// marker, so every row stays one line high in dense tables.
+    ///
+    /// Only the color of `style` is applied to the marker; single-line cells
+    /// are left untouched.
+    pub fn set_multiline_collapse(&mut self, style: TextStyle) {
+        self.collapse_multiline = Some(style);
+    }
+
+    /// Adds a conditional styling rule, evaluated against the rendered
+    /// values while the table is drawn.
+    ///
+    /// Rules address columns by their header name, so they only take effect
+    /// for tables drawn with a header; only the color of the rule's style is
+    /// applied.
+    pub fn add_style_rule(&mut self, rule: StyleRule) {
+        self.style_rules.push(rule);
+    }
+
+    /// Sets a summary row (e.g. totals or counts) rendered below the data
+    /// inside the bottom border, separated by its own horizontal line.
+    pub fn set_summary_row(&mut self, columns: Vec<String>, style: TextStyle) {
+        let row = self.data.count_rows();
+
+        if let Some(color) = style.color_style {
+            let color = AnsiColor::from(convert_style(color));
+            self.styles.data.insert(Entity::Row(row), color);
+            self.styles.data_is_set = true;
+        }
+
+        let alignment = convert_alignment(style.alignment);
+        if alignment != self.alignments.data {
+            for column in 0..columns.len() {
+                self.alignments.cells.insert((row, column), alignment);
+            }
+        }
+
+        self.summary = Some(columns.into_iter().map(CellInfo::new).collect());
+    }
+
+    /// Sets a priority for a column.
+    ///
+    /// When the table does not fit the terminal width and any priority is
+    /// set, the least important columns collapse into a trailing `...`
+    /// column (with a hint below the table) instead of the usual
+    /// right-to-left truncation. Columns without a priority default to `0`.
+    pub fn set_column_priority(&mut self, column: usize, priority: usize) {
+        self.priorities.insert(column, priority);
+    }
+
     pub fn set_indent(&mut self, left: usize, right: usize) {
         self.indent = (left, right);
     }
@@ -148,15 +411,123 @@ impl NuTable {
     /// Converts a table to a String.
     ///
     /// It returns None in case where table cannot be fit to a terminal width.
-    pub fn draw(self, config: NuTableConfig, termwidth: usize) -> Option<String> {
-        build_table(
+    pub fn draw(mut self, config: NuTableConfig, termwidth: usize) -> Option<String> {
+        let timer = crate::perf::start();
+
+        if !self.formats.0.is_empty() || !self.decimals.is_empty() {
+            format_columns(
+                &mut self.data,
+                &self.formats,
+                &self.decimals,
+                config.with_header,
+            );
+        }
+
+        if let Some(style) = &self.collapse_multiline {
+            collapse_multiline_cells(&mut self.data, style);
+        }
+
+        if let Some(rows) = self.abbreviation {
+            if let Some(row) = abbreviate_rows(&mut self.data, rows, config.with_header) {
+                for column in 0..self.data.count_columns() {
+                    self.alignments
+                        .cells
+                        .insert((row, column), AlignmentHorizontal::Center);
+                }
+            }
+        }
+
+        let mut repeated_headers = Vec::new();
+        if let Some(every) = self.header_every {
+            if config.with_header && every > 0 && self.data.count_rows() > 1 {
+                repeated_headers = repeat_header_rows(&mut self.data, every);
+                for &row in &repeated_headers {
+                    for column in 0..self.data.count_columns() {
+                        self.alignments
+                            .cells
+                            .insert((row, column), self.alignments.header);
+                    }
+                    self.styles
+                        .data
+                        .insert(Entity::Row(row), self.styles.header.clone());
+                    self.styles.data_is_set = true;
+                }
+            }
+        }
+
+        if config.with_header && !self.style_rules.is_empty() {
+            apply_style_rules(&self.data, &self.style_rules, &mut self.styles);
+        }
+
+        let with_summary = self.summary.is_some();
+        if let Some(row) = self.summary.take() {
+            push_row(&mut self.data, row);
+        }
+
+        if self.grapheme_widths {
+            remeasure_by_graphemes(&mut self.data);
+        }
+
+        if self.bidi_isolation {
+            isolate_bidi_cells(&mut self.data);
+        }
+
+        let table = build_table(
             self.data,
             config,
             self.alignments,
             self.styles,
+            self.priorities,
+            with_summary,
+            repeated_headers,
+            self.width_profile,
+            self.min_widths,
+            self.max_widths,
+            self.truncation,
             termwidth,
             self.indent,
-        )
+        );
+        crate::perf::record_draw(timer);
+
+        table
+    }
+
+    /// Renders the table as a GitHub flavored markdown table.
+    ///
+    /// The first row becomes the header (markdown tables always carry one)
+    /// and the alignment row is derived from the per-column alignment
+    /// settings, so the output matches what the terminal renderer decides.
+    pub fn draw_markdown(mut self) -> String {
+        if self.data.count_rows() == 0 || self.data.count_columns() == 0 {
+            return String::new();
+        }
+
+        if !self.formats.0.is_empty() {
+            format_columns(&mut self.data, &self.formats, &HashSet::new(), true);
+        }
+
+        let data: Vec<Vec<_>> = self.data.into();
+        let mut out = String::new();
+
+        for (row, columns) in data.iter().enumerate() {
+            out.push('|');
+            for cell in columns {
+                out.push_str(&format!(" {} |", markdown_cell(cell.as_ref())));
+            }
+            out.push('\n');
+
+            if row == 0 {
+                out.push('|');
+                for col in 0..columns.len() {
+                    let alignment = self.alignments.columns.get(&col).copied();
+                    out.push_str(&format!(" {} |", markdown_marker(alignment)));
+                }
+                out.push('\n');
+            }
+        }
+        out.pop();
+
+        out
     }
 
     /// Return a total table width.
@@ -203,11 +574,19 @@ impl Default for NuTableConfig {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn build_table(
     mut data: NuRecords,
     cfg: NuTableConfig,
     alignments: Alignments,
     styles: Styles,
+    priorities: HashMap<usize, usize>,
+    with_summary: bool,
+    repeated_headers: Vec<usize>,
+    width_profile: Option<WidthProfile>,
+    min_widths: HashMap<usize, usize>,
+    max_widths: HashMap<usize, usize>,
+    truncation: TruncationPolicy,
     termwidth: usize,
     indent: (usize, usize),
 ) -> Option<String> {
@@ -216,7 +595,45 @@ fn build_table(
     }
 
     let pad = indent.0 + indent.1;
-    let widths = maybe_truncate_columns(&mut data, &cfg.theme, termwidth, pad);
+
+    let width_profile = width_profile.filter(|profile| {
+        priorities.is_empty()
+            && profile.0.len() == data.count_columns()
+            && get_total_width2(&profile.0, &get_config(&cfg.theme, false, None)) <= termwidth
+    });
+
+    let with_policy = priorities.is_empty()
+        && (!min_widths.is_empty() || !max_widths.is_empty() || truncation.is_custom());
+    let timer = crate::perf::start();
+    let (widths, hint, widths_fit) = match width_profile {
+        Some(profile) => (profile.0, None, WidthsFit::PinTruncate),
+        None if with_policy => {
+            // the policy shrinks columns itself instead of dropping them,
+            // so it starts from the natural widths
+            let mut widths = build_width(&data, pad);
+            fit_widths_with_hints(&mut widths, &min_widths, &max_widths, pad, termwidth, &cfg);
+            truncate_cells_to_widths(&mut data, &widths, pad, &truncation);
+            (widths, None, WidthsFit::PinTruncate)
+        }
+        None if priorities.is_empty() => {
+            // most CI and script output is plain ASCII in an ASCII theme;
+            // its cached widths are exact, so a fitting table can pin them
+            // and skip the renderer's per-cell unicode estimation
+            match ascii_fast_path_widths(&data, &cfg, pad, termwidth) {
+                Some(widths) => (widths, None, WidthsFit::Pin),
+                None => {
+                    let widths = maybe_truncate_columns(&mut data, &cfg.theme, termwidth, pad);
+                    (widths, None, WidthsFit::Estimate)
+                }
+            }
+        }
+        None => {
+            let (widths, hint) =
+                collapse_columns_by_priority(&mut data, &priorities, &cfg.theme, termwidth, pad, &cfg);
+            (widths, hint, WidthsFit::Estimate)
+        }
+    };
+    crate::perf::record_layout(timer);
     if widths.is_empty() {
         return None;
     }
@@ -225,15 +642,37 @@ fn build_table(
         duplicate_row(&mut data, 0);
     }
 
-    draw_table(data, alignments, styles, widths, cfg, termwidth, indent)
+    let mut table = draw_table(
+        data,
+        alignments,
+        styles,
+        widths,
+        cfg,
+        with_summary,
+        repeated_headers,
+        widths_fit,
+        termwidth,
+        indent,
+    )?;
+
+    if let Some(hint) = hint {
+        table.push('\n');
+        table.push_str(&string_truncate(&hint, termwidth));
+    }
+
+    Some(table)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn draw_table(
     data: NuRecords,
     alignments: Alignments,
     styles: Styles,
     widths: Vec<usize>,
     cfg: NuTableConfig,
+    with_summary: bool,
+    repeated_headers: Vec<usize>,
+    widths_fit: WidthsFit,
     termwidth: usize,
     indent: (usize, usize),
 ) -> Option<String> {
@@ -242,21 +681,49 @@ fn draw_table(
     let with_footer = with_header && cfg.with_footer;
     let sep_color = cfg.split_color;
     let border_header = cfg.header_on_border;
+    let summary_line = with_summary.then(|| data.count_rows() - 1 - with_footer as usize);
 
     let data: Vec<Vec<_>> = data.into();
     let mut table = Builder::from(data).build();
 
     set_indent(&mut table, indent.0, indent.1);
-    load_theme(&mut table, &cfg.theme, with_footer, with_header, sep_color);
+    load_theme(
+        &mut table,
+        &cfg.theme,
+        with_footer,
+        with_header,
+        summary_line,
+        &repeated_headers,
+        sep_color,
+    );
     align_table(&mut table, alignments, with_index, with_header, with_footer);
     colorize_table(&mut table, styles, with_index, with_header, with_footer);
 
-    let width_ctrl = TableWidthCtrl::new(widths, cfg, termwidth);
+    match widths_fit {
+        WidthsFit::Estimate => {
+            let width_ctrl = TableWidthCtrl::new(widths, cfg, termwidth);
 
-    if with_header && border_header {
-        set_border_head(&mut table, with_footer, width_ctrl);
-    } else {
-        table.with(width_ctrl);
+            if with_header && border_header {
+                set_border_head(&mut table, with_footer, width_ctrl);
+            } else {
+                table.with(width_ctrl);
+            }
+        }
+        WidthsFit::Pin => {
+            table.with(SetDimensions(widths));
+        }
+        WidthsFit::PinTruncate => {
+            // a cached profile pins the layout; cells are truncated to their
+            // profiled column instead of widening the table
+            let pad = indent.0 + indent.1;
+            for (col, width) in widths.iter().enumerate() {
+                table.with(
+                    Modify::new(Columns::single(col))
+                        .with(Width::truncate(width.saturating_sub(pad))),
+                );
+            }
+            table.with(SetDimensions(widths));
+        }
     }
 
     table_to_string(table, termwidth)
@@ -298,6 +765,51 @@ fn table_to_string(table: Table, termwidth: usize) -> Option<String> {
     }
 }
 
+/// How [`draw_table`] treats the column widths computed by [`build_table`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WidthsFit {
+    /// The renderer re-estimates the widths and trims the table itself.
+    Estimate,
+    /// The widths are pinned as they are; every cell is known to fit.
+    Pin,
+    /// The widths are pinned and cells are cut to their column.
+    PinTruncate,
+}
+
+/// The natural column widths when the table qualifies for the ASCII fast
+/// path and fits the terminal; `None` sends it through the usual estimation.
+///
+/// Plain ASCII text carries no ANSI sequences and every byte is one column
+/// wide, so the widths cached at cell construction are exact and the
+/// renderer doesn't need to re-measure anything.
+fn ascii_fast_path_widths(
+    data: &NuRecords,
+    cfg: &NuTableConfig,
+    pad: usize,
+    termwidth: usize,
+) -> Option<Vec<usize>> {
+    if cfg.expand || cfg.header_on_border {
+        return None;
+    }
+
+    if !cfg.theme.is_ascii() || !is_plain_ascii(data) {
+        return None;
+    }
+
+    let widths = build_width(data, pad);
+    let fits = get_total_width2(&widths, &get_config(&cfg.theme, false, None)) <= termwidth;
+
+    fits.then_some(widths)
+}
+
+/// Whether every cell is printable ASCII, which rules out ANSI sequences
+/// (ESC is a control byte) and anything wider or narrower than one column.
+fn is_plain_ascii(data: &NuRecords) -> bool {
+    data.iter()
+        .flatten()
+        .all(|cell| cell.as_ref().bytes().all(|b| matches!(b, b' '..=b'~' | b'\n')))
+}
+
 struct TableWidthCtrl {
     width: Vec<usize>,
     cfg: NuTableConfig,
@@ -437,18 +949,36 @@ fn load_theme(
     theme: &TableTheme,
     with_footer: bool,
     with_header: bool,
+    summary_line: Option<usize>,
+    repeated_headers: &[usize],
     sep_color: Option<Style>,
 ) {
     let mut theme = theme.get_theme();
+    let header_line = theme.get_horizontal(1);
 
     if !with_header {
         theme.set_horizontals(std::collections::HashMap::new());
     } else if with_footer && table.count_rows() > 2 {
-        if let Some(line) = theme.get_horizontal(1) {
+        if let Some(line) = header_line {
             theme.insert_horizontal(table.count_rows() - 1, line);
         }
     }
 
+    // the summary row gets its own separator, reusing the header's line so it
+    // is present even for themes without horizontal data lines
+    if let (Some(row), Some(line)) = (summary_line, header_line) {
+        theme.insert_horizontal(row, line);
+    }
+
+    // repeated header rows are separated from the data on both sides, just
+    // like the leading header
+    if let Some(line) = header_line {
+        for &row in repeated_headers {
+            theme.insert_horizontal(row, line);
+            theme.insert_horizontal(row + 1, line);
+        }
+    }
+
     table.with(theme);
 
     if let Some(style) = sep_color {
@@ -627,6 +1157,106 @@ fn truncate_columns_by_columns(
     widths
 }
 
+// VERSION where columns carry priority weights; the LEAST IMPORTANT columns collapse into a
+// trailing `...` column until the rest fits, and a hint below the table names what was hidden.
+fn collapse_columns_by_priority(
+    data: &mut NuRecords,
+    priorities: &HashMap<usize, usize>,
+    theme: &TableTheme,
+    termwidth: usize,
+    pad: usize,
+    cfg: &NuTableConfig,
+) -> (Vec<usize>, Option<String>) {
+    const TRAILING_COLUMN_WIDTH: usize = 3;
+
+    let config = get_config(theme, false, None);
+    let mut widths = build_width(&*data, pad);
+    let total_width = get_total_width2(&widths, &config);
+    if total_width <= termwidth {
+        return (widths, None);
+    }
+
+    let vertical = config.get_borders().has_vertical() as usize;
+
+    // The lowest priority collapses first; the rightmost column among equals.
+    let mut order: Vec<usize> = (0..data.count_columns()).collect();
+    order.sort_by_key(|&col| {
+        (
+            priorities.get(&col).copied().unwrap_or(0),
+            std::cmp::Reverse(col),
+        )
+    });
+
+    let mut total = total_width;
+    let mut keep = vec![true; widths.len()];
+    let mut hidden = Vec::new();
+    for &col in &order {
+        if total + TRAILING_COLUMN_WIDTH + pad + vertical <= termwidth {
+            break;
+        }
+
+        keep[col] = false;
+        hidden.push(col);
+        total -= widths[col] + vertical;
+    }
+
+    // not even a single column together with the `...` column fits
+    if hidden.len() == keep.len() || total + TRAILING_COLUMN_WIDTH + pad + vertical > termwidth {
+        return (vec![], None);
+    }
+
+    hidden.sort_unstable();
+    let hint = collapse_hint(data, &hidden, cfg.with_header);
+
+    retain_columns(data, &keep);
+    let mut i = 0;
+    widths.retain(|_| {
+        let keep = keep[i];
+        i += 1;
+        keep
+    });
+
+    push_empty_column(data);
+    widths.push(TRAILING_COLUMN_WIDTH + pad);
+
+    (widths, Some(hint))
+}
+
+fn collapse_hint(data: &NuRecords, hidden: &[usize], with_header: bool) -> String {
+    let mut hint = match hidden.len() {
+        1 => String::from("... and 1 more column"),
+        count => format!("... and {count} more columns"),
+    };
+
+    if with_header {
+        let names = hidden
+            .iter()
+            .map(|&col| data.get_text((0, col)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        hint.push_str(": ");
+        hint.push_str(&names);
+    }
+
+    hint
+}
+
+fn retain_columns(data: &mut NuRecords, keep: &[bool]) {
+    let records = std::mem::take(data);
+    let mut inner: Vec<Vec<_>> = records.into();
+
+    for row in &mut inner {
+        let mut col = 0;
+        row.retain(|_| {
+            let keep = keep[col];
+            col += 1;
+            keep
+        });
+    }
+
+    *data = VecRecords::new(inner);
+}
+
 /// The same as [`tabled::peaker::PriorityMax`] but prioritizes left columns first in case of equal width.
 #[derive(Debug, Default, Clone)]
 pub struct PriorityMax;
@@ -642,7 +1272,7 @@ impl Peaker for PriorityMax {
     }
 }
 
-fn get_total_width2(widths: &[usize], cfg: &ColoredConfig) -> usize {
+pub(crate) fn get_total_width2(widths: &[usize], cfg: &ColoredConfig) -> usize {
     let total = widths.iter().sum::<usize>();
     let countv = cfg.count_vertical(widths.len());
     let margin = cfg.get_margin();
@@ -652,7 +1282,7 @@ fn get_total_width2(widths: &[usize], cfg: &ColoredConfig) -> usize {
 
 fn get_config(theme: &TableTheme, with_header: bool, color: Option<Style>) -> ColoredConfig {
     let mut table = Table::new([[""]]);
-    load_theme(&mut table, theme, false, with_header, color);
+    load_theme(&mut table, theme, false, with_header, None, &[], color);
     table.get_config().clone()
 }
 
@@ -668,6 +1298,326 @@ fn push_empty_column(data: &mut NuRecords) {
     *data = VecRecords::new(inner);
 }
 
+// replaces cells whose codepoint based width disagrees with the grapheme
+// cluster measurement, keeping the pre-computed widths the layout relies on
+fn remeasure_by_graphemes(data: &mut NuRecords) {
+    let records = std::mem::take(data);
+    let mut inner: Vec<Vec<_>> = records.into();
+
+    for row in &mut inner {
+        for cell in row {
+            let width = string_width_graphemes(cell.as_ref());
+            if width != Cell::width(cell) {
+                *cell = grapheme_cell(cell.as_ref().to_owned(), width);
+            }
+        }
+    }
+
+    *data = VecRecords::new(inner);
+}
+
+/// Wraps every cell containing right-to-left text in a bidi isolate pair,
+/// keeping the measured width of the visible text, as the isolates are
+/// invisible.
+fn isolate_bidi_cells(data: &mut NuRecords) {
+    const FSI: char = '\u{2068}';
+    const PDI: char = '\u{2069}';
+
+    let records = std::mem::take(data);
+    let mut inner: Vec<Vec<_>> = records.into();
+
+    for row in &mut inner {
+        for cell in row {
+            if !cell.as_ref().chars().any(is_rtl_char) {
+                continue;
+            }
+
+            let width = Cell::width(cell);
+            let text: String = cell
+                .as_ref()
+                .lines()
+                .map(|line| format!("{FSI}{line}{PDI}"))
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            let lines = if text.contains('\n') {
+                text.lines()
+                    .map(|line| {
+                        let width = crate::string_width(line.trim_matches(|c| c == FSI || c == PDI));
+                        StrWithWidth::new(std::borrow::Cow::Owned(line.to_owned()), width)
+                    })
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+            *cell = CellInfo::exact(text, width, lines);
+        }
+    }
+
+    *data = VecRecords::new(inner);
+}
+
+/// Whether a character belongs to a right-to-left script (Hebrew, Arabic
+/// and their presentation forms).
+fn is_rtl_char(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0590}'..='\u{08FF}' | '\u{FB1D}'..='\u{FDFF}' | '\u{FE70}'..='\u{FEFF}'
+    )
+}
+
+fn grapheme_cell(text: String, width: usize) -> NuTableCell {
+    let lines = if text.contains('\n') {
+        text.lines()
+            .map(|line| {
+                StrWithWidth::new(
+                    std::borrow::Cow::Owned(line.to_owned()),
+                    line_width_graphemes(line),
+                )
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    CellInfo::exact(text, width, lines)
+}
+
+fn apply_style_rules(data: &NuRecords, rules: &[StyleRule], styles: &mut Styles) {
+    if data.count_rows() == 0 {
+        return;
+    }
+
+    for rule in rules {
+        let column = (0..data.count_columns()).find(|&col| data[0][col].as_ref() == rule.column);
+        let (Some(col), Some(color)) = (column, rule.style.color_style) else {
+            continue;
+        };
+        let color = AnsiColor::from(convert_style(color));
+
+        for row in 1..data.count_rows() {
+            if rule.applies_to(data[row][col].as_ref()) {
+                styles.data.insert(Entity::Cell(row, col), color.clone());
+                styles.data_is_set = true;
+            }
+        }
+    }
+}
+
+fn format_columns(
+    data: &mut NuRecords,
+    formats: &ColumnFormats,
+    decimals: &HashSet<usize>,
+    with_header: bool,
+) {
+    let records = std::mem::take(data);
+    let mut inner: Vec<Vec<_>> = records.into();
+    let skip = with_header as usize;
+
+    for row in inner.iter_mut().skip(skip) {
+        for (col, cell) in row.iter_mut().enumerate() {
+            if let Some(format) = formats.0.get(&col) {
+                *cell = CellInfo::new(format(cell.as_ref()));
+            }
+        }
+    }
+
+    for &col in decimals {
+        align_decimal_point(&mut inner, col, skip);
+    }
+
+    *data = VecRecords::new(inner);
+}
+
+// pads values on the right so that together with the column's right
+// alignment the decimal points end up on top of each other
+fn align_decimal_point(rows: &mut [Vec<NuTableCell>], column: usize, skip: usize) {
+    fn fraction_width(text: &str) -> usize {
+        match text.rfind('.') {
+            Some(i) => text[i..].chars().count(),
+            None => 0,
+        }
+    }
+
+    let max_fraction = rows
+        .iter()
+        .skip(skip)
+        .filter_map(|row| row.get(column))
+        .map(|cell| fraction_width(cell.as_ref()))
+        .max()
+        .unwrap_or(0);
+    if max_fraction == 0 {
+        return;
+    }
+
+    for row in rows.iter_mut().skip(skip) {
+        if let Some(cell) = row.get_mut(column) {
+            let padding = max_fraction - fraction_width(cell.as_ref());
+            if padding > 0 {
+                let mut text = cell.as_ref().to_owned();
+                text.push_str(&" ".repeat(padding));
+                *cell = CellInfo::new(text);
+            }
+        }
+    }
+}
+
+/// Applies the per-column width hints and shrinks `widths` to fit
+/// `termwidth` without cutting a column below its minimum, taking from the
+/// widest shrinkable column first; minimums win over the terminal when both
+/// cannot be satisfied.
+fn fit_widths_with_hints(
+    widths: &mut [usize],
+    min_widths: &HashMap<usize, usize>,
+    max_widths: &HashMap<usize, usize>,
+    pad: usize,
+    termwidth: usize,
+    cfg: &NuTableConfig,
+) {
+    let config = get_config(&cfg.theme, false, None);
+    let floor = |col: usize| min_widths.get(&col).map(|min| min + pad).unwrap_or(pad + 1);
+
+    for (col, width) in widths.iter_mut().enumerate() {
+        if let Some(max) = max_widths.get(&col) {
+            *width = std::cmp::min(*width, max + pad);
+        }
+        *width = std::cmp::max(*width, floor(col));
+    }
+
+    while get_total_width2(widths, &config) > termwidth {
+        let col = (0..widths.len())
+            .filter(|&col| widths[col] > floor(col))
+            .max_by_key(|&col| widths[col]);
+        match col {
+            Some(col) => widths[col] -= 1,
+            None => break,
+        }
+    }
+}
+
+/// Cuts every cell line which outgrew its column to the column's content
+/// width, according to the truncation policy.
+fn truncate_cells_to_widths(
+    data: &mut NuRecords,
+    widths: &[usize],
+    pad: usize,
+    truncation: &TruncationPolicy,
+) {
+    let records = std::mem::take(data);
+    let mut inner: Vec<Vec<_>> = records.into();
+
+    for row in &mut inner {
+        for (col, cell) in row.iter_mut().enumerate() {
+            let budget = widths[col].saturating_sub(pad);
+            if Cell::width(cell) <= budget {
+                continue;
+            }
+
+            let text = cell
+                .as_ref()
+                .lines()
+                .map(|line| string_truncate_with_policy(line, budget, truncation))
+                .collect::<Vec<_>>()
+                .join("\n");
+            *cell = CellInfo::new(text);
+        }
+    }
+
+    *data = VecRecords::new(inner);
+}
+
+/// Collapses every multi-line cell to its first line plus a `(+N lines)`
+/// marker colored with `style`.
+fn collapse_multiline_cells(data: &mut NuRecords, style: &TextStyle) {
+    let records = std::mem::take(data);
+    let mut inner: Vec<Vec<_>> = records.into();
+
+    for row in &mut inner {
+        for cell in row.iter_mut() {
+            let hidden = cell.as_ref().lines().count().saturating_sub(1);
+            if hidden == 0 {
+                continue;
+            }
+
+            let first = cell.as_ref().lines().next().unwrap_or("").to_owned();
+            let mut marker = match hidden {
+                1 => String::from("(+1 line)"),
+                _ => format!("(+{hidden} lines)"),
+            };
+            if let Some(color) = style.color_style {
+                marker = color.paint(marker).to_string();
+            }
+
+            let buf = if first.is_empty() {
+                marker
+            } else {
+                format!("{first} {marker}")
+            };
+
+            *cell = CellInfo::new(buf);
+        }
+    }
+
+    *data = VecRecords::new(inner);
+}
+
+/// Keeps the first and last `rows` data rows and puts a `...` row in
+/// between, returning its index; `None` when the data is short enough to
+/// show as a whole.
+fn abbreviate_rows(data: &mut NuRecords, rows: usize, with_header: bool) -> Option<usize> {
+    let head = with_header as usize;
+    if data.count_rows() <= head + rows * 2 + 1 {
+        return None;
+    }
+
+    let records = std::mem::take(data);
+    let mut inner: Vec<Vec<_>> = records.into();
+
+    let count_columns = inner[0].len();
+    let tail = inner.split_off(inner.len() - rows);
+    inner.truncate(head + rows);
+    inner.push(vec![CellInfo::new(String::from("...")); count_columns]);
+    inner.extend(tail);
+
+    *data = VecRecords::new(inner);
+
+    Some(head + rows)
+}
+
+/// Re-inserts a copy of the header row after every `every` data rows,
+/// returning the indices of the inserted copies; no copy is placed behind
+/// the last data row.
+fn repeat_header_rows(data: &mut NuRecords, every: usize) -> Vec<usize> {
+    let records = std::mem::take(data);
+    let mut inner: Vec<Vec<_>> = records.into();
+
+    let header = inner[0].clone();
+    let mut repeated = Vec::new();
+    let mut row = 1 + every;
+    while row < inner.len() {
+        inner.insert(row, header.clone());
+        repeated.push(row);
+        row += every + 1;
+    }
+
+    *data = VecRecords::new(inner);
+
+    repeated
+}
+
+fn push_row(data: &mut NuRecords, mut row: Vec<NuTableCell>) {
+    let records = std::mem::take(data);
+    let mut inner: Vec<Vec<_>> = records.into();
+
+    if let Some(first) = inner.first() {
+        row.resize(first.len(), CellInfo::default());
+    }
+    inner.push(row);
+
+    *data = VecRecords::new(inner);
+}
+
 fn duplicate_row(data: &mut NuRecords, row: usize) {
     let records = std::mem::take(data);
     let mut inner: Vec<Vec<_>> = records.into();
@@ -689,6 +1639,19 @@ fn truncate_columns(data: &mut NuRecords, count: usize) {
     *data = VecRecords::new(inner);
 }
 
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', "<br>")
+}
+
+fn markdown_marker(alignment: Option<AlignmentHorizontal>) -> &'static str {
+    match alignment {
+        Some(AlignmentHorizontal::Left) => ":---",
+        Some(AlignmentHorizontal::Center) => ":---:",
+        Some(AlignmentHorizontal::Right) => "---:",
+        None => "---",
+    }
+}
+
 fn convert_alignment(alignment: nu_color_config::Alignment) -> AlignmentHorizontal {
     match alignment {
         nu_color_config::Alignment::Center => AlignmentHorizontal::Center,
@@ -705,7 +1668,7 @@ impl<R, D> TableOption<R, D, ColoredConfig> for SetAlignment {
     }
 }
 
-struct SetDimensions(Vec<usize>);
+pub(crate) struct SetDimensions(pub(crate) Vec<usize>);
 
 impl<R> TableOption<R, CompleteDimensionVecRecords<'_>, ColoredConfig> for SetDimensions {
     fn change(self, _: &mut R, _: &mut ColoredConfig, dims: &mut CompleteDimensionVecRecords<'_>) {
@@ -714,10 +1677,11 @@ impl<R> TableOption<R, CompleteDimensionVecRecords<'_>, ColoredConfig> for SetDi
 }
 
 // it assumes no spans is used.
-fn build_width(records: &NuRecords, pad: usize) -> Vec<usize> {
+pub(crate) fn build_width(records: &NuRecords, pad: usize) -> Vec<usize> {
     use tabled::grid::records::vec_records::Cell;
 
     let count_columns = records.count_columns();
+    crate::perf::count_cells(records.count_rows() * count_columns);
     let mut widths = vec![0; count_columns];
     for columns in records.iter_rows() {
         for (col, cell) in columns.iter().enumerate() {
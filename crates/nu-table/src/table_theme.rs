@@ -1,3 +1,4 @@
+use nu_protocol::{Record, ShellError, Value};
 use tabled::settings::style::{HorizontalLine, Line, RawStyle, Style};
 
 #[derive(Debug, Clone)]
@@ -216,6 +217,108 @@ impl TableTheme {
         }
     }
 
+    /// Builds a theme from a record of border characters, so config can
+    /// define themes beyond the hardcoded set.
+    ///
+    /// Recognized keys are the edges (`top`, `bottom`, `left`, `right`),
+    /// the inner lines (`vertical`, `horizontal`), the corners (`top_left`,
+    /// `top_right`, `bottom_left`, `bottom_right`) and the intersections
+    /// (`top_intersection`, `bottom_intersection`, `left_intersection`,
+    /// `right_intersection`, `intersection`). Every key is optional and an
+    /// empty string removes the border; the `horizontal` characters double
+    /// as the header separator.
+    pub fn from_record(record: &Record) -> Result<TableTheme, ShellError> {
+        let mut full_theme: RawStyle = Style::empty().into();
+        let mut horizontal = None;
+        let mut intersection = None;
+        let mut left_intersection = None;
+        let mut right_intersection = None;
+
+        for (key, value) in record {
+            let c = border_char(value)?;
+            match key.as_str() {
+                "top" => {
+                    full_theme.set_top(c);
+                }
+                "bottom" => {
+                    full_theme.set_bottom(c);
+                }
+                "left" => {
+                    full_theme.set_left(c);
+                }
+                "right" => {
+                    full_theme.set_right(c);
+                }
+                "vertical" => {
+                    full_theme.set_vertical(c);
+                }
+                "horizontal" => {
+                    horizontal = c;
+                    full_theme.set_horizontal(c);
+                }
+                "top_left" => {
+                    full_theme.set_corner_top_left(c);
+                }
+                "top_right" => {
+                    full_theme.set_corner_top_right(c);
+                }
+                "bottom_left" => {
+                    full_theme.set_corner_bottom_left(c);
+                }
+                "bottom_right" => {
+                    full_theme.set_corner_bottom_right(c);
+                }
+                "top_intersection" => {
+                    full_theme.set_intersection_top(c);
+                }
+                "bottom_intersection" => {
+                    full_theme.set_intersection_bottom(c);
+                }
+                "left_intersection" => {
+                    left_intersection = c;
+                    full_theme.set_intersection_left(c);
+                }
+                "right_intersection" => {
+                    right_intersection = c;
+                    full_theme.set_intersection_right(c);
+                }
+                "intersection" => {
+                    intersection = c;
+                    full_theme.set_intersection(c);
+                }
+                _ => {
+                    return Err(ShellError::GenericError {
+                        error: format!("unexpected key '{key}' in the table theme record"),
+                        msg: "not a border location".into(),
+                        span: Some(value.span()),
+                        help: None,
+                        inner: vec![],
+                    });
+                }
+            }
+        }
+
+        // like the hardcoded themes: no horizontal lines between data rows,
+        // but a header separator built from the inner line characters
+        let mut theme = full_theme.clone();
+        theme.set_horizontal(None);
+        theme.set_intersection_left(None);
+        theme.set_intersection_right(None);
+        theme.set_intersection(None);
+        if horizontal.is_some() {
+            theme.insert_horizontal(
+                1,
+                Line::new(horizontal, intersection, left_intersection, right_intersection),
+            );
+        }
+
+        Ok(Self {
+            theme,
+            full_theme,
+            has_inner: true,
+        })
+    }
+
     pub fn has_top_line(&self) -> bool {
         self.theme.get_top().is_some()
             || self.theme.get_top_intersection().is_some()
@@ -245,6 +348,15 @@ impl TableTheme {
         self.full_theme.get_borders().has_horizontal()
     }
 
+    /// Whether every border character of the theme is plain ASCII; such
+    /// themes qualify for the renderer's ASCII fast path.
+    pub fn is_ascii(&self) -> bool {
+        borders_are_ascii(&self.theme)
+            && borders_are_ascii(&self.full_theme)
+            && line_is_ascii(self.theme.get_horizontal(1))
+            && line_is_ascii(self.full_theme.get_horizontal(1))
+    }
+
     pub fn get_theme_full(&self) -> RawStyle {
         self.full_theme.clone()
     }
@@ -253,3 +365,55 @@ impl TableTheme {
         self.theme.clone()
     }
 }
+
+fn borders_are_ascii(style: &RawStyle) -> bool {
+    let borders = style.get_borders();
+    [
+        borders.top,
+        borders.top_left,
+        borders.top_right,
+        borders.top_intersection,
+        borders.bottom,
+        borders.bottom_left,
+        borders.bottom_right,
+        borders.bottom_intersection,
+        borders.horizontal,
+        borders.vertical,
+        borders.intersection,
+        borders.left,
+        borders.left_intersection,
+        borders.right,
+        borders.right_intersection,
+    ]
+    .iter()
+    .flatten()
+    .all(char::is_ascii)
+}
+
+fn line_is_ascii(line: Option<Line>) -> bool {
+    let line = match line {
+        Some(line) => tabled::grid::config::HorizontalLine::from(line),
+        None => return true,
+    };
+
+    [line.main, line.intersection, line.left, line.right]
+        .iter()
+        .flatten()
+        .all(char::is_ascii)
+}
+
+fn border_char(value: &Value) -> Result<Option<char>, ShellError> {
+    let text = value.coerce_str()?;
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (None, _) => Ok(None),
+        (Some(c), None) => Ok(Some(c)),
+        _ => Err(ShellError::GenericError {
+            error: String::from("expected a single border character"),
+            msg: format!("got '{text}'"),
+            span: Some(value.span()),
+            help: None,
+            inner: vec![],
+        }),
+    }
+}
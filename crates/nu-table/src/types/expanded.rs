@@ -11,14 +11,30 @@ use crate::{
 use nu_color_config::{Alignment, StyleComputer, TextStyle};
 use nu_engine::column::get_columns;
 use nu_protocol::{Config, Record, ShellError, Span, Value};
-use std::{cmp::max, collections::HashMap};
+use std::{cmp::max, collections::HashMap, sync::Arc};
 use tabled::grid::config::Position;
 
-#[derive(Debug, Clone)]
+type SummaryFn = Arc<dyn Fn(&Value) -> String + Send + Sync>;
+
+#[derive(Clone)]
 pub struct ExpandedTable {
     expand_limit: Option<usize>,
     flatten: bool,
     flatten_sep: String,
+    summary_style: Option<TextStyle>,
+    summary_format: Option<SummaryFn>,
+}
+
+impl std::fmt::Debug for ExpandedTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExpandedTable")
+            .field("expand_limit", &self.expand_limit)
+            .field("flatten", &self.flatten)
+            .field("flatten_sep", &self.flatten_sep)
+            .field("summary_style", &self.summary_style)
+            .field("summary_format", &self.summary_format.is_some())
+            .finish()
+    }
 }
 
 impl ExpandedTable {
@@ -27,9 +43,27 @@ impl ExpandedTable {
             expand_limit,
             flatten,
             flatten_sep,
+            summary_style: None,
+            summary_format: None,
         }
     }
 
+    /// Sets the style of the text a nested value collapses to once the
+    /// expansion limit is reached.
+    pub fn set_summary_style(&mut self, style: TextStyle) {
+        self.summary_style = Some(style);
+    }
+
+    /// Sets a hook producing the text a nested value collapses to once the
+    /// expansion limit is reached, e.g. `[table 3×2]` instead of the
+    /// default abbreviation.
+    pub fn set_summary_format<F>(&mut self, format: F)
+    where
+        F: Fn(&Value) -> String + Send + Sync + 'static,
+    {
+        self.summary_format = Some(Arc::new(format));
+    }
+
     pub fn build_value(self, item: &Value, opts: TableOpts<'_>) -> NuText {
         expanded_table_entry2(item, Cfg { opts, format: self })
     }
@@ -394,7 +428,14 @@ fn expand_table_value(
 ) -> Result<Option<(String, bool)>, ShellError> {
     let is_limited = matches!(cfg.format.expand_limit, Some(0));
     if is_limited {
-        return Ok(Some((value_to_string_clean(value, cfg), false)));
+        let (mut text, style) = summarize_value(value, cfg);
+        if cfg.format.summary_style.is_some() {
+            if let Some(color) = style.color_style {
+                text = color.paint(text).to_string();
+            }
+        }
+
+        return Ok(Some((text, false)));
     }
 
     let span = value.span();
@@ -464,7 +505,7 @@ fn expanded_table_entry(item: &Value, header: &str, cfg: Cfg<'_>) -> NuText {
 fn expanded_table_entry2(item: &Value, cfg: Cfg<'_>) -> NuText {
     let is_limit_reached = matches!(cfg.format.expand_limit, Some(0));
     if is_limit_reached {
-        return nu_value_to_string_clean(item, cfg.opts.config, cfg.opts.style_computer);
+        return summarize_value(item, &cfg);
     }
 
     let span = item.span();
@@ -578,12 +619,22 @@ fn create_table_cfg(cfg: &Cfg<'_>, out: &TableOutput) -> crate::NuTableConfig {
     )
 }
 
-fn value_to_string(value: &Value, cfg: &Cfg<'_>) -> String {
-    nu_value_to_string(value, cfg.opts.config, cfg.opts.style_computer).0
+/// The text a nested value collapses to once the expansion limit is
+/// reached, with the summary hooks of [`ExpandedTable`] applied.
+fn summarize_value(value: &Value, cfg: &Cfg<'_>) -> NuText {
+    let (text, style) = match &cfg.format.summary_format {
+        Some(format) => ((format)(value), TextStyle::default()),
+        None => nu_value_to_string_clean(value, cfg.opts.config, cfg.opts.style_computer),
+    };
+
+    match cfg.format.summary_style {
+        Some(style) => (text, style),
+        None => (text, style),
+    }
 }
 
-fn value_to_string_clean(value: &Value, cfg: &Cfg<'_>) -> String {
-    nu_value_to_string_clean(value, cfg.opts.config, cfg.opts.style_computer).0
+fn value_to_string(value: &Value, cfg: &Cfg<'_>) -> String {
+    nu_value_to_string(value, cfg.opts.config, cfg.opts.style_computer).0
 }
 
 fn value_to_wrapped_string(value: &Value, cfg: &Cfg<'_>, value_width: usize) -> String {
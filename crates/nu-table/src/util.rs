@@ -13,6 +13,44 @@ pub fn string_width(text: &str) -> usize {
     string_width_multiline(text)
 }
 
+/// Returns the displayed width of a string, measuring grapheme clusters
+/// instead of single codepoints, so emoji sequences (ZWJ, flags, skin tones)
+/// count as one glyph.
+pub fn string_width_graphemes(text: &str) -> usize {
+    text.lines().map(line_width_graphemes).max().unwrap_or(0)
+}
+
+pub(crate) fn line_width_graphemes(line: &str) -> usize {
+    use unicode_segmentation::UnicodeSegmentation;
+
+    nu_utils::strip_ansi_unlikely(line)
+        .graphemes(true)
+        .map(grapheme_width)
+        .sum()
+}
+
+fn grapheme_width(grapheme: &str) -> usize {
+    const ZWJ: char = '\u{200D}';
+
+    // sequences joined with a ZWJ or carrying a skin tone render as a single
+    // double-width glyph, as do flags built from two regional indicators
+    if grapheme
+        .chars()
+        .any(|c| c == ZWJ || matches!(c, '\u{1F3FB}'..='\u{1F3FF}'))
+    {
+        return 2;
+    }
+    if grapheme
+        .chars()
+        .next()
+        .is_some_and(|c| matches!(c, '\u{1F1E6}'..='\u{1F1FF}'))
+    {
+        return 2;
+    }
+
+    unicode_width::UnicodeWidthStr::width(grapheme)
+}
+
 pub fn string_wrap(text: &str, width: usize, keep_words: bool) -> String {
     // todo: change me...
     //
@@ -23,6 +61,12 @@ pub fn string_wrap(text: &str, width: usize, keep_words: bool) -> String {
         return String::new();
     }
 
+    if text.contains('\x1b') {
+        return string_wrap_keeping_ansi(text, width, keep_words);
+    }
+
+    crate::perf::count_wrap();
+
     let wrap = if keep_words {
         Width::wrap(width).keep_words()
     } else {
@@ -37,9 +81,133 @@ pub fn string_wrap(text: &str, width: usize, keep_words: bool) -> String {
         .to_string()
 }
 
+/// Wraps `text` to `width` like [`string_wrap`], but closes the ANSI styling
+/// open at the end of each produced line and reopens it on the continuation
+/// line, so wrapped colored content doesn't bleed into the table borders.
+pub fn string_wrap_keeping_ansi(text: &str, width: usize, keep_words: bool) -> String {
+    const RESET: &str = "\u{1b}[0m";
+
+    if text.is_empty() || width == 0 {
+        return String::new();
+    }
+
+    crate::perf::count_wrap();
+
+    let mut out = String::new();
+    for (i, line) in text.lines().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+
+        // the styles which were opened and not closed again; they get
+        // reopened after every line break
+        let mut active: Vec<String> = Vec::new();
+        let mut line_width = 0;
+
+        for chunk in split_ansi_chunks(line) {
+            let chunk_width: usize = chunk
+                .iter()
+                .filter_map(|token| match token {
+                    AnsiToken::Char(c) => unicode_width::UnicodeWidthChar::width(*c),
+                    AnsiToken::Escape(_) => None,
+                })
+                .sum();
+
+            let is_word = chunk
+                .iter()
+                .find_map(|token| match token {
+                    AnsiToken::Char(c) => Some(!c.is_whitespace()),
+                    AnsiToken::Escape(_) => None,
+                })
+                .unwrap_or(false);
+            if keep_words && is_word && line_width + chunk_width > width && chunk_width <= width {
+                break_line(&mut out, &active, RESET);
+                line_width = 0;
+            }
+
+            for token in chunk {
+                match token {
+                    AnsiToken::Escape(esc) => {
+                        if esc == RESET || esc == "\u{1b}[m" {
+                            active.clear();
+                        } else {
+                            active.push(esc.clone());
+                        }
+                        out.push_str(&esc);
+                    }
+                    AnsiToken::Char(c) => {
+                        let char_width = unicode_width::UnicodeWidthChar::width(c).unwrap_or(0);
+                        if line_width + char_width > width {
+                            break_line(&mut out, &active, RESET);
+                            line_width = 0;
+                        }
+                        out.push(c);
+                        line_width += char_width;
+                    }
+                }
+            }
+        }
+    }
+
+    out
+}
+
+#[derive(Debug, Clone)]
+enum AnsiToken {
+    Escape(String),
+    Char(char),
+}
+
+/// Splits a line into runs of word and whitespace characters, with escape
+/// sequences riding along in stream order.
+fn split_ansi_chunks(line: &str) -> Vec<Vec<AnsiToken>> {
+    let mut chunks: Vec<Vec<AnsiToken>> = Vec::new();
+    let mut chunk: Vec<AnsiToken> = Vec::new();
+    let mut in_word = false;
+
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            let mut esc = String::from(c);
+            for c in chars.by_ref() {
+                esc.push(c);
+                // an escape sequence ends with its final byte `@`..`~`
+                if c != '[' && ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+            chunk.push(AnsiToken::Escape(esc));
+            continue;
+        }
+
+        if chunk.iter().any(|t| matches!(t, AnsiToken::Char(_))) && c.is_whitespace() == in_word {
+            chunks.push(std::mem::take(&mut chunk));
+        }
+        in_word = !c.is_whitespace();
+        chunk.push(AnsiToken::Char(c));
+    }
+    if !chunk.is_empty() {
+        chunks.push(chunk);
+    }
+
+    chunks
+}
+
+fn break_line(out: &mut String, active: &[String], reset: &str) {
+    if !active.is_empty() {
+        out.push_str(reset);
+    }
+    out.push('\n');
+    for esc in active {
+        out.push_str(esc);
+    }
+}
+
 pub fn string_truncate(text: &str, width: usize) -> String {
     // todo: change me...
 
+    crate::perf::count_truncate();
+
     let line = match text.lines().next() {
         Some(first_line) => first_line,
         None => return String::new(),
@@ -48,9 +216,97 @@ pub fn string_truncate(text: &str, width: usize) -> String {
     Truncate::truncate_text(line, width).into_owned()
 }
 
+/// A policy for cutting text to a width: the indicator marking the cut and
+/// which end of the string survives it.
+///
+/// The default policy cuts the tail without an indicator, matching
+/// [`string_truncate`].
+#[derive(Debug, Clone, Default)]
+pub struct TruncationPolicy {
+    /// An indicator (e.g. `…` or `...`) put where text was cut away; its
+    /// width counts against the budget.
+    pub suffix: String,
+    /// Keeps the end of the string and cuts its start instead, with the
+    /// indicator leading.
+    pub keep_end: bool,
+}
+
+impl TruncationPolicy {
+    /// Whether the policy differs from the plain-cut default.
+    pub(crate) fn is_custom(&self) -> bool {
+        !self.suffix.is_empty() || self.keep_end
+    }
+}
+
+/// Truncates the first line of `text` to `width` according to `policy`.
+///
+/// When the start of the string is cut, ANSI styling is stripped, as the
+/// sequences opening the style would be cut away with it.
+pub fn string_truncate_with_policy(text: &str, width: usize, policy: &TruncationPolicy) -> String {
+    crate::perf::count_truncate();
+
+    let line = match text.lines().next() {
+        Some(first_line) => first_line,
+        None => return String::new(),
+    };
+
+    if string_width(line) <= width {
+        return line.to_owned();
+    }
+
+    let suffix_width = string_width(&policy.suffix);
+    if width <= suffix_width {
+        return Truncate::truncate_text(line, width).into_owned();
+    }
+
+    let budget = width - suffix_width;
+    if policy.keep_end {
+        let line = nu_utils::strip_ansi_unlikely(line);
+        let mut kept = 0;
+        let chars: Vec<char> = line.chars().collect();
+        let mut start = chars.len();
+        for (i, c) in chars.iter().enumerate().rev() {
+            let width = unicode_width::UnicodeWidthChar::width(*c).unwrap_or(0);
+            if kept + width > budget {
+                break;
+            }
+            kept += width;
+            start = i;
+        }
+
+        let kept: String = chars[start..].iter().collect();
+        format!("{}{kept}", policy.suffix)
+    } else {
+        let cut = Truncate::truncate_text(line, budget);
+        format!("{cut}{}", policy.suffix)
+    }
+}
+
+const DEFAULT_TAB_WIDTH: usize = 4;
+
 pub fn clean_charset(text: &str) -> String {
-    // todo: optimize, I bet it can be done in 1 path
-    text.replace('\t', "    ").replace('\r', "")
+    clean_charset_with_tab_width(text, DEFAULT_TAB_WIDTH)
+}
+
+pub fn clean_charset_with_tab_width(text: &str, tab_width: usize) -> String {
+    // This runs on every string cell, so it's a single pass over the text.
+    let mut buf = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\t' => {
+                for _ in 0..tab_width {
+                    buf.push(' ');
+                }
+            }
+            // `\n` splits multiline cells and ESC starts the sequences of
+            // already colored text; both must survive.
+            '\n' | '\x1b' => buf.push(c),
+            c if c.is_control() => {}
+            c => buf.push(c),
+        }
+    }
+
+    buf
 }
 
 pub fn colorize_space(data: &mut [Vec<CellInfo<String>>], style_computer: &StyleComputer<'_>) {
@@ -0,0 +1,93 @@
+use crate::{common::nu_value_to_string_colored, string_width, TableTheme};
+use nu_color_config::StyleComputer;
+use nu_protocol::{Config, Record, Span, Value};
+
+/// VerticalTable renders one record per block of `column: value` lines with
+/// a separator in between, like psql's expanded mode.
+///
+/// It is meant for records wider than the terminal, where the regular
+/// layout would truncate most of the columns away; the caller picks it when
+/// [`NuTable::draw`](crate::NuTable::draw) gives up on the width.
+pub struct VerticalTable {
+    records: Vec<Record>,
+}
+
+impl VerticalTable {
+    pub fn new(records: Vec<Record>) -> Self {
+        Self { records }
+    }
+
+    /// Renders the blocks to a string; column names and the separators are
+    /// colored via the `header` and `separator` styles of the style
+    /// computer, values the same way the regular table colors them.
+    pub fn draw(
+        self,
+        style_computer: &StyleComputer,
+        config: &Config,
+        theme: &TableTheme,
+        termwidth: usize,
+    ) -> String {
+        let sep_char = theme
+            .get_theme()
+            .get_horizontal(1)
+            .and_then(|line| tabled::grid::config::HorizontalLine::from(line).main)
+            .unwrap_or('-');
+
+        // the widest column name across all records aligns the values
+        let key_width = self
+            .records
+            .iter()
+            .flat_map(|record| record.columns())
+            .map(|column| string_width(column))
+            .max()
+            .unwrap_or(0);
+
+        let header_style = style_computer.compute("header", &Value::string("", Span::unknown()));
+        let separator_style = style_computer.compute("separator", &Value::nothing(Span::unknown()));
+
+        let mut blocks = Vec::with_capacity(self.records.len());
+        let mut block_width = 0;
+        for (i, record) in self.records.iter().enumerate() {
+            let mut lines = Vec::with_capacity(record.len());
+            for (column, value) in record.iter() {
+                let padding = " ".repeat(key_width - string_width(column));
+                let key = header_style.paint(column.as_str());
+                let value = nu_value_to_string_colored(value, config, style_computer);
+
+                // continuation lines of a multiline value stay aligned
+                // under its first line
+                let mut value_lines = value.lines();
+                let mut line = format!("{key}{padding}: {}", value_lines.next().unwrap_or(""));
+                block_width = block_width.max(string_width(&line).min(termwidth));
+                lines.push(line);
+                for rest in value_lines {
+                    line = format!("{}  {rest}", " ".repeat(key_width));
+                    block_width = block_width.max(string_width(&line).min(termwidth));
+                    lines.push(line);
+                }
+            }
+
+            blocks.push((format!("[ {} ]", i + 1), lines));
+        }
+
+        let mut out = String::new();
+        for (head, lines) in blocks {
+            let fill = block_width.saturating_sub(string_width(&head) + 1);
+            let separator = format!(
+                "{}{head}{}",
+                sep_char,
+                sep_char.to_string().repeat(fill.max(1))
+            );
+            out.push_str(&separator_style.paint(separator).to_string());
+            out.push('\n');
+            for line in lines {
+                out.push_str(&line);
+                out.push('\n');
+            }
+        }
+
+        out.pop();
+
+        out
+    }
+}
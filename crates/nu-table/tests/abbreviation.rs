@@ -0,0 +1,86 @@
+mod common;
+
+use common::cell;
+use nu_table::{NuTable, NuTableConfig, TableTheme as theme};
+
+fn listing() -> NuTable {
+    NuTable::from(vec![
+        vec![cell("name")],
+        vec![cell("a")],
+        vec![cell("b")],
+        vec![cell("c")],
+        vec![cell("a-long-tail-name")],
+    ])
+}
+
+#[test]
+fn test_abbreviation_keeps_both_ends() {
+    let mut table = listing();
+    table.set_abbreviation(1);
+
+    let cfg = NuTableConfig {
+        theme: theme::rounded(),
+        with_header: true,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        table.draw(cfg, 100).unwrap(),
+        "╭──────────────────╮\n\
+         │       name       │\n\
+         ├──────────────────┤\n\
+         │ a                │\n\
+         │       ...        │\n\
+         │ a-long-tail-name │\n\
+         ╰──────────────────╯"
+    );
+}
+
+#[test]
+fn test_abbreviation_leaves_short_tables_alone() {
+    let mut table = listing();
+    table.set_abbreviation(2);
+
+    let cfg = NuTableConfig {
+        theme: theme::rounded(),
+        with_header: true,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        table.draw(cfg, 100).unwrap(),
+        "╭──────────────────╮\n\
+         │       name       │\n\
+         ├──────────────────┤\n\
+         │ a                │\n\
+         │ b                │\n\
+         │ c                │\n\
+         │ a-long-tail-name │\n\
+         ╰──────────────────╯"
+    );
+}
+
+#[test]
+fn test_abbreviation_without_a_header() {
+    let mut table = NuTable::from(vec![
+        vec![cell("1"), cell("a")],
+        vec![cell("2"), cell("b")],
+        vec![cell("3"), cell("c")],
+        vec![cell("4"), cell("d")],
+    ]);
+    table.set_abbreviation(1);
+
+    let cfg = NuTableConfig {
+        theme: theme::rounded(),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        table.draw(cfg, 100).unwrap(),
+        "╭─────┬─────╮\n\
+         │ 1   │ a   │\n\
+         │ ... │ ... │\n\
+         │ 4   │ d   │\n\
+         ╰─────┴─────╯"
+    );
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use common::{cell, create_row};
+use nu_table::{NuTable, NuTableConfig, TableTheme as theme};
+
+#[test]
+fn test_ascii_theme_detection() {
+    assert!(theme::basic().is_ascii());
+    assert!(theme::ascii_rounded().is_ascii());
+    assert!(theme::none().is_ascii());
+    assert!(!theme::rounded().is_ascii());
+    assert!(!theme::thin().is_ascii());
+    assert!(!theme::heavy().is_ascii());
+}
+
+#[test]
+fn test_ascii_table_renders_through_the_fast_path() {
+    let table = NuTable::from(vec![create_row(3); 3]);
+
+    let cfg = NuTableConfig {
+        theme: theme::basic(),
+        with_header: true,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        table.draw(cfg, 100).unwrap(),
+        "+---+---+---+\n\
+         | 0 | 1 | 2 |\n\
+         +---+---+---+\n\
+         | 0 | 1 | 2 |\n\
+         +---+---+---+\n\
+         | 0 | 1 | 2 |\n\
+         +---+---+---+"
+    );
+}
+
+#[test]
+fn test_ascii_table_still_trims_when_too_wide() {
+    let table = NuTable::from(vec![
+        vec![cell("name"), cell("comment")],
+        vec![cell("a"), cell("a rather long plain ascii comment")],
+    ]);
+
+    let cfg = NuTableConfig {
+        theme: theme::basic(),
+        with_header: true,
+        ..Default::default()
+    };
+
+    let table = table.draw(cfg, 25).unwrap();
+
+    assert!(table.lines().all(|line| line.len() <= 25));
+}
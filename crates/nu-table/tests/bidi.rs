@@ -0,0 +1,54 @@
+mod common;
+
+use common::cell;
+use nu_table::{NuTable, NuTableConfig, TableTheme as theme};
+
+const FSI: char = '\u{2068}';
+const PDI: char = '\u{2069}';
+
+#[test]
+fn test_bidi_isolation_wraps_rtl_cells() {
+    let mut table = NuTable::from(vec![
+        vec![cell("name"), cell("greeting")],
+        vec![cell("hebrew"), cell("\u{5e9}\u{5dc}\u{5d5}\u{5dd}")],
+        vec![cell("plain"), cell("hello")],
+    ]);
+    table.set_bidi_isolation(true);
+
+    let cfg = NuTableConfig {
+        theme: theme::rounded(),
+        with_header: true,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        table.draw(cfg, 100).unwrap(),
+        format!(
+            "╭────────┬──────────╮\n\
+             │  name  │ greeting │\n\
+             ├────────┼──────────┤\n\
+             │ hebrew │ {FSI}\u{5e9}\u{5dc}\u{5d5}\u{5dd}{PDI}     │\n\
+             │ plain  │ hello    │\n\
+             ╰────────┴──────────╯"
+        )
+    );
+}
+
+#[test]
+fn test_bidi_isolation_leaves_ltr_tables_alone() {
+    let mut table = NuTable::from(vec![vec![cell("a")], vec![cell("b")]]);
+    table.set_bidi_isolation(true);
+
+    let cfg = NuTableConfig {
+        theme: theme::rounded(),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        table.draw(cfg, 100).unwrap(),
+        "╭───╮\n\
+         │ a │\n\
+         │ b │\n\
+         ╰───╯"
+    );
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use common::cell;
+use nu_color_config::TextStyle;
+use nu_table::{NuTable, NuTableConfig, TableTheme as theme};
+
+fn config() -> NuTableConfig {
+    NuTableConfig {
+        theme: theme::rounded(),
+        with_header: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_multiline_collapse() {
+    let mut table = NuTable::from(vec![
+        vec![cell("name"), cell("note")],
+        vec![cell("a"), cell("first\nsecond\nthird")],
+        vec![cell("b"), cell("only one")],
+    ]);
+    table.set_multiline_collapse(TextStyle::default());
+
+    assert_eq!(
+        table.draw(config(), 100).unwrap(),
+        "╭──────┬──────────────────╮\n\
+         │ name │       note       │\n\
+         ├──────┼──────────────────┤\n\
+         │ a    │ first (+2 lines) │\n\
+         │ b    │ only one         │\n\
+         ╰──────┴──────────────────╯"
+    );
+}
+
+#[test]
+fn test_multiline_collapse_singular_marker() {
+    let mut table = NuTable::from(vec![
+        vec![cell("name"), cell("note")],
+        vec![cell("a"), cell("first\nsecond")],
+    ]);
+    table.set_multiline_collapse(TextStyle::default());
+
+    assert_eq!(
+        table.draw(config(), 100).unwrap(),
+        "╭──────┬─────────────────╮\n\
+         │ name │      note       │\n\
+         ├──────┼─────────────────┤\n\
+         │ a    │ first (+1 line) │\n\
+         ╰──────┴─────────────────╯"
+    );
+}
+
+#[test]
+fn test_multiline_collapse_styles_the_marker() {
+    let mut table = NuTable::from(vec![
+        vec![cell("name"), cell("note")],
+        vec![cell("a"), cell("first\nsecond\nthird")],
+    ]);
+    table.set_multiline_collapse(TextStyle::default_field());
+
+    let table = table.draw(config(), 100).unwrap();
+
+    assert!(table.contains("first \u{1b}[1;32m(+2 lines)\u{1b}[0m"));
+}
+
+#[test]
+fn test_multiline_cells_left_alone_by_default() {
+    let table = NuTable::from(vec![
+        vec![cell("name"), cell("note")],
+        vec![cell("a"), cell("first\nsecond")],
+    ]);
+
+    assert_eq!(
+        table.draw(config(), 100).unwrap(),
+        "╭──────┬────────╮\n\
+         │ name │  note  │\n\
+         ├──────┼────────┤\n\
+         │ a    │ first  │\n\
+         │      │ second │\n\
+         ╰──────┴────────╯"
+    );
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use common::cell;
+use nu_table::{ColumnAlignment, NuTable, NuTableConfig, TableTheme as theme};
+
+#[test]
+fn test_decimal_alignment_lines_up_the_points() {
+    let mut table = NuTable::from(vec![
+        vec![cell("a"), cell("10")],
+        vec![cell("b"), cell("12.52")],
+        vec![cell("c"), cell("0.1")],
+    ]);
+    table.set_column_alignment(1, ColumnAlignment::Decimal);
+
+    let cfg = NuTableConfig {
+        theme: theme::rounded(),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        table.draw(cfg, 100).as_deref(),
+        Some(
+            "╭───┬───────╮\n\
+             │ a │ 10    │\n\
+             │ b │ 12.52 │\n\
+             │ c │  0.1  │\n\
+             ╰───┴───────╯"
+        )
+    );
+}
+
+#[test]
+fn test_decimal_alignment_skips_the_header_row() {
+    let mut table = NuTable::from(vec![
+        vec![cell("size")],
+        vec![cell("1.5")],
+        vec![cell("20")],
+    ]);
+    table.set_column_alignment(0, ColumnAlignment::Decimal);
+
+    let cfg = NuTableConfig {
+        theme: theme::rounded(),
+        with_header: true,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        table.draw(cfg, 100).as_deref(),
+        Some(
+            "╭──────╮\n\
+             │ size │\n\
+             ├──────┤\n\
+             │  1.5 │\n\
+             │ 20   │\n\
+             ╰──────╯"
+        )
+    );
+}
+
+#[test]
+fn test_column_format_hook_rewrites_data_values() {
+    let mut table = NuTable::from(vec![
+        vec![cell("size")],
+        vec![cell("10")],
+        vec![cell("25")],
+    ]);
+    table.set_column_format(0, |value| format!("{value} kb"));
+    table.set_column_alignment(0, ColumnAlignment::Right);
+
+    let cfg = NuTableConfig {
+        theme: theme::rounded(),
+        with_header: true,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        table.draw(cfg, 100).as_deref(),
+        Some(
+            "╭───────╮\n\
+             │ size  │\n\
+             ├───────┤\n\
+             │ 10 kb │\n\
+             │ 25 kb │\n\
+             ╰───────╯"
+        )
+    );
+}
@@ -0,0 +1,81 @@
+use nu_color_config::TextStyle;
+use nu_table::{NuTableConfig, TableDiff, TableTheme as theme};
+
+fn config() -> NuTableConfig {
+    NuTableConfig {
+        theme: theme::rounded(),
+        ..Default::default()
+    }
+}
+
+fn columns() -> Vec<String> {
+    vec![String::from("name"), String::from("size")]
+}
+
+fn row(name: &str, size: &str) -> Vec<String> {
+    vec![name.to_string(), size.to_string()]
+}
+
+#[test]
+fn test_diff_marks_changed_cells() {
+    let old = vec![row("a", "1"), row("b", "2")];
+    let new = vec![row("a", "1"), row("b", "3")];
+    let diff = TableDiff::new(columns(), old, new);
+
+    assert_eq!(
+        diff.draw(config(), 100).unwrap(),
+        "╭───┬──────┬────────╮\n\
+         │   │ name │  size  │\n\
+         ├───┼──────┼────────┤\n\
+         │   │ a    │ 1      │\n\
+         │ ~ │ b    │ 2 -> 3 │\n\
+         ╰───┴──────┴────────╯"
+    );
+}
+
+#[test]
+fn test_diff_keeps_rows_below_an_insertion_untouched() {
+    let old = vec![row("a", "1"), row("c", "3")];
+    let new = vec![row("a", "1"), row("b", "2"), row("c", "3")];
+    let diff = TableDiff::new(columns(), old, new);
+
+    assert_eq!(
+        diff.draw(config(), 100).unwrap(),
+        "╭───┬──────┬──────╮\n\
+         │   │ name │ size │\n\
+         ├───┼──────┼──────┤\n\
+         │   │ a    │ 1    │\n\
+         │ + │ b    │ 2    │\n\
+         │   │ c    │ 3    │\n\
+         ╰───┴──────┴──────╯"
+    );
+}
+
+#[test]
+fn test_diff_marks_removed_rows() {
+    let old = vec![row("a", "1"), row("b", "2")];
+    let new = vec![row("a", "1")];
+    let diff = TableDiff::new(columns(), old, new);
+
+    assert_eq!(
+        diff.draw(config(), 100).unwrap(),
+        "╭───┬──────┬──────╮\n\
+         │   │ name │ size │\n\
+         ├───┼──────┼──────┤\n\
+         │   │ a    │ 1    │\n\
+         │ - │ b    │ 2    │\n\
+         ╰───┴──────┴──────╯"
+    );
+}
+
+#[test]
+fn test_diff_styles_changed_cells() {
+    let old = vec![row("a", "1")];
+    let new = vec![row("a", "2")];
+    let mut diff = TableDiff::new(columns(), old, new);
+    diff.set_changed_style(TextStyle::default_field());
+
+    let table = diff.draw(config(), 100).unwrap();
+
+    assert!(table.contains("\u{1b}[1;32m1 -> 2\u{1b}[0m"));
+}
@@ -0,0 +1,65 @@
+use nu_color_config::{Alignment, StyleComputer, TextStyle};
+use nu_protocol::{
+    engine::{EngineState, Stack},
+    Config, Span, TableMode, Value,
+};
+use nu_table::{ExpandedTable, TableOpts};
+
+fn build(table: ExpandedTable, value: &Value) -> (String, TextStyle) {
+    let engine_state = EngineState::new();
+    let stack = Stack::new();
+    let style_computer = StyleComputer::new(&engine_state, &stack, Default::default());
+    let config = Config::default();
+    let opts = TableOpts::new(
+        &config,
+        &style_computer,
+        None,
+        Span::test_data(),
+        80,
+        (1, 1),
+        TableMode::Rounded,
+        0,
+        false,
+    );
+
+    table.build_value(value, opts)
+}
+
+fn nested_table() -> Value {
+    let row = Value::test_record(nu_protocol::record! {
+        "a" => Value::test_int(1),
+        "b" => Value::test_int(2),
+    });
+
+    Value::test_list(vec![row.clone(), row.clone(), row])
+}
+
+#[test]
+fn test_summary_format_hook_replaces_the_abbreviation() {
+    let mut table = ExpandedTable::new(Some(0), false, String::new());
+    table.set_summary_format(|value| match value {
+        Value::List { vals, .. } => format!("[table {}\u{d7}2]", vals.len()),
+        _ => String::from("?"),
+    });
+
+    let (text, _) = build(table, &nested_table());
+    assert_eq!(text, "[table 3\u{d7}2]");
+}
+
+#[test]
+fn test_summary_style_is_returned_with_the_text() {
+    let mut table = ExpandedTable::new(Some(0), false, String::new());
+    let style = TextStyle::with_style(Alignment::Left, nu_ansi_term::Color::DarkGray.normal());
+    table.set_summary_style(style);
+
+    let (text, out_style) = build(table, &nested_table());
+    assert_eq!(text, "[table 3 rows]");
+    assert_eq!(out_style.color_style, style.color_style);
+}
+
+#[test]
+fn test_summary_defaults_to_the_abbreviated_string() {
+    let table = ExpandedTable::new(Some(0), false, String::new());
+    let (text, _) = build(table, &nested_table());
+    assert_eq!(text, "[table 3 rows]");
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use common::cell;
+use nu_table::{string_width, string_width_graphemes, NuTable, NuTableConfig, TableTheme as theme};
+
+#[test]
+fn test_grapheme_width_counts_emoji_sequences_once() {
+    // family emoji: four codepoints joined with ZWJs, one glyph on screen
+    assert_eq!(string_width_graphemes("👨\u{200d}👩\u{200d}👧"), 2);
+    assert_eq!(string_width_graphemes("🇩🇪"), 2);
+    assert_eq!(string_width_graphemes("👍\u{1f3fd}"), 2);
+    assert_eq!(string_width_graphemes("abc"), 3);
+    assert_eq!(string_width_graphemes("ab\ncdef"), 4);
+}
+
+#[test]
+fn test_grapheme_width_mode_aligns_emoji_heavy_columns() {
+    let family = "👨\u{200d}👩\u{200d}👧";
+    assert!(string_width(family) > string_width_graphemes(family));
+
+    let mut table = NuTable::from(vec![
+        vec![cell(family), cell("x")],
+        vec![cell("aa"), cell("y")],
+    ]);
+    table.set_grapheme_width_mode(true);
+
+    let cfg = NuTableConfig {
+        theme: theme::rounded(),
+        ..Default::default()
+    };
+
+    // the emoji column is 2 cells wide, same as "aa"
+    assert_eq!(
+        table.draw(cfg, 100).as_deref(),
+        Some(&*format!(
+            "╭────┬───╮\n\
+             │ {family} │ x │\n\
+             │ aa │ y │\n\
+             ╰────┴───╯"
+        ))
+    );
+}
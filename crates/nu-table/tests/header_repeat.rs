@@ -0,0 +1,85 @@
+mod common;
+
+use common::cell;
+use nu_table::{NuTable, NuTableConfig, TableTheme as theme};
+
+fn listing() -> NuTable {
+    NuTable::from(vec![
+        vec![cell("name")],
+        vec![cell("a")],
+        vec![cell("b")],
+        vec![cell("c")],
+        vec![cell("d")],
+    ])
+}
+
+#[test]
+fn test_header_is_repeated_every_k_rows() {
+    let mut table = listing();
+    table.set_header_repeat(2);
+
+    let cfg = NuTableConfig {
+        theme: theme::rounded(),
+        with_header: true,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        table.draw(cfg, 100).unwrap(),
+        "╭──────╮\n\
+         │ name │\n\
+         ├──────┤\n\
+         │ a    │\n\
+         │ b    │\n\
+         ├──────┤\n\
+         │ name │\n\
+         ├──────┤\n\
+         │ c    │\n\
+         │ d    │\n\
+         ╰──────╯"
+    );
+}
+
+#[test]
+fn test_header_is_not_repeated_behind_the_last_row() {
+    let mut table = listing();
+    table.set_header_repeat(4);
+
+    let cfg = NuTableConfig {
+        theme: theme::rounded(),
+        with_header: true,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        table.draw(cfg, 100).unwrap(),
+        "╭──────╮\n\
+         │ name │\n\
+         ├──────┤\n\
+         │ a    │\n\
+         │ b    │\n\
+         │ c    │\n\
+         │ d    │\n\
+         ╰──────╯"
+    );
+}
+
+#[test]
+fn test_header_repeat_needs_a_header() {
+    let mut table = NuTable::from(vec![vec![cell("a")], vec![cell("b")], vec![cell("c")]]);
+    table.set_header_repeat(1);
+
+    let cfg = NuTableConfig {
+        theme: theme::rounded(),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        table.draw(cfg, 100).unwrap(),
+        "╭───╮\n\
+         │ a │\n\
+         │ b │\n\
+         │ c │\n\
+         ╰───╯"
+    );
+}
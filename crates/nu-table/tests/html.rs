@@ -0,0 +1,67 @@
+mod common;
+
+use common::cell;
+use nu_color_config::{Alignment, TextStyle};
+use nu_table::HtmlTable;
+
+#[test]
+fn test_html_table_is_semantic() {
+    let table = HtmlTable::new(
+        vec![
+            vec![cell("name"), cell("size")],
+            vec![cell("a"), cell("10")],
+        ],
+        true,
+    );
+
+    assert_eq!(
+        table.draw(),
+        "<table>\
+         <thead><tr><th>name</th><th>size</th></tr></thead>\
+         <tbody><tr><td>a</td><td>10</td></tr></tbody>\
+         </table>"
+    );
+}
+
+#[test]
+fn test_html_styles_map_to_css() {
+    let mut table = HtmlTable::new(
+        vec![
+            vec![cell("name"), cell("size")],
+            vec![cell("a"), cell("10")],
+        ],
+        true,
+    );
+    table.set_header_style(TextStyle::with_style(
+        Alignment::Center,
+        nu_ansi_term::Color::Green.bold(),
+    ));
+    table.set_column_style(
+        1,
+        TextStyle::with_style(Alignment::Right, nu_ansi_term::Color::Rgb(0, 128, 255).normal()),
+    );
+
+    assert_eq!(
+        table.draw(),
+        "<table>\
+         <thead><tr>\
+         <th style=\"text-align:center;color:green;font-weight:bold\">name</th>\
+         <th style=\"text-align:center;color:green;font-weight:bold\">size</th>\
+         </tr></thead>\
+         <tbody><tr>\
+         <td>a</td>\
+         <td style=\"text-align:right;color:#0080ff\">10</td>\
+         </tr></tbody>\
+         </table>"
+    );
+}
+
+#[test]
+fn test_html_escapes_cell_content() {
+    let table = HtmlTable::new(vec![vec![cell("<b>&\"quoted\"</b>")]], false);
+
+    assert_eq!(
+        table.draw(),
+        "<table><tbody><tr><td>&lt;b&gt;&amp;&quot;quoted&quot;&lt;/b&gt;</td></tr></tbody></table>"
+    );
+}
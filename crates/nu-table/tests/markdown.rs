@@ -0,0 +1,45 @@
+mod common;
+
+use common::cell;
+use nu_table::{ColumnAlignment, NuTable};
+
+#[test]
+fn test_markdown_table_with_alignment_markers() {
+    let mut table = NuTable::from(vec![
+        vec![cell("name"), cell("size"), cell("kind")],
+        vec![cell("a"), cell("10"), cell("file")],
+        vec![cell("b"), cell("12.5"), cell("dir")],
+    ]);
+    table.set_column_alignment(0, ColumnAlignment::Left);
+    table.set_column_alignment(1, ColumnAlignment::Decimal);
+
+    assert_eq!(
+        table.draw_markdown(),
+        "| name | size | kind |\n\
+         | :--- | ---: | --- |\n\
+         | a | 10 | file |\n\
+         | b | 12.5 | dir |"
+    );
+}
+
+#[test]
+fn test_markdown_escapes_pipes_and_newlines() {
+    let table = NuTable::from(vec![
+        vec![cell("value")],
+        vec![cell("a|b")],
+        vec![cell("two\nlines")],
+    ]);
+
+    assert_eq!(
+        table.draw_markdown(),
+        "| value |\n\
+         | --- |\n\
+         | a\\|b |\n\
+         | two<br>lines |"
+    );
+}
+
+#[test]
+fn test_markdown_of_an_empty_table() {
+    assert_eq!(NuTable::new(0, 0).draw_markdown(), "");
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use common::cell;
+use nu_table::{NuTable, NuTableConfig, TableTheme as theme};
+
+fn file_listing() -> NuTable {
+    let mut table = NuTable::from(vec![
+        vec![cell("name"), cell("size"), cell("modified")],
+        vec![cell("some-long-file-name.toml"), cell("5kb"), cell("now")],
+        vec![cell("src"), cell("-"), cell("yesterday")],
+    ]);
+    table.set_column_priority(0, 2);
+    table.set_column_priority(1, 1);
+
+    table
+}
+
+#[test]
+fn test_priority_collapses_low_priority_columns_first() {
+    let cfg = NuTableConfig {
+        theme: theme::rounded(),
+        with_header: true,
+        ..Default::default()
+    };
+
+    let table = file_listing().draw(cfg, 40);
+
+    assert_eq!(
+        table.as_deref(),
+        Some(
+            "╭──────────────────────────┬─────╮\n\
+             │           name           │ ... │\n\
+             ├──────────────────────────┼─────┤\n\
+             │ some-long-file-name.toml │ ... │\n\
+             │ src                      │ ... │\n\
+             ╰──────────────────────────┴─────╯\n\
+             ... and 2 more columns: size, modified"
+        )
+    );
+}
+
+#[test]
+fn test_priority_is_not_used_when_the_table_fits() {
+    let cfg = NuTableConfig {
+        theme: theme::rounded(),
+        with_header: true,
+        ..Default::default()
+    };
+
+    let table = file_listing().draw(cfg, 100).expect("the table fits");
+
+    assert!(!table.contains("..."));
+    assert!(table.contains("modified"));
+}
+
+#[test]
+fn test_priority_hint_skips_names_without_a_header() {
+    let cfg = NuTableConfig {
+        theme: theme::rounded(),
+        ..Default::default()
+    };
+
+    let table = file_listing().draw(cfg, 40).expect("one column fits");
+
+    assert!(table.ends_with("... and 2 more columns"));
+}
+
+#[test]
+fn test_priority_collapse_fails_like_truncation_on_tiny_terminals() {
+    let cfg = NuTableConfig {
+        theme: theme::rounded(),
+        with_header: true,
+        ..Default::default()
+    };
+
+    assert_eq!(file_listing().draw(cfg, 5), None);
+}
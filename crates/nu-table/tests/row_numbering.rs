@@ -0,0 +1,79 @@
+mod common;
+
+use common::cell;
+use nu_table::{NuTable, NuTableConfig, StreamingTable, TableTheme as theme};
+
+#[test]
+fn test_enumerate_rows_prepends_an_index_column() {
+    let mut table = NuTable::from(vec![
+        vec![cell("name")],
+        vec![cell("a")],
+        vec![cell("b")],
+    ]);
+    table.enumerate_rows(0, true);
+
+    let cfg = NuTableConfig {
+        theme: theme::rounded(),
+        with_header: true,
+        with_index: true,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        table.draw(cfg, 100).unwrap(),
+        "╭───┬──────╮\n\
+         │ # │ name │\n\
+         ├───┼──────┤\n\
+         │ 0 │ a    │\n\
+         │ 1 │ b    │\n\
+         ╰───┴──────╯"
+    );
+}
+
+#[test]
+fn test_enumerate_rows_continues_from_an_offset() {
+    let mut table = NuTable::from(vec![vec![cell("a")], vec![cell("b")]]);
+    table.enumerate_rows(100, false);
+
+    let cfg = NuTableConfig {
+        theme: theme::rounded(),
+        with_index: true,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        table.draw(cfg, 100).unwrap(),
+        "╭─────┬───╮\n\
+         │ 100 │ a │\n\
+         │ 101 │ b │\n\
+         ╰─────┴───╯"
+    );
+}
+
+#[test]
+fn test_streaming_rows_are_numbered_across_pushes() {
+    let cfg = NuTableConfig {
+        theme: theme::rounded(),
+        with_header: true,
+        with_index: true,
+        ..Default::default()
+    };
+
+    let mut table = StreamingTable::new(cfg, 100);
+    table.set_row_numbering(7);
+    let head = table
+        .start(Some(vec![cell("name")]), vec![vec![cell("a")]])
+        .expect("the sample fits");
+    let extra = table.push(vec![cell("b")]);
+    let bottom = table.finish();
+
+    assert_eq!(
+        format!("{head}\n{extra}\n{bottom}"),
+        "╭───┬──────╮\n\
+         │ # │ name │\n\
+         ├───┼──────┤\n\
+         │ 7 │ a    │\n\
+         │ 8 │ b    │\n\
+         ╰───┴──────╯"
+    );
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use common::{cell, create_row as row};
+use nu_table::{NuTableConfig, StreamingTable, TableTheme as theme};
+
+#[test]
+fn test_streaming_blocks_compose_a_table() {
+    let cfg = NuTableConfig {
+        theme: theme::rounded(),
+        with_header: true,
+        ..Default::default()
+    };
+
+    let mut table = StreamingTable::new(cfg, 100);
+    let head = table
+        .start(Some(row(4)), vec![row(4), row(4)])
+        .expect("the sample fits");
+    let extra = table.push(row(4));
+    let bottom = table.finish();
+
+    assert_eq!(
+        format!("{head}\n{extra}\n{bottom}"),
+        "╭───┬───┬───┬───╮\n\
+         │ 0 │ 1 │ 2 │ 3 │\n\
+         ├───┼───┼───┼───┤\n\
+         │ 0 │ 1 │ 2 │ 3 │\n\
+         │ 0 │ 1 │ 2 │ 3 │\n\
+         │ 0 │ 1 │ 2 │ 3 │\n\
+         ╰───┴───┴───┴───╯"
+    );
+}
+
+#[test]
+fn test_streaming_rows_truncate_to_the_sampled_widths() {
+    let cfg = NuTableConfig {
+        theme: theme::rounded(),
+        ..Default::default()
+    };
+
+    let mut table = StreamingTable::new(cfg, 100);
+    let head = table
+        .start(None, vec![vec![cell("123"), cell("45")]])
+        .expect("the sample fits");
+    let extra = table.push(vec![cell("much longer"), cell("x")]);
+    let bottom = table.finish();
+
+    assert_eq!(
+        format!("{head}\n{extra}\n{bottom}"),
+        "╭─────┬────╮\n\
+         │ 123 │ 45 │\n\
+         │ muc │ x  │\n\
+         ╰─────┴────╯"
+    );
+}
+
+#[test]
+fn test_streaming_refuses_samples_wider_than_the_terminal() {
+    let cfg = NuTableConfig {
+        theme: theme::rounded(),
+        ..Default::default()
+    };
+
+    let mut table = StreamingTable::new(cfg, 10);
+    assert_eq!(table.start(None, vec![row(4)]), None);
+    assert_eq!(StreamingTable::new(NuTableConfig::default(), 100).start(None, vec![]), None);
+}
+
+#[test]
+fn test_streaming_basic_theme_keeps_no_bottom_for_finish() {
+    let cfg = NuTableConfig {
+        theme: theme::none(),
+        ..Default::default()
+    };
+
+    let mut table = StreamingTable::new(cfg, 100);
+    let head = table.start(None, vec![row(2)]).expect("the sample fits");
+    let extra = table.push(row(2));
+
+    assert_eq!(format!("{head}\n{extra}"), " 0   1 \n 0   1 ");
+    assert_eq!(table.finish(), "");
+}
@@ -1,6 +1,7 @@
 mod common;
 
 use common::create_row as row;
+use nu_protocol::Value;
 use nu_table::{NuTable, NuTableConfig, TableTheme as theme};
 use tabled::grid::records::vec_records::CellInfo;
 
@@ -451,6 +452,65 @@ fn test_with_love() {
     assert_eq!(create_table_with_size(vec![], true, theme::with_love()), "");
 }
 
+#[test]
+fn test_theme_from_record() {
+    let record = nu_protocol::record! {
+        "top" => Value::test_string("─"),
+        "bottom" => Value::test_string("─"),
+        "left" => Value::test_string("│"),
+        "right" => Value::test_string("│"),
+        "vertical" => Value::test_string("│"),
+        "horizontal" => Value::test_string("─"),
+        "top_left" => Value::test_string("╭"),
+        "top_right" => Value::test_string("╮"),
+        "bottom_left" => Value::test_string("╰"),
+        "bottom_right" => Value::test_string("╯"),
+        "top_intersection" => Value::test_string("┬"),
+        "bottom_intersection" => Value::test_string("┴"),
+        "left_intersection" => Value::test_string("├"),
+        "right_intersection" => Value::test_string("┤"),
+        "intersection" => Value::test_string("┼"),
+    };
+    let custom = theme::from_record(&record).expect("a valid theme record");
+
+    assert_eq!(
+        create_table(vec![row(4); 3], true, custom),
+        create_table(vec![row(4); 3], true, theme::rounded()),
+    );
+}
+
+#[test]
+fn test_theme_from_record_empty_strings_remove_borders() {
+    let record = nu_protocol::record! {
+        "vertical" => Value::test_string("|"),
+        "horizontal" => Value::test_string("-"),
+        "intersection" => Value::test_string("+"),
+        "left" => Value::test_string(""),
+    };
+    let custom = theme::from_record(&record).expect("a valid theme record");
+
+    assert_eq!(
+        create_table(vec![row(4); 3], true, custom),
+        " 0 | 1 | 2 | 3 \n\
+         ---+---+---+---\n \
+         0 | 1 | 2 | 3 \n \
+         0 | 1 | 2 | 3 "
+    );
+}
+
+#[test]
+fn test_theme_from_record_rejects_bad_records() {
+    let unknown_key = nu_protocol::record! {
+        "diagonal" => Value::test_string("╲"),
+    };
+    assert!(theme::from_record(&unknown_key).is_err());
+
+    let too_long = nu_protocol::record! {
+        "top" => Value::test_string("--"),
+    };
+    assert!(theme::from_record(&too_long).is_err());
+}
+
 fn create_table(data: Vec<Vec<CellInfo<String>>>, with_header: bool, theme: theme) -> String {
     let config = NuTableConfig {
         theme,
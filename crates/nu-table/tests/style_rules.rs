@@ -0,0 +1,71 @@
+mod common;
+
+use common::cell;
+use nu_color_config::{Alignment, TextStyle};
+use nu_table::{NuTable, NuTableConfig, StyleRule, TableTheme as theme};
+
+fn red() -> TextStyle {
+    TextStyle::with_style(Alignment::Left, nu_ansi_term::Color::Red.normal())
+}
+
+fn sized_listing() -> NuTable {
+    NuTable::from(vec![
+        vec![cell("name"), cell("size")],
+        vec![cell("small.txt"), cell("10")],
+        vec![cell("big.txt"), cell("120")],
+    ])
+}
+
+#[test]
+fn test_range_rule_colors_matching_numbers() {
+    let mut table = sized_listing();
+    table.add_style_rule(StyleRule::range("size", 100.0, 1000.0, red()));
+
+    let cfg = NuTableConfig {
+        theme: theme::rounded(),
+        with_header: true,
+        ..Default::default()
+    };
+
+    let table = table.draw(cfg, 100).expect("the table fits");
+
+    assert!(table.contains("\u{1b}[31m120\u{1b}[0m"));
+    assert!(!table.contains("\u{1b}[31m10\u{1b}[0m"));
+}
+
+#[test]
+fn test_regex_rule_colors_matching_values() {
+    let mut table = sized_listing();
+    table.add_style_rule(StyleRule::matches("name", r"^big\.", red()).expect("a valid pattern"));
+
+    let cfg = NuTableConfig {
+        theme: theme::rounded(),
+        with_header: true,
+        ..Default::default()
+    };
+
+    let table = table.draw(cfg, 100).expect("the table fits");
+
+    assert!(table.contains("\u{1b}[31mbig.txt\u{1b}[0m"));
+    assert!(!table.contains("\u{1b}[31msmall.txt\u{1b}[0m"));
+}
+
+#[test]
+fn test_rules_only_apply_with_a_header() {
+    let mut table = sized_listing();
+    table.add_style_rule(StyleRule::range("size", 100.0, 1000.0, red()));
+
+    let cfg = NuTableConfig {
+        theme: theme::rounded(),
+        ..Default::default()
+    };
+
+    let table = table.draw(cfg, 100).expect("the table fits");
+
+    assert!(!table.contains('\u{1b}'));
+}
+
+#[test]
+fn test_invalid_rule_pattern_is_an_error() {
+    assert!(StyleRule::matches("name", "(unclosed", red()).is_err());
+}
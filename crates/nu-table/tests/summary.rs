@@ -0,0 +1,86 @@
+mod common;
+
+use common::cell;
+use nu_color_config::TextStyle;
+use nu_table::{NuTable, NuTableConfig, TableTheme as theme};
+
+fn listing_with_total() -> NuTable {
+    let mut table = NuTable::from(vec![
+        vec![cell("name"), cell("size")],
+        vec![cell("a"), cell("10")],
+        vec![cell("b"), cell("5")],
+    ]);
+    table.set_summary_row(
+        vec![String::from("total"), String::from("15")],
+        TextStyle::default(),
+    );
+
+    table
+}
+
+#[test]
+fn test_summary_row_renders_inside_the_bottom_border() {
+    let cfg = NuTableConfig {
+        theme: theme::rounded(),
+        with_header: true,
+        ..Default::default()
+    };
+
+    let table = listing_with_total().draw(cfg, 100);
+
+    assert_eq!(
+        table.as_deref(),
+        Some(
+            "╭───────┬──────╮\n\
+             │ name  │ size │\n\
+             ├───────┼──────┤\n\
+             │ a     │ 10   │\n\
+             │ b     │ 5    │\n\
+             ├───────┼──────┤\n\
+             │ total │ 15   │\n\
+             ╰───────┴──────╯"
+        )
+    );
+}
+
+#[test]
+fn test_summary_row_separator_works_without_a_header() {
+    let cfg = NuTableConfig {
+        theme: theme::rounded(),
+        ..Default::default()
+    };
+
+    let mut table = NuTable::from(vec![vec![cell("a"), cell("10")], vec![cell("b"), cell("5")]]);
+    table.set_summary_row(
+        vec![String::from("total"), String::from("15")],
+        TextStyle::default(),
+    );
+
+    assert_eq!(
+        table.draw(cfg, 100).as_deref(),
+        Some(
+            "╭───────┬────╮\n\
+             │ a     │ 10 │\n\
+             │ b     │ 5  │\n\
+             ├───────┼────┤\n\
+             │ total │ 15 │\n\
+             ╰───────┴────╯"
+        )
+    );
+}
+
+#[test]
+fn test_summary_row_stays_above_the_repeated_footer_header() {
+    let cfg = NuTableConfig {
+        theme: theme::rounded(),
+        with_header: true,
+        with_footer: true,
+        ..Default::default()
+    };
+
+    let table = listing_with_total().draw(cfg, 100).expect("the table fits");
+
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines[lines.len() - 2], "│ name  │ size │");
+    assert_eq!(lines[lines.len() - 4], "│ total │ 15   │");
+}
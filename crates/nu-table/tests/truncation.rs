@@ -0,0 +1,96 @@
+mod common;
+
+use common::cell;
+use nu_table::{string_truncate_with_policy, TruncationPolicy};
+use nu_table::{NuTable, NuTableConfig, TableTheme as theme};
+
+fn config() -> NuTableConfig {
+    NuTableConfig {
+        theme: theme::rounded(),
+        with_header: true,
+        ..Default::default()
+    }
+}
+
+fn suffix(suffix: &str) -> TruncationPolicy {
+    TruncationPolicy {
+        suffix: suffix.to_owned(),
+        keep_end: false,
+    }
+}
+
+#[test]
+fn test_truncation_policy_string() {
+    assert_eq!(string_truncate_with_policy("0123456789", 5, &suffix("…")), "0123…");
+    assert_eq!(string_truncate_with_policy("0123456789", 5, &suffix("...")), "01...");
+    assert_eq!(
+        string_truncate_with_policy(
+            "0123456789",
+            5,
+            &TruncationPolicy {
+                suffix: String::from("…"),
+                keep_end: true,
+            }
+        ),
+        "…6789"
+    );
+    assert_eq!(string_truncate_with_policy("0123", 5, &suffix("…")), "0123");
+}
+
+#[test]
+fn test_truncation_suffix_marks_cut_cells() {
+    let mut table = NuTable::from(vec![
+        vec![cell("name")],
+        vec![cell("a-very-long-file-name")],
+    ]);
+    table.set_truncation_policy(suffix("…"));
+
+    assert_eq!(
+        table.draw(config(), 12).unwrap(),
+        "╭──────────╮\n\
+         │   name   │\n\
+         ├──────────┤\n\
+         │ a-very-… │\n\
+         ╰──────────╯"
+    );
+}
+
+#[test]
+fn test_truncation_keeping_the_end() {
+    let mut table = NuTable::from(vec![
+        vec![cell("name")],
+        vec![cell("a-very-long-file-name")],
+    ]);
+    table.set_truncation_policy(TruncationPolicy {
+        suffix: String::from("…"),
+        keep_end: true,
+    });
+
+    assert_eq!(
+        table.draw(config(), 12).unwrap(),
+        "╭──────────╮\n\
+         │   name   │\n\
+         ├──────────┤\n\
+         │ …le-name │\n\
+         ╰──────────╯"
+    );
+}
+
+#[test]
+fn test_minimum_column_width_shifts_the_cut() {
+    let mut table = NuTable::from(vec![
+        vec![cell("name"), cell("kind")],
+        vec![cell("a-very-long-file-name"), cell("directory")],
+    ]);
+    table.set_truncation_policy(suffix("…"));
+    table.set_minimum_column_width(1, 9);
+
+    assert_eq!(
+        table.draw(config(), 20).unwrap(),
+        "╭──────┬───────────╮\n\
+         │ name │   kind    │\n\
+         ├──────┼───────────┤\n\
+         │ a-v… │ directory │\n\
+         ╰──────┴───────────╯"
+    );
+}
@@ -0,0 +1,46 @@
+use nu_table::{clean_charset, clean_charset_with_tab_width, string_wrap, string_wrap_keeping_ansi};
+
+#[test]
+fn test_clean_charset_expands_tabs_and_drops_carriage_returns() {
+    assert_eq!(clean_charset("1\t2\r\n3"), "1    2\n3");
+    assert_eq!(clean_charset_with_tab_width("1\t2", 2), "1  2");
+}
+
+#[test]
+fn test_clean_charset_strips_control_characters() {
+    assert_eq!(clean_charset("a\u{0}b\u{7}c\u{7f}d"), "abcd");
+}
+
+#[test]
+fn test_clean_charset_keeps_ansi_sequences() {
+    assert_eq!(clean_charset("\u{1b}[31mred\u{1b}[0m"), "\u{1b}[31mred\u{1b}[0m");
+}
+
+#[test]
+fn test_wrap_closes_and_reopens_ansi_styling() {
+    assert_eq!(
+        string_wrap_keeping_ansi("\u{1b}[31mredred\u{1b}[0m", 3, false),
+        "\u{1b}[31mred\u{1b}[0m\n\u{1b}[31mred\u{1b}[0m"
+    );
+    assert_eq!(
+        string_wrap_keeping_ansi("aa\u{1b}[32mbbbb\u{1b}[0mcc", 4, false),
+        "aa\u{1b}[32mbb\u{1b}[0m\n\u{1b}[32mbb\u{1b}[0mcc"
+    );
+}
+
+#[test]
+fn test_wrap_keeping_ansi_respects_word_boundaries() {
+    assert_eq!(
+        string_wrap_keeping_ansi("\u{1b}[31mone two\u{1b}[0m", 5, true),
+        "\u{1b}[31mone \u{1b}[0m\n\u{1b}[31mtwo\u{1b}[0m"
+    );
+}
+
+#[test]
+fn test_wrap_dispatches_on_ansi_content() {
+    assert_eq!(string_wrap("plain text", 5, false), "plain\n text");
+    assert_eq!(
+        string_wrap("\u{1b}[31mredred\u{1b}[0m", 3, false),
+        "\u{1b}[31mred\u{1b}[0m\n\u{1b}[31mred\u{1b}[0m"
+    );
+}
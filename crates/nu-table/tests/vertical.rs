@@ -0,0 +1,77 @@
+use nu_color_config::StyleComputer;
+use nu_protocol::{
+    engine::{EngineState, Stack},
+    record, Config, Span, Value,
+};
+use nu_table::{TableTheme as theme, VerticalTable};
+
+fn draw(records: Vec<nu_protocol::Record>, termwidth: usize) -> String {
+    let engine_state = EngineState::new();
+    let stack = Stack::new();
+    let style_computer = StyleComputer::new(&engine_state, &stack, Default::default());
+
+    VerticalTable::new(records).draw(
+        &style_computer,
+        &Config::default(),
+        &theme::rounded(),
+        termwidth,
+    )
+}
+
+#[test]
+fn test_vertical_blocks_per_record() {
+    let records = vec![
+        record! {
+            "name" => Value::test_string("a"),
+            "size" => Value::test_int(10),
+        },
+        record! {
+            "name" => Value::test_string("b"),
+            "size" => Value::test_int(12),
+        },
+    ];
+
+    assert_eq!(
+        draw(records, 80),
+        "─[ 1 ]──\n\
+         name: a\n\
+         size: 10\n\
+         ─[ 2 ]──\n\
+         name: b\n\
+         size: 12"
+    );
+}
+
+#[test]
+fn test_vertical_aligns_values_to_the_widest_column() {
+    let records = vec![record! {
+        "name" => Value::test_string("a"),
+        "modified" => Value::test_string("now"),
+    }];
+
+    assert_eq!(
+        draw(records, 80),
+        "─[ 1 ]───────\n\
+         name    : a\n\
+         modified: now"
+    );
+}
+
+#[test]
+fn test_vertical_keeps_multiline_values_aligned() {
+    let records = vec![record! {
+        "note" => Value::string("two\nlines", Span::test_data()),
+    }];
+
+    assert_eq!(
+        draw(records, 80),
+        "─[ 1 ]─────\n\
+         note: two\n\
+         \u{20}     lines"
+    );
+}
+
+#[test]
+fn test_vertical_of_no_records() {
+    assert_eq!(draw(Vec::new(), 80), "");
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use common::cell;
+use nu_table::{NuTable, NuTableConfig, TableTheme as theme, TruncationPolicy};
+
+fn config() -> NuTableConfig {
+    NuTableConfig {
+        theme: theme::rounded(),
+        with_header: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_maximum_width_stops_a_long_cell_from_reflowing() {
+    let mut table = NuTable::from(vec![
+        vec![cell("hash"), cell("name")],
+        vec![cell("d2d2d2d2d2d2d2d2d2d2d2d2"), cell("a")],
+    ]);
+    table.set_maximum_column_width(0, 8);
+    table.set_truncation_policy(TruncationPolicy {
+        suffix: String::from("…"),
+        keep_end: false,
+    });
+
+    assert_eq!(
+        table.draw(config(), 80).unwrap(),
+        "╭──────────┬──────╮\n\
+         │   hash   │ name │\n\
+         ├──────────┼──────┤\n\
+         │ d2d2d2d… │ a    │\n\
+         ╰──────────┴──────╯"
+    );
+}
+
+#[test]
+fn test_fixed_width_pads_and_cuts_a_column() {
+    let mut table = NuTable::from(vec![
+        vec![cell("time"), cell("event")],
+        vec![cell("12:00:00"), cell("start")],
+        vec![cell("1:00"), cell("a-rather-long-event")],
+    ]);
+    table.set_fixed_column_width(0, 5);
+
+    assert_eq!(
+        table.draw(config(), 80).unwrap(),
+        "╭───────┬─────────────────────╮\n\
+         │ time  │        event        │\n\
+         ├───────┼─────────────────────┤\n\
+         │ 12:00 │ start               │\n\
+         │ 1:00  │ a-rather-long-event │\n\
+         ╰───────┴─────────────────────╯"
+    );
+}
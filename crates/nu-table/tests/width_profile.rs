@@ -0,0 +1,81 @@
+mod common;
+
+use common::cell;
+use nu_table::{NuTable, NuTableConfig, TableTheme as theme};
+
+fn config() -> NuTableConfig {
+    NuTableConfig {
+        theme: theme::rounded(),
+        with_header: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_width_profile_keeps_columns_stable_across_batches() {
+    let first = NuTable::from(vec![
+        vec![cell("name"), cell("size")],
+        vec![cell("a-rather-long-name"), cell("10")],
+    ]);
+    let profile = first.width_profile();
+
+    let mut second = NuTable::from(vec![
+        vec![cell("name"), cell("size")],
+        vec![cell("b"), cell("12")],
+    ]);
+    second.set_width_profile(profile);
+
+    assert_eq!(
+        second.draw(config(), 100).unwrap(),
+        "╭────────────────────┬──────╮\n\
+         │        name        │ size │\n\
+         ├────────────────────┼──────┤\n\
+         │ b                  │ 12   │\n\
+         ╰────────────────────┴──────╯"
+    );
+}
+
+#[test]
+fn test_width_profile_truncates_outgrowing_cells() {
+    let first = NuTable::from(vec![
+        vec![cell("name"), cell("size")],
+        vec![cell("abc"), cell("10")],
+    ]);
+    let profile = first.width_profile();
+
+    let mut second = NuTable::from(vec![
+        vec![cell("name"), cell("size")],
+        vec![cell("much longer"), cell("12")],
+    ]);
+    second.set_width_profile(profile);
+
+    assert_eq!(
+        second.draw(config(), 100).unwrap(),
+        "╭──────┬──────╮\n\
+         │ name │ size │\n\
+         ├──────┼──────┤\n\
+         │ much │ 12   │\n\
+         ╰──────┴──────╯"
+    );
+}
+
+#[test]
+fn test_width_profile_of_other_columns_is_ignored() {
+    let first = NuTable::from(vec![vec![cell("name")], vec![cell("a")]]);
+    let profile = first.width_profile();
+
+    let mut second = NuTable::from(vec![
+        vec![cell("name"), cell("size")],
+        vec![cell("b"), cell("12")],
+    ]);
+    second.set_width_profile(profile);
+
+    assert_eq!(
+        second.draw(config(), 100).unwrap(),
+        "╭──────┬──────╮\n\
+         │ name │ size │\n\
+         ├──────┼──────┤\n\
+         │ b    │ 12   │\n\
+         ╰──────┴──────╯"
+    );
+}
@@ -0,0 +1,96 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{Error, LitStr};
+
+pub(crate) fn discover_compile_fail_tests(input: TokenStream) -> TokenStream {
+    match try_discover(input) {
+        Ok(tokens) => tokens,
+        Err(error) => error.into_compile_error(),
+    }
+}
+
+fn try_discover(input: TokenStream) -> syn::Result<TokenStream> {
+    let dir = if input.is_empty() {
+        String::from("tests/compile_fail")
+    } else {
+        syn::parse2::<LitStr>(input)?.value()
+    };
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| Error::new(Span::call_site(), "CARGO_MANIFEST_DIR is not set"))?;
+    let root = std::path::Path::new(&manifest_dir).join(&dir);
+    let entries = std::fs::read_dir(&root).map_err(|err| {
+        Error::new(
+            Span::call_site(),
+            format!("cannot read fixture directory {}: {err}", root.display()),
+        )
+    })?;
+
+    let mut fixtures: Vec<std::path::PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "rs"))
+        .collect();
+    fixtures.sort();
+
+    let mut registrations = Vec::new();
+    for (index, path) in fixtures.iter().enumerate() {
+        let source = std::fs::read_to_string(path).map_err(|err| {
+            Error::new(
+                Span::call_site(),
+                format!("cannot read fixture {}: {err}", path.display()),
+            )
+        })?;
+        let expected: Vec<String> = source
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("// expect-error:"))
+            .map(|rest| rest.trim_start().to_string())
+            .collect();
+        if expected.is_empty() {
+            return Err(Error::new(
+                Span::call_site(),
+                format!(
+                    "fixture {} has no // expect-error: annotation",
+                    path.display(),
+                ),
+            ));
+        }
+
+        let stem = path
+            .file_stem()
+            .expect("a .rs file has a stem")
+            .to_string_lossy()
+            .into_owned();
+        let file = path.to_string_lossy().into_owned();
+        let func = format_ident!("__kitest_compile_fail_{index}");
+        let entry = format_ident!("ENTRY_COMPILE_FAIL_{index}");
+        registrations.push(quote! {
+            fn #func() {
+                // The include makes cargo re-expand this macro when the
+                // fixture changes; the compile itself happens at runtime.
+                const _: &str = include_str!(#file);
+                ::nu_test_support::harness::run_compile_fail(
+                    #stem,
+                    #file,
+                    &[#(#expected),*],
+                );
+            }
+            #[::nu_test_support::harness::linkme::distributed_slice(
+                ::nu_test_support::harness::TESTS
+            )]
+            static #entry: ::nu_test_support::harness::TestMetadata =
+                ::nu_test_support::harness::TestMetadata {
+                    name: concat!(module_path!(), "::compile_fail::", #stem),
+                    file: #file,
+                    line: 1,
+                    func: #func,
+                    extra: ::nu_test_support::harness::TestMetaExtra::DEFAULT,
+                };
+        });
+    }
+
+    Ok(quote! {
+        const _: () = {
+            #(#registrations)*
+        };
+    })
+}
@@ -0,0 +1,52 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Error, ItemFn, LitStr};
+
+/// Which slot of a `GroupFixture` the annotated function fills.
+pub(crate) enum Kind {
+    Setup,
+    Teardown,
+}
+
+pub(crate) fn group_fixture(attr: TokenStream, item: TokenStream, kind: Kind) -> TokenStream {
+    match try_group_fixture(attr, item, kind) {
+        Ok(tokens) => tokens,
+        Err(error) => error.into_compile_error(),
+    }
+}
+
+fn try_group_fixture(
+    attr: TokenStream,
+    item: TokenStream,
+    kind: Kind,
+) -> syn::Result<TokenStream> {
+    let group: LitStr = syn::parse2(attr)?;
+    let item: ItemFn = syn::parse2(item)?;
+    if !item.sig.inputs.is_empty() {
+        return Err(Error::new_spanned(
+            &item.sig.inputs,
+            "group fixtures cannot take arguments",
+        ));
+    }
+
+    let name = &item.sig.ident;
+    let (entry, setup, teardown) = match kind {
+        Kind::Setup => (format_ident!("SETUP"), quote!(Some(#name)), quote!(None)),
+        Kind::Teardown => (format_ident!("TEARDOWN"), quote!(None), quote!(Some(#name))),
+    };
+    Ok(quote! {
+        #item
+
+        const _: () = {
+            #[::nu_test_support::harness::linkme::distributed_slice(
+                ::nu_test_support::harness::GROUP_FIXTURES
+            )]
+            static #entry: ::nu_test_support::harness::GroupFixture =
+                ::nu_test_support::harness::GroupFixture {
+                    group: #group,
+                    setup: #setup,
+                    teardown: #teardown,
+                };
+        };
+    })
+}
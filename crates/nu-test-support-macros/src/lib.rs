@@ -0,0 +1,114 @@
+//! Attribute macros for Nushell's kitest test harness.
+//!
+//! The macros only generate registration code; the types and the runner live
+//! in `nu-test-support::harness`. Use them through the re-exports there, not
+//! through this crate directly.
+
+use proc_macro::TokenStream;
+
+mod compile_fail;
+mod fixture;
+mod scripts;
+mod test;
+
+/// Register a function as a kitest test.
+///
+/// The function is registered into `nu_test_support::harness::TESTS`, where
+/// the harness's `main` picks it up. `async fn` bodies work too and are
+/// driven to completion on the harness's built-in executor. Companion
+/// attributes placed below this one are consumed by the macro and recorded
+/// as metadata:
+///
+/// - `#[cwd("relative/path")]` — working directory for the test, resolved
+///   relative to the crate root.
+/// - `#[timeout(30sec)]` — fail the test if it runs longer than this; the
+///   unit is the literal's suffix (`ms`, `sec` or `min`).
+/// - `#[retry(3)]` — rerun a failing test up to this many attempts before
+///   counting it as failed, for known-flaky integration tests.
+/// - `#[tags("slow", "network")]` — label the test for the harness's
+///   `--include-tag` / `--exclude-tag` filters.
+/// - `#[serial]` — run the test alone after all parallel tests, for tests
+///   touching process-global state.
+/// - `#[isolated]` — run the test in its own child process, so real global
+///   statics, env vars and cwd don't leak between tests.
+/// - `#[case(1, 2, 3)]` — register one test per `#[case]`, calling the
+///   function with the case's arguments; cases are named `test::case_N` in
+///   attribute order.
+/// - `#[env(KEY = "value", DATA = sandbox.join("data").display().to_string())]`
+///   — declare env vars for the test. String literals are stored as-is; any
+///   other expression is evaluated lazily at group setup time with the
+///   harness's per-run sandbox path in scope as `sandbox`. The harness
+///   applies the vars around the test (for grouped tests, around the whole
+///   group) and restores the previous environment afterwards.
+/// - `#[group("db")]` — tie the test to a group, whose
+///   [`group_setup`](macro@group_setup) / [`group_teardown`](macro@group_teardown)
+///   fixtures run around the suite.
+/// - `#[locale("de_DE")]` — run the body under the test locale override, so
+///   number/date formatting is the same on every machine; these tests run
+///   serially since the override is process-wide.
+/// - `#[bench]` / `#[bench(200)]` — run the body as a benchmark: one warmup
+///   plus the given number of timed iterations (50 by default), reporting
+///   wall-time statistics instead of a plain pass.
+/// - `#[stdout_eq("...")]` / `#[stderr_contains("...")]` — capture the
+///   body's output on the descriptor level and assert it afterwards; these
+///   tests run serially since the capture is process-wide.
+/// - `#[nocapture]` — tee this test's captured output through to the real
+///   streams while it runs, like a per-test `--nocapture`; useful when
+///   debugging a hang inside a captured body.
+/// - `#[experimental_matrix(nu_experimental::DATABASE_CMD_NEXT)]` — register
+///   one test per on/off combination of the listed experimental options,
+///   each running under a thread-local override guard and named like
+///   `test::database_cmd_next=on`.
+#[proc_macro_attribute]
+pub fn test(attr: TokenStream, item: TokenStream) -> TokenStream {
+    test::test(attr.into(), item.into()).into()
+}
+
+/// Register every annotated `.nu` script of a directory as a kitest test.
+///
+/// `nu_test_support::discover_nu_scripts!();` scans `tests/scripts/` (or the
+/// directory given as a string literal) for `.nu` files containing
+/// `# expect:` annotations and registers one test per script, named
+/// `scripts::<file stem>`. The harness runs each script on the kitest engine
+/// and compares the rendered result against the annotations, one output line
+/// per `# expect:` line — so contributors can add shell-behavior tests
+/// without writing Rust. Scripts without annotations are skipped.
+#[proc_macro]
+pub fn discover_nu_scripts(input: TokenStream) -> TokenStream {
+    scripts::discover_nu_scripts(input.into()).into()
+}
+
+/// Register every compile-fail fixture of a directory as a kitest test.
+///
+/// `nu_test_support::discover_compile_fail_tests!();` scans
+/// `tests/compile_fail/` (or the directory given as a string literal) for
+/// `.rs` fixtures that must *not* compile — typically misuses of the
+/// [`test`](macro@test) attribute. Each fixture is registered as a test
+/// named `compile_fail::<file stem>`; at runtime the harness compiles it
+/// against this crate and asserts every `// expect-error:` annotation
+/// appears in the diagnostics, trybuild-style but reported like any other
+/// kitest test. Fixtures without annotations are a compile error, so a typo
+/// can't silently turn into "any failure counts".
+#[proc_macro]
+pub fn discover_compile_fail_tests(input: TokenStream) -> TokenStream {
+    compile_fail::discover_compile_fail_tests(input.into()).into()
+}
+
+/// Register a function as the setup hook of a test group.
+///
+/// `#[nu_test_support::group_setup("db")]` runs the function once before the
+/// harness executes any test marked `#[group("db")]`. Groups without
+/// selected tests leave their fixtures untouched.
+#[proc_macro_attribute]
+pub fn group_setup(attr: TokenStream, item: TokenStream) -> TokenStream {
+    fixture::group_fixture(attr.into(), item.into(), fixture::Kind::Setup).into()
+}
+
+/// Register a function as the teardown hook of a test group.
+///
+/// The counterpart to [`group_setup`](macro@group_setup); runs once after
+/// the last test of the group finished, in reverse registration order.
+#[proc_macro_attribute]
+pub fn group_teardown(attr: TokenStream, item: TokenStream) -> TokenStream {
+    fixture::group_fixture(attr.into(), item.into(), fixture::Kind::Teardown).into()
+}
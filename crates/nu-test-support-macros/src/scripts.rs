@@ -0,0 +1,95 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{Error, LitStr};
+
+pub(crate) fn discover_nu_scripts(input: TokenStream) -> TokenStream {
+    match try_discover(input) {
+        Ok(tokens) => tokens,
+        Err(error) => error.into_compile_error(),
+    }
+}
+
+fn try_discover(input: TokenStream) -> syn::Result<TokenStream> {
+    let dir = if input.is_empty() {
+        String::from("tests/scripts")
+    } else {
+        syn::parse2::<LitStr>(input)?.value()
+    };
+    // Proc macros run with the manifest dir of the crate being expanded, so
+    // the scripts resolve relative to the test crate's own root.
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| Error::new(Span::call_site(), "CARGO_MANIFEST_DIR is not set"))?;
+    let root = std::path::Path::new(&manifest_dir).join(&dir);
+    let entries = std::fs::read_dir(&root).map_err(|err| {
+        Error::new(
+            Span::call_site(),
+            format!("cannot read script directory {}: {err}", root.display()),
+        )
+    })?;
+
+    let mut scripts: Vec<std::path::PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "nu"))
+        .collect();
+    // Directory order is filesystem-dependent; sorted paths keep the
+    // registration (and shard assignment) stable across machines.
+    scripts.sort();
+
+    let mut registrations = Vec::new();
+    for (index, path) in scripts.iter().enumerate() {
+        let source = std::fs::read_to_string(path).map_err(|err| {
+            Error::new(
+                Span::call_site(),
+                format!("cannot read script {}: {err}", path.display()),
+            )
+        })?;
+        let expected: Vec<String> = source
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("# expect:"))
+            .map(|rest| rest.trim_start().to_string())
+            .collect();
+        // Only annotated scripts become tests; helpers sourced by other
+        // scripts can live in the same directory.
+        if expected.is_empty() {
+            continue;
+        }
+
+        let stem = path
+            .file_stem()
+            .expect("a .nu file has a stem")
+            .to_string_lossy()
+            .into_owned();
+        let file = path.to_string_lossy().into_owned();
+        let func = format_ident!("__kitest_script_{index}");
+        let entry = format_ident!("ENTRY_SCRIPT_{index}");
+        registrations.push(quote! {
+            fn #func() {
+                // `include_str!` hands the source to the runtime *and* makes
+                // cargo re-expand this macro when the script changes.
+                ::nu_test_support::harness::run_nu_script(
+                    #stem,
+                    include_str!(#file),
+                    &[#(#expected),*],
+                );
+            }
+            #[::nu_test_support::harness::linkme::distributed_slice(
+                ::nu_test_support::harness::TESTS
+            )]
+            static #entry: ::nu_test_support::harness::TestMetadata =
+                ::nu_test_support::harness::TestMetadata {
+                    name: concat!(module_path!(), "::scripts::", #stem),
+                    file: #file,
+                    line: 1,
+                    func: #func,
+                    extra: ::nu_test_support::harness::TestMetaExtra::DEFAULT,
+                };
+        });
+    }
+
+    Ok(quote! {
+        const _: () = {
+            #(#registrations)*
+        };
+    })
+}
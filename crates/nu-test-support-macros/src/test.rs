@@ -0,0 +1,471 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    punctuated::Punctuated, Attribute, Error, Expr, ExprLit, ItemFn, Lit, LitInt, LitStr,
+    MetaNameValue, Path, Token,
+};
+
+pub(crate) fn test(attr: TokenStream, item: TokenStream) -> TokenStream {
+    match try_test(attr, item) {
+        Ok(tokens) => tokens,
+        Err(error) => error.into_compile_error(),
+    }
+}
+
+fn try_test(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    if !attr.is_empty() {
+        return Err(Error::new_spanned(
+            attr,
+            "#[nu_test_support::test] takes no arguments",
+        ));
+    }
+
+    let mut item: ItemFn = syn::parse2(item)?;
+
+    let mut cwd = None;
+    let mut timeout = None;
+    let mut retry = None;
+    let mut tags = None;
+    let mut serial = false;
+    let mut isolated = false;
+    let mut nocapture = false;
+    let mut stdout_eq = None;
+    let mut stderr_contains = None;
+    let mut bench = None;
+    let mut group = None;
+    let mut env = Vec::new();
+    let mut locale = None;
+    let mut cases: Vec<TokenStream> = Vec::new();
+    let mut matrix = None;
+    item.attrs = std::mem::take(&mut item.attrs)
+        .into_iter()
+        .filter_map(|attr| match companion(&attr) {
+            Some("cwd") => {
+                cwd = Some(attr.parse_args::<LitStr>());
+                None
+            }
+            Some("timeout") => {
+                timeout = Some(attr.parse_args::<LitInt>().and_then(parse_duration));
+                None
+            }
+            Some("retry") => {
+                retry = Some(attr.parse_args::<LitInt>().and_then(parse_attempts));
+                None
+            }
+            Some("tags") => {
+                tags = Some(
+                    attr.parse_args_with(Punctuated::<LitStr, Token![,]>::parse_terminated),
+                );
+                None
+            }
+            Some("serial") => {
+                serial = true;
+                None
+            }
+            Some("isolated") => {
+                isolated = true;
+                None
+            }
+            Some("nocapture") => {
+                nocapture = true;
+                None
+            }
+            Some("stdout_eq") => {
+                stdout_eq = Some(attr.parse_args::<LitStr>());
+                None
+            }
+            Some("stderr_contains") => {
+                stderr_contains = Some(attr.parse_args::<LitStr>());
+                None
+            }
+            Some("bench") => {
+                bench = Some(match attr.meta {
+                    // A plain `#[bench]` uses the harness's default count.
+                    syn::Meta::Path(_) => Ok(None),
+                    _ => attr
+                        .parse_args::<LitInt>()
+                        .and_then(parse_iterations)
+                        .map(Some),
+                });
+                None
+            }
+            Some("case") => {
+                cases.push(match attr.meta.require_list() {
+                    Ok(list) => list.tokens.clone(),
+                    Err(_) => TokenStream::new(),
+                });
+                None
+            }
+            Some("group") => {
+                group = Some(attr.parse_args::<LitStr>());
+                None
+            }
+            Some("locale") => {
+                locale = Some(attr.parse_args::<LitStr>());
+                None
+            }
+            Some("env") => {
+                env.push(
+                    attr.parse_args_with(Punctuated::<MetaNameValue, Token![,]>::parse_terminated),
+                );
+                None
+            }
+            Some("experimental_matrix") => {
+                matrix =
+                    Some(attr.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated));
+                None
+            }
+            _ => Some(attr),
+        })
+        .collect();
+    let cwd = match cwd.transpose()? {
+        // Resolve at compile time against the test crate's own root.
+        Some(path) => quote!(Some(concat!(env!("CARGO_MANIFEST_DIR"), "/", #path))),
+        None => quote!(None),
+    };
+    let timeout = match timeout.transpose()? {
+        Some(millis) => quote!(Some(::std::time::Duration::from_millis(#millis))),
+        None => quote!(None),
+    };
+    let retry = match retry.transpose()? {
+        Some(attempts) => quote!(#attempts),
+        None => quote!(1),
+    };
+    let tags = match tags.transpose()? {
+        Some(tags) => {
+            let tags = tags.iter();
+            quote!(&[#(#tags),*])
+        }
+        None => quote!(&[]),
+    };
+
+    let stdout_eq = match stdout_eq.transpose()? {
+        Some(expected) => quote!(Some(#expected)),
+        None => quote!(None),
+    };
+    let stderr_contains = match stderr_contains.transpose()? {
+        Some(needle) => quote!(Some(#needle)),
+        None => quote!(None),
+    };
+    let group = match group.transpose()? {
+        Some(group) => quote!(Some(#group)),
+        None => quote!(None),
+    };
+    let locale = match locale.transpose()? {
+        Some(locale) => quote!(Some(#locale)),
+        None => quote!(None),
+    };
+    let bench = match bench.transpose()? {
+        Some(Some(iterations)) => quote!(Some(#iterations)),
+        Some(None) => quote!(Some(::nu_test_support::harness::DEFAULT_BENCH_ITERATIONS)),
+        None => quote!(None),
+    };
+
+    // String literals are stored as-is; any other expression becomes a
+    // helper function the harness evaluates at group setup time, with the
+    // run's sandbox path in scope as `sandbox`.
+    let mut env_helpers = Vec::new();
+    let mut env_entries = Vec::new();
+    for declarations in env {
+        for declaration in declarations? {
+            let key = declaration.path.require_ident()?.to_string();
+            match &declaration.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(value),
+                    ..
+                }) => env_entries.push(quote! {
+                    ::nu_test_support::harness::EnvVar {
+                        key: #key,
+                        value: ::nu_test_support::harness::EnvValue::Static(#value),
+                    }
+                }),
+                expr => {
+                    let helper = format_ident!("__kitest_env_{}", env_helpers.len());
+                    env_helpers.push(quote! {
+                        #[allow(unused_variables)]
+                        fn #helper(
+                            context: &::nu_test_support::harness::EnvContext,
+                        ) -> ::std::string::String {
+                            let sandbox = context.sandbox();
+                            ::std::convert::Into::into(#expr)
+                        }
+                    });
+                    env_entries.push(quote! {
+                        ::nu_test_support::harness::EnvVar {
+                            key: #key,
+                            value: ::nu_test_support::harness::EnvValue::Dynamic(#helper),
+                        }
+                    });
+                }
+            }
+        }
+    }
+    let env = quote!(&[#(#env_entries),*]);
+
+    let name = &item.sig.ident;
+    let asyncness = item.sig.asyncness.is_some();
+    let extra = quote! {
+        ::nu_test_support::harness::TestMetaExtra {
+            cwd: #cwd,
+            timeout: #timeout,
+            retry: #retry,
+            tags: #tags,
+            serial: #serial,
+            isolated: #isolated,
+            group: #group,
+            bench: #bench,
+            stdout_eq: #stdout_eq,
+            stderr_contains: #stderr_contains,
+            nocapture: #nocapture,
+            env: #env,
+            locale: #locale,
+            ..::nu_test_support::harness::TestMetaExtra::DEFAULT
+        }
+    };
+
+    let registrations: Vec<TokenStream> = if let Some(options) = matrix.transpose()? {
+        if !cases.is_empty() {
+            return Err(Error::new_spanned(
+                &item.sig.ident,
+                "#[experimental_matrix(...)] cannot be combined with #[case(...)]",
+            ));
+        }
+        if !item.sig.inputs.is_empty() {
+            return Err(Error::new_spanned(
+                &item.sig.inputs,
+                "#[experimental_matrix(...)] tests cannot take arguments",
+            ));
+        }
+        if options.is_empty() {
+            return Err(Error::new_spanned(
+                &item.sig.ident,
+                "#[experimental_matrix(...)] needs at least one experimental option",
+            ));
+        }
+        let options: Vec<&Path> = options.iter().collect();
+        // One registered test per on/off combination of the listed options,
+        // each running the body under a thread-local override guard.
+        (0..1u64 << options.len())
+            .map(|combination| {
+                let values: Vec<bool> = (0..options.len())
+                    .map(|bit| combination & (1 << bit) != 0)
+                    .collect();
+                let suffix = options
+                    .iter()
+                    .zip(&values)
+                    .map(|(option, on)| {
+                        let option = option
+                            .segments
+                            .last()
+                            .expect("a path has at least one segment")
+                            .ident
+                            .to_string()
+                            .to_lowercase();
+                        let state = if *on { "on" } else { "off" };
+                        format!("{option}={state}")
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",");
+                let suffix = format!("::{suffix}");
+                let matrix_fn = format_ident!("__kitest_matrix_{combination}");
+                let overrides = options.iter().zip(&values).map(|(option, on)| {
+                    quote!((&#option, #on))
+                });
+                let call = call_body(asyncness, quote!(#name()));
+                let wrapper = quote! {
+                    fn #matrix_fn() {
+                        let _guard =
+                            ::nu_test_support::harness::nu_experimental::test_support
+                                ::ExperimentalOptionsGuard::with([#(#overrides),*]);
+                        #call;
+                    }
+                };
+                let entry = registration(
+                    &format_ident!("ENTRY_MATRIX_{combination}"),
+                    quote!(concat!(module_path!(), "::", stringify!(#name), #suffix)),
+                    quote!(#matrix_fn),
+                    &extra,
+                );
+                quote! {
+                    #wrapper
+                    #entry
+                }
+            })
+            .collect()
+    } else if cases.is_empty() {
+        if !item.sig.inputs.is_empty() {
+            return Err(Error::new_spanned(
+                &item.sig.inputs,
+                "kitest tests without #[case(...)] cannot take arguments",
+            ));
+        }
+        if asyncness {
+            // Register a synchronous wrapper driving the future instead.
+            let async_fn = format_ident!("__kitest_async");
+            let call = call_body(true, quote!(#name()));
+            let wrapper = quote! {
+                fn #async_fn() {
+                    #call;
+                }
+            };
+            let entry = registration(
+                &format_ident!("ENTRY"),
+                quote!(concat!(module_path!(), "::", stringify!(#name))),
+                quote!(#async_fn),
+                &extra,
+            );
+            vec![quote! {
+                #wrapper
+                #entry
+            }]
+        } else {
+            vec![registration(
+                &format_ident!("ENTRY"),
+                quote!(concat!(module_path!(), "::", stringify!(#name))),
+                quote!(#name),
+                &extra,
+            )]
+        }
+    } else {
+        // One registered test per `#[case(...)]`, each through a thin
+        // wrapper applying the case's arguments, named `test::case_N`.
+        cases
+            .iter()
+            .enumerate()
+            .map(|(index, args)| {
+                let number = index + 1;
+                let case_fn = format_ident!("__kitest_case_{number}");
+                let suffix = format!("::case_{number}");
+                let call = call_body(asyncness, quote!(#name(#args)));
+                let wrapper = quote! {
+                    fn #case_fn() {
+                        #call;
+                    }
+                };
+                let entry = registration(
+                    &format_ident!("ENTRY_{number}"),
+                    quote!(concat!(module_path!(), "::", stringify!(#name), #suffix)),
+                    quote!(#case_fn),
+                    &extra,
+                );
+                quote! {
+                    #wrapper
+                    #entry
+                }
+            })
+            .collect()
+    };
+
+    Ok(quote! {
+        #item
+
+        const _: () = {
+            #(#env_helpers)*
+            #(#registrations)*
+        };
+    })
+}
+
+/// The expression a wrapper runs the test body with; `async fn` bodies are
+/// driven to completion on the harness's executor.
+fn call_body(asyncness: bool, call: TokenStream) -> TokenStream {
+    if asyncness {
+        quote!(::nu_test_support::harness::block_on(#call))
+    } else {
+        call
+    }
+}
+
+fn registration(
+    entry: &proc_macro2::Ident,
+    name: TokenStream,
+    func: TokenStream,
+    extra: &TokenStream,
+) -> TokenStream {
+    quote! {
+        #[::nu_test_support::harness::linkme::distributed_slice(
+            ::nu_test_support::harness::TESTS
+        )]
+        // The functional update is intentional future-proofing, even when
+        // every field happens to be filled in.
+        #[allow(clippy::needless_update)]
+        static #entry: ::nu_test_support::harness::TestMetadata =
+            ::nu_test_support::harness::TestMetadata {
+                name: #name,
+                file: file!(),
+                line: line!(),
+                func: #func,
+                extra: #extra,
+            };
+    }
+}
+
+/// The name of the companion attribute this macro consumes, if it is one.
+fn companion(attr: &Attribute) -> Option<&'static str> {
+    const COMPANIONS: &[&str] = &[
+        "bench",
+        "case",
+        "cwd",
+        "env",
+        "experimental_matrix",
+        "group",
+        "isolated",
+        "locale",
+        "nocapture",
+        "retry",
+        "serial",
+        "stderr_contains",
+        "stdout_eq",
+        "tags",
+        "timeout",
+    ];
+    COMPANIONS
+        .iter()
+        .find(|name| attr.path().is_ident(name))
+        .copied()
+}
+
+/// Parse a duration literal like `30sec`, `500ms` or `2min` into milliseconds.
+///
+/// The unit is the literal's suffix, so the whole duration stays one token.
+fn parse_duration(literal: LitInt) -> syn::Result<u64> {
+    let amount: u64 = literal.base10_parse()?;
+    let factor = match literal.suffix() {
+        "ms" => 1,
+        "s" | "sec" => 1_000,
+        "min" => 60_000,
+        unit => {
+            return Err(Error::new_spanned(
+                &literal,
+                format!("unknown duration unit {unit:?}, expected ms, sec or min"),
+            ))
+        }
+    };
+    amount.checked_mul(factor).ok_or_else(|| {
+        Error::new_spanned(&literal, "duration does not fit into u64 milliseconds")
+    })
+}
+
+/// Parse the iteration count of `#[bench(...)]`.
+fn parse_iterations(literal: LitInt) -> syn::Result<u32> {
+    let iterations: u32 = literal.base10_parse()?;
+    if iterations == 0 {
+        return Err(Error::new_spanned(
+            &literal,
+            "#[bench] needs at least one iteration",
+        ));
+    }
+    Ok(iterations)
+}
+
+/// Parse the attempt count of `#[retry(...)]`.
+fn parse_attempts(literal: LitInt) -> syn::Result<u32> {
+    let attempts: u32 = literal.base10_parse()?;
+    if attempts == 0 {
+        return Err(Error::new_spanned(
+            &literal,
+            "#[retry] needs at least one attempt",
+        ));
+    }
+    Ok(attempts)
+}
@@ -0,0 +1,73 @@
+//! The runtime side of
+//! [`discover_compile_fail_tests!`](crate::discover_compile_fail_tests):
+//! compile a fixture that must not compile and assert its diagnostics.
+
+use std::path::{Path, PathBuf};
+
+/// Compile a fixture against this crate and assert that it fails with every
+/// `// expect-error:` annotation in the diagnostics.
+///
+/// The fixture is built with the `rustc` on `PATH` — the same toolchain
+/// cargo used for the test binary — as a `lib` crate with `nu_test_support`
+/// as an extern, resolving transitive dependencies out of the test binary's
+/// own `deps` directory. Called by the registrations the discovery macro
+/// generates, not directly by tests.
+pub fn run_compile_fail(name: &str, fixture: &str, expected: &[&str]) {
+    let deps = deps_dir();
+    let support = newest_lib(&deps, "libnu_test_support-", ".rlib");
+    let out_dir = std::env::temp_dir().join(format!("kitest-compile-fail-{}", std::process::id()));
+    std::fs::create_dir_all(&out_dir).expect("can create the compile-fail out dir");
+
+    let output = std::process::Command::new("rustc")
+        .args(["--edition", "2021", "--crate-type", "lib", "--emit", "metadata"])
+        .arg("--out-dir")
+        .arg(&out_dir)
+        .arg("-L")
+        .arg(format!("dependency={}", deps.display()))
+        .arg("--extern")
+        .arg(format!("nu_test_support={}", support.display()))
+        .arg(fixture)
+        .output()
+        .expect("can run rustc on the fixture");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(
+        !output.status.success(),
+        "fixture {name}.rs compiled, but must not",
+    );
+    for expected in expected {
+        assert!(
+            stderr.contains(expected),
+            "fixture {name}.rs did not produce the expected diagnostic {expected:?}; rustc said:\n{stderr}",
+        );
+    }
+}
+
+/// The `target/<profile>/deps` directory the test binary itself lives in.
+fn deps_dir() -> PathBuf {
+    std::env::current_exe()
+        .expect("can locate the test binary")
+        .parent()
+        .expect("the test binary has a parent directory")
+        .to_path_buf()
+}
+
+/// The most recently built library matching `libname-<hash>`, so stale
+/// artifacts of earlier builds don't shadow the current one.
+fn newest_lib(deps: &Path, prefix: &str, extension: &str) -> PathBuf {
+    std::fs::read_dir(deps)
+        .expect("can read the deps directory")
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|file| file.to_str())
+                .is_some_and(|file| file.starts_with(prefix) && file.ends_with(extension))
+        })
+        .max_by_key(|path| {
+            path.metadata()
+                .and_then(|metadata| metadata.modified())
+                .expect("library artifacts have timestamps")
+        })
+        .unwrap_or_else(|| panic!("no {prefix}*{extension} in {}", deps.display()))
+}
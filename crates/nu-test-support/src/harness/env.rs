@@ -0,0 +1,158 @@
+//! Environment variables declared on tests with `#[env(...)]`.
+
+use super::TestMetadata;
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+};
+
+/// One env var declared with `#[env(KEY = ...)]`.
+#[derive(Debug, Clone, Copy)]
+pub struct EnvVar {
+    /// The variable's name, the left-hand side of the declaration.
+    pub key: &'static str,
+    /// The variable's value, resolved at group setup time.
+    pub value: EnvValue,
+}
+
+/// The value side of an `#[env(...)]` declaration.
+#[derive(Debug, Clone, Copy)]
+pub enum EnvValue {
+    /// A string literal, known at compile time.
+    Static(&'static str),
+    /// Any other expression. The macro wraps it into a function the harness
+    /// calls at group setup time, with the run's [`EnvContext`] in reach, so
+    /// values can reference per-run paths that don't exist at compile time.
+    Dynamic(fn(&EnvContext) -> String),
+}
+
+/// What dynamic `#[env(...)]` expressions can reach when they are evaluated.
+///
+/// Inside the expression the context's sandbox is in scope as `sandbox`, so
+/// `#[env(DATA = sandbox.join("data").display().to_string())]` resolves
+/// against the run's scratch directory.
+pub struct EnvContext {
+    sandbox: PathBuf,
+}
+
+impl EnvContext {
+    /// The per-run scratch directory of this harness process.
+    pub fn sandbox(&self) -> &Path {
+        &self.sandbox
+    }
+}
+
+/// The context all dynamic values of this run are evaluated against; the
+/// sandbox is created on first use and keyed by PID, so concurrent harness
+/// processes don't share it.
+pub(super) fn context() -> &'static EnvContext {
+    static CONTEXT: OnceLock<EnvContext> = OnceLock::new();
+    CONTEXT.get_or_init(|| {
+        let sandbox = std::env::temp_dir().join(format!("kitest-{}", std::process::id()));
+        std::fs::create_dir_all(&sandbox).expect("can create the kitest sandbox");
+        EnvContext { sandbox }
+    })
+}
+
+/// Resolve a test's `#[env(...)]` declarations into concrete values.
+pub(super) fn resolve(test: &TestMetadata) -> Vec<(String, String)> {
+    let context = context();
+    test.extra
+        .env
+        .iter()
+        .map(|var| {
+            let value = match var.value {
+                EnvValue::Static(value) => value.to_string(),
+                EnvValue::Dynamic(eval) => eval(context),
+            };
+            (var.key.to_string(), value)
+        })
+        .collect()
+}
+
+type GroupEnv = HashMap<&'static str, Vec<(String, String)>>;
+
+fn group_env_map() -> &'static Mutex<GroupEnv> {
+    static MAP: OnceLock<Mutex<GroupEnv>> = OnceLock::new();
+    MAP.get_or_init(Default::default)
+}
+
+/// Evaluate the env declarations of every grouped, selected test.
+///
+/// Runs once at group setup time — not at macro expansion and not per test —
+/// so dynamic values see the sandbox and whatever the setups prepared, and
+/// every test of a group agrees on the same resolved values.
+pub(super) fn resolve_groups(selected: &[&'static TestMetadata]) {
+    let mut groups = GroupEnv::new();
+    for test in selected {
+        let Some(group) = test.extra.group else {
+            continue;
+        };
+        if test.extra.env.is_empty() {
+            continue;
+        }
+        groups.entry(group).or_default().extend(resolve(test));
+    }
+    *group_env_map().lock().expect("no poisoned group env") = groups;
+}
+
+/// The resolved `#[env(...)]` values of a group, in declaration order.
+///
+/// `None` until the harness evaluated the group's declarations at setup
+/// time, or when no selected test of the group declares env vars.
+pub fn group_env(group: &str) -> Option<Vec<(String, String)>> {
+    group_env_map()
+        .lock()
+        .expect("no poisoned group env")
+        .get(group)
+        .cloned()
+}
+
+/// Whether the harness has to apply env vars around this test: its own
+/// `#[env(...)]` declarations, or those of its group.
+///
+/// Such tests are scheduled into the serial bucket, since the environment is
+/// process-wide; applying it while another group's tests run in parallel
+/// would race.
+pub(super) fn has_env(test: &TestMetadata) -> bool {
+    !test.extra.env.is_empty()
+        || test
+            .extra
+            .group
+            .is_some_and(|group| group_env(group).is_some())
+}
+
+/// Applied env vars, restored to their previous state on drop.
+pub(super) struct EnvGuard {
+    saved: Vec<(String, Option<OsString>)>,
+}
+
+impl EnvGuard {
+    /// Set the given vars, remembering what each one was before.
+    pub(super) fn apply(vars: &[(String, String)]) -> Self {
+        let saved = vars
+            .iter()
+            .map(|(key, value)| {
+                let previous = std::env::var_os(key);
+                std::env::set_var(key, value);
+                (key.clone(), previous)
+            })
+            .collect();
+        EnvGuard { saved }
+    }
+}
+
+impl Drop for EnvGuard {
+    fn drop(&mut self) {
+        // Restore in reverse, so a key declared twice ends up back at its
+        // oldest value.
+        for (key, previous) in self.saved.drain(..).rev() {
+            match previous {
+                Some(value) => std::env::set_var(&key, value),
+                None => std::env::remove_var(&key),
+            }
+        }
+    }
+}
@@ -0,0 +1,52 @@
+//! Detection of process-global state leaking out of tests.
+
+use std::{collections::BTreeMap, ffi::OsString, path::PathBuf};
+
+/// The process-global state of the moment a test started.
+///
+/// Captured before and compared after a test when the harness runs with
+/// `--check-leaks`; groups share one process, so a test leaking an env var
+/// or the working directory silently changes what later tests see.
+pub(super) struct LeakSnapshot {
+    cwd: Option<PathBuf>,
+    env: BTreeMap<OsString, OsString>,
+}
+
+impl LeakSnapshot {
+    pub(super) fn capture() -> Self {
+        LeakSnapshot {
+            cwd: std::env::current_dir().ok(),
+            env: std::env::vars_os().collect(),
+        }
+    }
+
+    /// Describe what changed since the capture, if anything did.
+    pub(super) fn diff(&self) -> Option<String> {
+        let mut leaks = Vec::new();
+
+        let cwd = std::env::current_dir().ok();
+        if cwd != self.cwd {
+            leaks.push(format!(
+                "cwd changed from {:?} to {:?}",
+                self.cwd.as_deref().unwrap_or_else(|| "?".as_ref()),
+                cwd.as_deref().unwrap_or_else(|| "?".as_ref()),
+            ));
+        }
+
+        let env: BTreeMap<OsString, OsString> = std::env::vars_os().collect();
+        for (key, value) in &env {
+            match self.env.get(key) {
+                None => leaks.push(format!("env var {key:?} added")),
+                Some(old) if old != value => leaks.push(format!("env var {key:?} changed")),
+                Some(_) => {}
+            }
+        }
+        for key in self.env.keys() {
+            if !env.contains_key(key) {
+                leaks.push(format!("env var {key:?} removed"));
+            }
+        }
+
+        (!leaks.is_empty()).then(|| leaks.join(", "))
+    }
+}
@@ -0,0 +1,87 @@
+//! Opt-in peak memory tracking behind the `memory-tracking` feature.
+//!
+//! The feature installs a global allocator wrapping the system one, keeping
+//! a running total and a high-water mark of live allocation bytes. The
+//! counters are process-wide, so like `--check-leaks` the per-test numbers
+//! are only cleanly attributable with `--test-threads 1`. Without the
+//! feature nothing is installed and the trackers report `None`.
+
+#[cfg(feature = "memory-tracking")]
+mod tracking {
+    use std::{
+        alloc::{GlobalAlloc, Layout, System},
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    pub(super) static CURRENT: AtomicUsize = AtomicUsize::new(0);
+    pub(super) static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+    /// Counts live bytes on top of the system allocator.
+    struct TrackingAllocator;
+
+    unsafe impl GlobalAlloc for TrackingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                let live = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+                PEAK.fetch_max(live, Ordering::Relaxed);
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+}
+
+/// The allocation counters around one test; a no-op without the
+/// `memory-tracking` feature.
+pub(super) struct MemoryTracker {
+    #[cfg(feature = "memory-tracking")]
+    baseline: usize,
+}
+
+impl MemoryTracker {
+    /// Reset the high-water mark to what is currently live and remember it
+    /// as this test's baseline.
+    pub(super) fn start() -> Self {
+        #[cfg(feature = "memory-tracking")]
+        let tracker = {
+            use std::sync::atomic::Ordering;
+            let live = tracking::CURRENT.load(Ordering::Relaxed);
+            tracking::PEAK.store(live, Ordering::Relaxed);
+            MemoryTracker { baseline: live }
+        };
+        #[cfg(not(feature = "memory-tracking"))]
+        let tracker = MemoryTracker {};
+        tracker
+    }
+
+    /// The peak bytes allocated on top of the baseline since
+    /// [`start`](Self::start); `None` without the feature.
+    pub(super) fn peak(self) -> Option<u64> {
+        #[cfg(feature = "memory-tracking")]
+        let peak = {
+            use std::sync::atomic::Ordering;
+            let peak = tracking::PEAK.load(Ordering::Relaxed);
+            Some(peak.saturating_sub(self.baseline) as u64)
+        };
+        #[cfg(not(feature = "memory-tracking"))]
+        let peak = None;
+        peak
+    }
+}
+
+/// Render a byte count the way humans read it in reports.
+pub(super) fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        bytes if bytes >= 1 << 20 => format!("{:.1} MiB", bytes as f64 / f64::from(1 << 20)),
+        bytes if bytes >= 1 << 10 => format!("{:.1} KiB", bytes as f64 / f64::from(1 << 10)),
+        bytes => format!("{bytes} B"),
+    }
+}
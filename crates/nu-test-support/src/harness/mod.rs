@@ -0,0 +1,813 @@
+//! The kitest harness runtime: test registration, selection and execution.
+//!
+//! Integration test binaries opt out of libtest with `harness = false` in
+//! their `[[test]]` section and call [`main`] instead. Tests register
+//! themselves into [`TESTS`] through the [`test`](crate::test) attribute:
+//!
+//! ```ignore
+//! #[nu_test_support::test]
+//! fn my_test() {
+//!     // ...
+//! }
+//!
+//! fn main() {
+//!     nu_test_support::harness::main();
+//! }
+//! ```
+
+use linkme::distributed_slice;
+use std::{
+    panic::catch_unwind,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc,
+    },
+    time::{Duration, Instant},
+};
+
+mod compile_fail;
+mod env;
+mod leaks;
+mod memory;
+mod nu_script;
+mod output_capture;
+mod report;
+mod runtime;
+mod skip;
+mod snapshot;
+
+use report::Format;
+
+pub use compile_fail::run_compile_fail;
+pub use env::{group_env, EnvContext, EnvValue, EnvVar};
+pub use nu_script::run_nu_script;
+pub use output_capture::{capture_output, CapturedOutput};
+pub use runtime::block_on;
+pub use skip::record_skip;
+pub use snapshot::{check_snapshot, normalize};
+
+// Re-exported for the registration code generated by the `test` attribute.
+pub use linkme;
+pub use nu_experimental;
+
+/// All tests registered in this binary.
+#[distributed_slice]
+pub static TESTS: [TestMetadata];
+
+/// All group fixtures registered in this binary.
+#[distributed_slice]
+pub static GROUP_FIXTURES: [GroupFixture];
+
+/// A setup or teardown hook for a test group, registered with the
+/// [`group_setup`](crate::group_setup) and
+/// [`group_teardown`](crate::group_teardown) attributes.
+///
+/// Setups run once before the suite executes any test of the group,
+/// teardowns once after the last one finished; groups without selected tests
+/// keep their fixtures untouched.
+pub struct GroupFixture {
+    /// The group this fixture belongs to, as named in `#[group("...")]`.
+    pub group: &'static str,
+    /// Runs before the group's first test.
+    pub setup: Option<fn()>,
+    /// Runs after the group's last test.
+    pub teardown: Option<fn()>,
+}
+
+/// A registered kitest test.
+pub struct TestMetadata {
+    /// The full path of the test: module path plus function name.
+    pub name: &'static str,
+    /// The file the test is defined in, for `--list` and tooling.
+    pub file: &'static str,
+    /// The line of the test's attribute in [`file`](Self::file).
+    pub line: u32,
+    /// The test body.
+    pub func: fn(),
+    /// Metadata from companion attributes.
+    pub extra: TestMetaExtra,
+}
+
+/// Metadata collected from companion attributes on a test.
+#[derive(Debug, Clone, Copy)]
+pub struct TestMetaExtra {
+    /// The working directory for the test from `#[cwd("...")]`, resolved
+    /// against the test crate's `CARGO_MANIFEST_DIR` at compile time.
+    pub cwd: Option<&'static str>,
+    /// The maximum runtime from `#[timeout(...)]` before the test counts as
+    /// failed.
+    pub timeout: Option<Duration>,
+    /// The maximum number of attempts from `#[retry(...)]`; 1 means no
+    /// retries.
+    pub retry: u32,
+    /// Labels from `#[tags(...)]` for tag-based filtering.
+    pub tags: &'static [&'static str],
+    /// Whether `#[serial]` excludes the test from parallel execution.
+    pub serial: bool,
+    /// Whether `#[isolated]` runs the test in its own child process.
+    pub isolated: bool,
+    /// The group from `#[group("...")]`, tying the test to that group's
+    /// fixtures.
+    pub group: Option<&'static str>,
+    /// The number of timed iterations from `#[bench]`; `Some` switches the
+    /// registration from a test into a benchmark.
+    pub bench: Option<u32>,
+    /// The exact stdout the body must produce, from `#[stdout_eq("...")]`.
+    pub stdout_eq: Option<&'static str>,
+    /// A substring the body's stderr must contain, from
+    /// `#[stderr_contains("...")]`.
+    pub stderr_contains: Option<&'static str>,
+    /// Whether `#[nocapture]` tees this test's captured output through to
+    /// the real streams, like a per-test `--nocapture`.
+    pub nocapture: bool,
+    /// Env vars from `#[env(...)]`; dynamic values are resolved at group
+    /// setup time.
+    pub env: &'static [EnvVar],
+    /// The locale from `#[locale("...")]`, applied through the test locale
+    /// override while the body runs.
+    pub locale: Option<&'static str>,
+}
+
+impl TestMetaExtra {
+    /// The metadata of a test without companion attributes.
+    ///
+    /// Generated registrations fill in the fields they know and functionally
+    /// update from this, so new fields don't break older expansions.
+    pub const DEFAULT: Self = TestMetaExtra {
+        cwd: None,
+        timeout: None,
+        retry: 1,
+        tags: &[],
+        serial: false,
+        isolated: false,
+        group: None,
+        bench: None,
+        stdout_eq: None,
+        stderr_contains: None,
+        nocapture: false,
+        env: &[],
+        locale: None,
+    };
+}
+
+/// The number of timed iterations a plain `#[bench]` runs.
+pub const DEFAULT_BENCH_ITERATIONS: u32 = 50;
+
+/// The result of one executed test, as handed to the reporters.
+pub(crate) struct TestResult {
+    pub(crate) test: &'static TestMetadata,
+    pub(crate) outcome: Outcome,
+    pub(crate) attempts: u32,
+    pub(crate) duration: Duration,
+    pub(crate) bench: Option<BenchStats>,
+    /// Peak live allocation bytes above the test's baseline; `Some` only
+    /// with the `memory-tracking` feature.
+    pub(crate) peak_memory: Option<u64>,
+}
+
+/// Wall-time statistics of a `#[bench]` registration.
+pub(crate) struct BenchStats {
+    pub(crate) iterations: u32,
+    pub(crate) min: Duration,
+    pub(crate) mean: Duration,
+    pub(crate) max: Duration,
+}
+
+impl TestResult {
+    pub(crate) fn is_failed(&self) -> bool {
+        matches!(
+            self.outcome,
+            Outcome::Panicked | Outcome::TimedOut(_) | Outcome::Leaked(_),
+        )
+    }
+}
+
+pub(crate) enum Outcome {
+    Passed,
+    Skipped(String),
+    Panicked,
+    TimedOut(Duration),
+    /// The body passed but leaked process-global state (with `--check-leaks`).
+    Leaked(String),
+}
+
+/// Run the registered tests, honoring libtest-style name filters.
+///
+/// Positional arguments select tests by substring (or exact name with
+/// `--exact`); `--include-tag` and `--exclude-tag` select by `#[tags(...)]`
+/// labels and can be given multiple times. `--format json` or
+/// `--format junit` replaces the human-readable output with a
+/// machine-readable report on stdout.
+///
+/// Tests run on `--test-threads` workers (defaulting to the available
+/// parallelism); `#[serial]` tests and tests with `#[cwd]` run alone
+/// afterwards. `--shard-index`/`--shard-count` deterministically keep only
+/// one shard of the suite, so CI can split it across machines.
+/// `--slowest N` prints the N slowest tests after the run and `--warn-slow
+/// <duration>` (e.g. `500ms`, `2sec`) warns about every test over the
+/// threshold, to keep an eye on suite runtime.
+/// `--nocapture` and `--show-output` tee captured output through to the
+/// real streams, like libtest; `#[nocapture]` does the same for one test.
+/// `--rerun-failed` restricts the run to the failures recorded by the
+/// previous one. `--check-leaks` fails tests that passed but left env var or
+/// working directory changes behind; since the environment is process-wide,
+/// pair it with `--test-threads 1` for reliable attribution.
+/// Exits the process with a non-zero code if any test fails.
+pub fn main() {
+    let mut args = std::env::args().skip(1);
+    let mut exact = false;
+    let mut filters = Vec::new();
+    let mut include_tags = Vec::new();
+    let mut exclude_tags = Vec::new();
+    let mut format = Format::Pretty;
+    let mut test_threads = None;
+    let mut shard_index = None;
+    let mut shard_count = None;
+    let mut rerun_failed = false;
+    let mut list = false;
+    let mut slowest = None;
+    let mut warn_slow = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--exact" => exact = true,
+            "--shard-index" => {
+                shard_index = Some(
+                    args.next()
+                        .expect("--shard-index needs an index")
+                        .parse::<u64>()
+                        .expect("--shard-index needs a number"),
+                )
+            }
+            "--shard-count" => {
+                shard_count = Some(
+                    args.next()
+                        .expect("--shard-count needs a count")
+                        .parse::<u64>()
+                        .expect("--shard-count needs a number"),
+                )
+            }
+            "--test-threads" => {
+                test_threads = Some(
+                    args.next()
+                        .expect("--test-threads needs a count")
+                        .parse::<usize>()
+                        .expect("--test-threads needs a number"),
+                )
+            }
+            "--include-tag" => {
+                include_tags.push(args.next().expect("--include-tag needs a tag"))
+            }
+            "--exclude-tag" => {
+                exclude_tags.push(args.next().expect("--exclude-tag needs a tag"))
+            }
+            "--format" => {
+                format = Format::parse(&args.next().expect("--format needs a format"))
+            }
+            "--rerun-failed" => rerun_failed = true,
+            // The libtest spellings, so `cargo test -- --nocapture` keeps
+            // working; captures are teed through instead of dropped.
+            "--nocapture" => output_capture::NO_CAPTURE.store(true, Ordering::Relaxed),
+            "--show-output" => output_capture::SHOW_OUTPUT.store(true, Ordering::Relaxed),
+            "--check-leaks" => CHECK_LEAKS.store(true, Ordering::Relaxed),
+            "--slowest" => {
+                slowest = Some(
+                    args.next()
+                        .expect("--slowest needs a count")
+                        .parse::<usize>()
+                        .expect("--slowest needs a number"),
+                )
+            }
+            "--warn-slow" => {
+                warn_slow = Some(parse_cli_duration(
+                    &args.next().expect("--warn-slow needs a duration"),
+                ))
+            }
+            "--list" => list = true,
+            "--update-snapshots" => snapshot::UPDATE_SNAPSHOTS.store(true, Ordering::Relaxed),
+            "--isolate" => FORCE_ISOLATED.store(true, Ordering::Relaxed),
+            // Internal marker: this process already is an isolated child, so
+            // run the test inline instead of recursing.
+            "--kitest-child" => IS_CHILD.store(true, Ordering::Relaxed),
+            // Tolerate flags cargo passes to every test binary.
+            _ if arg.starts_with('-') => {}
+            _ => filters.push(arg),
+        }
+    }
+
+    let mut selected: Vec<&TestMetadata> = TESTS
+        .iter()
+        .filter(|test| {
+            let name_matches = filters.is_empty()
+                || filters.iter().any(|filter| {
+                    if exact {
+                        test.name == *filter
+                    } else {
+                        test.name.contains(filter.as_str())
+                    }
+                });
+            let included = include_tags.is_empty()
+                || test
+                    .extra
+                    .tags
+                    .iter()
+                    .any(|tag| include_tags.iter().any(|include| include == tag));
+            let excluded = test
+                .extra
+                .tags
+                .iter()
+                .any(|tag| exclude_tags.iter().any(|exclude| exclude == tag));
+            name_matches && included && !excluded
+        })
+        .collect();
+
+    let failed_state = failed_state_path();
+    if rerun_failed {
+        match std::fs::read_to_string(&failed_state) {
+            Ok(recorded) => {
+                let failed: Vec<&str> = recorded
+                    .lines()
+                    .filter(|line| !line.is_empty())
+                    .collect();
+                selected.retain(|test| failed.contains(&test.name));
+            }
+            Err(_) => eprintln!(
+                "no failures recorded at {}, running the full selection",
+                failed_state.display(),
+            ),
+        }
+    }
+
+    match (shard_index, shard_count) {
+        (None, None) => {}
+        (Some(index), Some(count)) => {
+            assert!(count >= 1, "--shard-count must be at least 1");
+            assert!(
+                index < count,
+                "--shard-index {index} is out of range for --shard-count {count}",
+            );
+            // Shard on the group name when there is one, so a group's tests
+            // (and its fixtures) always land on the same machine.
+            selected.retain(|test| {
+                stable_hash(test.extra.group.unwrap_or(test.name)) % count == index
+            });
+        }
+        _ => panic!("--shard-index and --shard-count must be given together"),
+    }
+
+    if list {
+        // One line per test with its definition site, so IDEs and tools can
+        // jump there like they do for libtest.
+        for test in &selected {
+            let kind = match test.extra.bench {
+                Some(_) => "bench",
+                None => "test",
+            };
+            println!("{}: {kind} @ {}:{}", test.name, test.file, test.line);
+        }
+        return;
+    }
+
+    let pretty = format == Format::Pretty;
+    if pretty {
+        println!("\nrunning {} tests", selected.len());
+    }
+
+    // Fixtures of groups with at least one selected test run around the
+    // whole suite: setups first, teardowns in reverse once everything ran.
+    let active_groups: Vec<&str> = selected
+        .iter()
+        .filter_map(|test| test.extra.group)
+        .collect();
+    let fixtures: Vec<&GroupFixture> = GROUP_FIXTURES
+        .iter()
+        .filter(|fixture| active_groups.contains(&fixture.group))
+        .collect();
+    for fixture in &fixtures {
+        if let Some(setup) = fixture.setup {
+            setup();
+        }
+    }
+    // Dynamic `#[env(...)]` values are evaluated now, at group setup time,
+    // so they can reference per-run paths like the sandbox and whatever the
+    // setups prepared.
+    env::resolve_groups(&selected);
+
+    // Tests touching process-global state run alone: everything marked
+    // `#[serial]`, tests with `#[cwd]` since the working directory is
+    // process-wide, tests with `#[locale]` since the override is a process
+    // env var, tests with `#[env(...)]` (their own or their group's) since
+    // applying it mid-run would race other tests, and tests with output
+    // expectations since those swap the stdio descriptors.
+    // Benchmarks run alone as well, so parallel tests don't skew the timings.
+    let (mut serial, parallel): (Vec<&TestMetadata>, Vec<&TestMetadata>) =
+        selected.iter().copied().partition(|test| {
+            test.extra.serial
+                || test.extra.cwd.is_some()
+                || test.extra.locale.is_some()
+                || test.extra.stdout_eq.is_some()
+                || test.extra.stderr_contains.is_some()
+                || test.extra.bench.is_some()
+                || env::has_env(test)
+        });
+    // Keep each group's tests back to back, so its env is applied once
+    // before the first of them and restored right after the last.
+    serial.sort_by_key(|test| test.extra.group);
+
+    let threads = test_threads
+        .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()))
+        .max(1);
+
+    let mut results = Vec::with_capacity(selected.len());
+    if threads == 1 || parallel.len() <= 1 {
+        for &test in &parallel {
+            results.push(execute(test, pretty));
+        }
+    } else {
+        let next = std::sync::atomic::AtomicUsize::new(0);
+        let collected = std::sync::Mutex::new(Vec::with_capacity(parallel.len()));
+        std::thread::scope(|scope| {
+            for _ in 0..threads.min(parallel.len()) {
+                scope.spawn(|| loop {
+                    let index = next.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    let Some(&test) = parallel.get(index) else {
+                        break;
+                    };
+                    let result = execute(test, pretty);
+                    collected.lock().expect("no poisoned results").push(result);
+                });
+            }
+        });
+        results.extend(collected.into_inner().expect("no poisoned results"));
+    }
+    // A group's resolved env stays applied across its consecutive tests and
+    // is restored when the run moves past the group; ungrouped `#[env(...)]`
+    // declarations are scoped to their single test.
+    let mut group_guard: Option<(&str, env::EnvGuard)> = None;
+    for &test in &serial {
+        match test.extra.group.map(|group| (group, env::group_env(group))) {
+            Some((group, Some(vars))) => {
+                if group_guard.as_ref().map(|(active, _)| *active) != Some(group) {
+                    // Drop first: the previous group's restore has to happen
+                    // before the next group's values go in.
+                    drop(group_guard.take());
+                    group_guard = Some((group, env::EnvGuard::apply(&vars)));
+                }
+            }
+            _ => drop(group_guard.take()),
+        }
+        let _test_env = (test.extra.group.is_none() && !test.extra.env.is_empty())
+            .then(|| env::EnvGuard::apply(&env::resolve(test)));
+        results.push(execute(test, pretty));
+    }
+    drop(group_guard);
+
+    for fixture in fixtures.iter().rev() {
+        if let Some(teardown) = fixture.teardown {
+            teardown();
+        }
+    }
+
+    // Remember the failures for `--rerun-failed`; child processes spawned for
+    // isolation must not clobber the parent's list.
+    if !is_isolated_child() {
+        persist_failures(&failed_state, &results);
+    }
+
+    // Slow tests are worth a warning even when they pass; skipped tests
+    // barely ran, so they are exempt.
+    if let Some(threshold) = warn_slow {
+        for result in &results {
+            if result.duration >= threshold
+                && !matches!(result.outcome, Outcome::Skipped(_))
+            {
+                eprintln!(
+                    "warning: {} took {:?}, over the --warn-slow threshold of {threshold:?}",
+                    result.test.name, result.duration,
+                );
+            }
+        }
+    }
+
+    let failed = results.iter().filter(|result| result.is_failed()).count();
+    let skipped = results
+        .iter()
+        .filter(|result| matches!(result.outcome, Outcome::Skipped(_)))
+        .count();
+    match format {
+        Format::Pretty => {
+            if let Some(count) = slowest {
+                println!("\nslowest {count} tests:");
+                for result in report::slowest(&results, count) {
+                    println!("  {:?} {}", result.duration, result.test.name);
+                }
+            }
+            let result = if failed == 0 { "ok" } else { "FAILED" };
+            println!(
+                "\ntest result: {result}. {} passed; {skipped} skipped; {failed} failed\n",
+                results.len() - failed - skipped,
+            );
+        }
+        Format::Json => println!("{}", report::json(&results, slowest)),
+        Format::Junit => print!("{}", report::junit(&results)),
+    }
+
+    if failed > 0 {
+        std::process::exit(101);
+    }
+}
+
+fn execute(test: &'static TestMetadata, pretty: bool) -> TestResult {
+    if let Some(iterations) = test.extra.bench {
+        return execute_bench(test, iterations, pretty);
+    }
+
+    // With `--check-leaks`, compare the process-global state from before the
+    // first attempt against the state after; a body that passed but left
+    // changes behind fails the test.
+    let leak_snapshot = CHECK_LEAKS
+        .load(Ordering::Relaxed)
+        .then(leaks::LeakSnapshot::capture);
+    let memory = memory::MemoryTracker::start();
+
+    let attempts = test.extra.retry.max(1);
+    let mut outcome = Ok(());
+    let mut attempt = 0;
+    let start = Instant::now();
+    while attempt < attempts {
+        attempt += 1;
+        outcome = run_test(test);
+        // Skips are final; retrying won't install the missing precondition.
+        if matches!(outcome, Ok(()) | Err(Failure::Skipped(_))) {
+            break;
+        }
+    }
+    let duration = start.elapsed();
+    let outcome = match outcome {
+        Ok(()) => Outcome::Passed,
+        Err(Failure::Skipped(reason)) => Outcome::Skipped(reason),
+        Err(Failure::Panicked) => Outcome::Panicked,
+        Err(Failure::TimedOut(timeout)) => Outcome::TimedOut(timeout),
+    };
+    let outcome = match (outcome, leak_snapshot) {
+        (Outcome::Passed, Some(snapshot)) => match snapshot.diff() {
+            Some(leaked) => Outcome::Leaked(leaked),
+            None => Outcome::Passed,
+        },
+        (outcome, _) => outcome,
+    };
+
+    let peak_memory = memory.peak();
+
+    if pretty {
+        // One complete line per test, so parallel workers don't interleave
+        // halves.
+        let mut state = match &outcome {
+            Outcome::Passed if attempt > 1 => format!("ok (attempt {attempt} of {attempts})"),
+            Outcome::Passed => "ok".into(),
+            Outcome::Skipped(reason) => format!("skipped: {reason}"),
+            Outcome::Panicked if attempts > 1 => format!("FAILED (after {attempts} attempts)"),
+            Outcome::Panicked => "FAILED".into(),
+            Outcome::TimedOut(timeout) => format!("FAILED (timed out after {timeout:?})"),
+            Outcome::Leaked(leaked) => format!("FAILED (leaked: {leaked})"),
+        };
+        if let Some(peak) = peak_memory {
+            state.push_str(&format!(" (peak memory {})", memory::format_bytes(peak)));
+        }
+        println!("test {} ... {state}", test.name);
+    }
+
+    TestResult {
+        test,
+        outcome,
+        attempts: attempt,
+        duration,
+        bench: None,
+        peak_memory,
+    }
+}
+
+/// Run a `#[bench]` registration: one untimed warmup, then timed iterations.
+fn execute_bench(test: &'static TestMetadata, iterations: u32, pretty: bool) -> TestResult {
+    let memory = memory::MemoryTracker::start();
+    let start = Instant::now();
+    let mut samples = Vec::with_capacity(iterations as usize);
+    let mut outcome = if run_body(test).is_err() {
+        Outcome::Panicked
+    } else {
+        Outcome::Passed
+    };
+    if matches!(outcome, Outcome::Passed) {
+        for _ in 0..iterations {
+            let iteration = Instant::now();
+            if run_body(test).is_err() {
+                outcome = Outcome::Panicked;
+                break;
+            }
+            samples.push(iteration.elapsed());
+        }
+    }
+    let duration = start.elapsed();
+
+    let bench = (!samples.is_empty()).then(|| BenchStats {
+        iterations: samples.len() as u32,
+        min: samples.iter().min().copied().unwrap_or_default(),
+        mean: samples.iter().sum::<Duration>() / samples.len() as u32,
+        max: samples.iter().max().copied().unwrap_or_default(),
+    });
+
+    if pretty {
+        let state = match (&outcome, &bench) {
+            (Outcome::Passed, Some(stats)) => format!(
+                "bench: {} iterations, min {:?}, mean {:?}, max {:?}",
+                stats.iterations, stats.min, stats.mean, stats.max,
+            ),
+            _ => "FAILED".into(),
+        };
+        println!("test {} ... {state}", test.name);
+    }
+
+    TestResult {
+        test,
+        outcome,
+        attempts: 1,
+        duration,
+        bench,
+        peak_memory: memory.peak(),
+    }
+}
+
+// Everything but a plain pass; skipping isn't a failure, but it travels the
+// same way from the body back to `execute`.
+enum Failure {
+    Skipped(String),
+    Panicked,
+    TimedOut(Duration),
+}
+
+/// Where this binary's failures are recorded between runs: next to the test
+/// binary itself, so every integration test keeps its own list.
+fn failed_state_path() -> std::path::PathBuf {
+    std::env::current_exe()
+        .expect("can locate the test binary")
+        .with_extension("kitest-failed")
+}
+
+/// Write the names of failed tests for `--rerun-failed`, or clear the list
+/// after a fully green run.
+fn persist_failures(path: &std::path::Path, results: &[TestResult]) {
+    let failed: Vec<&str> = results
+        .iter()
+        .filter(|result| result.is_failed())
+        .map(|result| result.test.name)
+        .collect();
+    if failed.is_empty() {
+        let _ = std::fs::remove_file(path);
+    } else if let Err(err) = std::fs::write(path, failed.join("\n") + "\n") {
+        eprintln!("could not record failures at {}: {err}", path.display());
+    }
+}
+
+/// Parse a CLI duration like `500ms`, `2sec` or `1min`; a bare number is
+/// taken as milliseconds.
+fn parse_cli_duration(arg: &str) -> Duration {
+    let digits = arg.len() - arg.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let (amount, unit) = arg.split_at(digits);
+    let amount: u64 = amount
+        .parse()
+        .unwrap_or_else(|_| panic!("invalid duration {arg:?}"));
+    let factor = match unit {
+        "" | "ms" => 1,
+        "s" | "sec" => 1_000,
+        "min" => 60_000,
+        _ => panic!("unknown duration unit in {arg:?}, expected ms, sec or min"),
+    };
+    Duration::from_millis(amount * factor)
+}
+
+/// FNV-1a over the name, so shard assignment stays identical across
+/// machines, runs and Rust versions (unlike `DefaultHasher`).
+fn stable_hash(name: &str) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in name.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+// Whether `--check-leaks` fails tests that leaked process-global state.
+static CHECK_LEAKS: AtomicBool = AtomicBool::new(false);
+// Whether `--isolate` forces every test into its own child process.
+static FORCE_ISOLATED: AtomicBool = AtomicBool::new(false);
+// Whether this process is an isolated child spawned by another harness run.
+static IS_CHILD: AtomicBool = AtomicBool::new(false);
+
+/// Whether the current process is an isolated child of another harness run.
+///
+/// Useful for tests that want to assert they really got their own process.
+pub fn is_isolated_child() -> bool {
+    IS_CHILD.load(Ordering::Relaxed)
+}
+
+fn run_test(test: &'static TestMetadata) -> Result<(), Failure> {
+    if (test.extra.isolated || FORCE_ISOLATED.load(Ordering::Relaxed)) && !is_isolated_child() {
+        return run_isolated(test);
+    }
+    match test.extra.timeout {
+        None => run_body(test),
+        Some(timeout) => {
+            // Run the body on a watchdog-monitored thread. A test that blows
+            // its budget is reported as failed and its thread left behind;
+            // there is no safe way to kill it, but the harness moves on
+            // instead of hanging CI.
+            let (sender, receiver) = mpsc::channel();
+            std::thread::Builder::new()
+                .name(test.name.into())
+                .spawn(move || {
+                    let _ = sender.send(run_body(test));
+                })
+                .expect("can spawn watchdog thread");
+            match receiver.recv_timeout(timeout) {
+                Ok(result) => result,
+                Err(mpsc::RecvTimeoutError::Timeout) => Err(Failure::TimedOut(timeout)),
+                Err(mpsc::RecvTimeoutError::Disconnected) => Err(Failure::Panicked),
+            }
+        }
+    }
+}
+
+/// Run a test in a fresh child process by re-executing this binary.
+///
+/// The child selects exactly this test and runs it inline; its global
+/// statics, env vars and cwd can't leak back. The child's output is only
+/// surfaced when it fails.
+fn run_isolated(test: &TestMetadata) -> Result<(), Failure> {
+    let exe = std::env::current_exe().expect("can locate the test binary");
+    let output = std::process::Command::new(exe)
+        .args(["--exact", test.name, "--kitest-child"])
+        .output()
+        .expect("can spawn isolated test process");
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        eprint!("{}", String::from_utf8_lossy(&output.stdout));
+        eprint!("{}", String::from_utf8_lossy(&output.stderr));
+        Err(Failure::Panicked)
+    }
+}
+
+fn run_body(test: &TestMetadata) -> Result<(), Failure> {
+    // `#[cwd]` switches the process working directory, so remember where we
+    // were and go back afterwards, even if the test panics.
+    let original_cwd = test
+        .extra
+        .cwd
+        .map(|_| std::env::current_dir().expect("can read current directory"));
+
+    let result = catch_unwind(|| {
+        // Dropped (and the previous locale restored) before the panic, if
+        // any, leaves this closure.
+        let _locale = test.extra.locale.map(crate::locale_override::LocaleGuard::new);
+        if let Some(cwd) = test.extra.cwd {
+            std::env::set_current_dir(cwd)
+                .unwrap_or_else(|err| panic!("could not switch to #[cwd] {cwd:?}: {err}"));
+        }
+        match (test.extra.stdout_eq, test.extra.stderr_contains) {
+            (None, None) => (test.func)(),
+            (stdout_eq, stderr_contains) => {
+                let captured = output_capture::capture_or_tee(test.func, test.extra.nocapture);
+                if let Some(expected) = stdout_eq {
+                    assert_eq!(
+                        captured.stdout, expected,
+                        "stdout does not match #[stdout_eq]",
+                    );
+                }
+                if let Some(needle) = stderr_contains {
+                    assert!(
+                        captured.stderr.contains(needle),
+                        "stderr does not contain #[stderr_contains] {needle:?}:\n{}",
+                        captured.stderr,
+                    );
+                }
+            }
+        }
+    });
+
+    if let Some(original_cwd) = original_cwd {
+        let _ = std::env::set_current_dir(original_cwd);
+    }
+
+    match result {
+        Ok(()) => match skip::take_skip() {
+            Some(reason) => Err(Failure::Skipped(reason)),
+            None => Ok(()),
+        },
+        Err(_) => {
+            // Clear a reason recorded before a later panic, so it can't leak
+            // into the next body on this thread.
+            let _ = skip::take_skip();
+            Err(Failure::Panicked)
+        }
+    }
+}
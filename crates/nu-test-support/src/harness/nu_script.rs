@@ -0,0 +1,36 @@
+//! The runtime side of [`discover_nu_scripts!`](crate::discover_nu_scripts):
+//! run a discovered `.nu` script and assert its `# expect:` annotations.
+
+use crate::kitest::NuTestBuilder;
+use nu_protocol::{Config, Value};
+
+/// Run a script on the kitest engine and compare its rendered result against
+/// the script's `# expect:` lines.
+///
+/// The result is rendered with one line per list item (scalars are a single
+/// line), so a script ending in `[1 2 3]` matches three `# expect:` lines.
+/// Called by the registrations [`discover_nu_scripts!`](crate::discover_nu_scripts)
+/// generates, not directly by tests.
+pub fn run_nu_script(name: &str, source: &str, expected: &[&str]) {
+    let value = NuTestBuilder::new()
+        .execute(source)
+        .unwrap_or_else(|error| panic!("script {name}.nu failed:\n{error:?}"))
+        .into_value()
+        .unwrap_or_else(|error| panic!("script {name}.nu produced no value:\n{error:?}"));
+    let config = Config::default();
+    // Lists render as one line per item, everything else as a single line,
+    // so the annotations read like the script's output would.
+    let actual = match value {
+        Value::List { vals, .. } => vals
+            .iter()
+            .map(|item| item.to_expanded_string(", ", &config))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        value => value.to_expanded_string(", ", &config),
+    };
+    assert_eq!(
+        actual,
+        expected.join("\n"),
+        "script {name}.nu did not produce its # expect: lines",
+    );
+}
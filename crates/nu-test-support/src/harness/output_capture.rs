@@ -0,0 +1,178 @@
+//! Process-level stdout/stderr capture for harness tests.
+
+use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(unix)]
+use std::sync::Mutex;
+
+// The libtest flags cargo forwards to every test binary; both are honored by
+// teeing captures through to the real streams, so output stays visible live.
+pub(super) static NO_CAPTURE: AtomicBool = AtomicBool::new(false);
+pub(super) static SHOW_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// The output collected by [`capture_output`].
+#[derive(Debug, Clone)]
+pub struct CapturedOutput {
+    pub stdout: String,
+    pub stderr: String,
+}
+
+// Captures swap the process-wide stdio descriptors, so only one capture can
+// be active at a time.
+#[cfg(unix)]
+static CAPTURE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Run a closure and capture everything it writes to stdout and stderr.
+///
+/// Unlike libtest's capture this works on the file descriptor level, so
+/// output from child processes spawned inside the closure (like external
+/// commands run by an engine) is captured too, not just the `print!` family.
+///
+/// The descriptors are process-wide: output from other threads during the
+/// capture ends up in it as well, so tests using this should run with
+/// `#[serial]`. If the closure panics, the captured output is replayed onto
+/// the real stdout/stderr before the panic resumes, so the failure stays
+/// diagnosable.
+///
+/// On Windows the stdio handles can't be swapped this way; the closure runs
+/// uncaptured and both captured strings come back empty.
+pub fn capture_output(f: impl FnOnce()) -> CapturedOutput {
+    capture_or_tee(f, false)
+}
+
+/// [`capture_output`] with a per-test `#[nocapture]` override on top of the
+/// global `--nocapture`/`--show-output` flags.
+///
+/// Teed captures still collect everything for output assertions, but copy
+/// each chunk through to the real streams as it arrives — so a body hanging
+/// mid-test has its output on the terminal already.
+pub(super) fn capture_or_tee(f: impl FnOnce(), nocapture: bool) -> CapturedOutput {
+    let tee = nocapture
+        || NO_CAPTURE.load(Ordering::Relaxed)
+        || SHOW_OUTPUT.load(Ordering::Relaxed);
+    let (result, captured) = capture_output_impl(f, tee);
+    if let Err(panic) = result {
+        // A teed capture already printed live; replaying it would double it.
+        if !tee {
+            print!("{}", captured.stdout);
+            eprint!("{}", captured.stderr);
+        }
+        resume_unwind(panic);
+    }
+    captured
+}
+
+#[cfg(unix)]
+fn capture_output_impl(
+    f: impl FnOnce(),
+    tee: bool,
+) -> (std::thread::Result<()>, CapturedOutput) {
+    use std::{
+        io::Write,
+        os::fd::{AsRawFd, FromRawFd},
+    };
+
+    let _lock = CAPTURE_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let (stdout_read, stdout_write) = os_pipe::pipe().expect("can create stdout pipe");
+    let (stderr_read, stderr_write) = os_pipe::pipe().expect("can create stderr pipe");
+
+    // Flush Rust's buffers so pending output still goes to the real streams.
+    let _ = std::io::stdout().flush();
+    let _ = std::io::stderr().flush();
+
+    let saved_stdout = unsafe { libc::dup(1) };
+    let saved_stderr = unsafe { libc::dup(2) };
+    assert!(
+        saved_stdout >= 0 && saved_stderr >= 0,
+        "can save the stdio descriptors",
+    );
+    unsafe {
+        libc::dup2(stdout_write.as_raw_fd(), 1);
+        libc::dup2(stderr_write.as_raw_fd(), 2);
+    }
+    // The descriptors 1 and 2 hold the write ends now.
+    drop(stdout_write);
+    drop(stderr_write);
+
+    // Teed captures write through to copies of the real streams.
+    let tee_stdout = tee.then(|| {
+        let fd = unsafe { libc::dup(saved_stdout) };
+        assert!(fd >= 0, "can dup the real stdout for teeing");
+        unsafe { std::fs::File::from_raw_fd(fd) }
+    });
+    let tee_stderr = tee.then(|| {
+        let fd = unsafe { libc::dup(saved_stderr) };
+        assert!(fd >= 0, "can dup the real stderr for teeing");
+        unsafe { std::fs::File::from_raw_fd(fd) }
+    });
+
+    // Drain concurrently; the pipe buffer is small and a closure writing more
+    // than it holds would block forever otherwise.
+    let stdout_reader = std::thread::spawn(move || drain(stdout_read, tee_stdout));
+    let stderr_reader = std::thread::spawn(move || drain(stderr_read, tee_stderr));
+
+    let result = catch_unwind(AssertUnwindSafe(f));
+
+    let _ = std::io::stdout().flush();
+    let _ = std::io::stderr().flush();
+    // Restoring 1 and 2 closes the last write ends, so the readers hit EOF.
+    unsafe {
+        libc::dup2(saved_stdout, 1);
+        libc::dup2(saved_stderr, 2);
+        libc::close(saved_stdout);
+        libc::close(saved_stderr);
+    }
+
+    let stdout = stdout_reader.join().expect("stdout reader doesn't panic");
+    let stderr = stderr_reader.join().expect("stderr reader doesn't panic");
+    (
+        result,
+        CapturedOutput {
+            stdout: String::from_utf8_lossy(&stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&stderr).into_owned(),
+        },
+    )
+}
+
+/// Drain a pipe into a buffer, optionally copying every chunk through to the
+/// given tee as it arrives.
+#[cfg(unix)]
+fn drain(mut source: impl std::io::Read, mut tee: Option<std::fs::File>) -> Vec<u8> {
+    use std::io::Write;
+
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 8192];
+    loop {
+        match source.read(&mut chunk) {
+            Ok(0) | Err(_) => break,
+            Ok(read) => {
+                if let Some(tee) = tee.as_mut() {
+                    let _ = tee.write_all(&chunk[..read]);
+                }
+                buffer.extend_from_slice(&chunk[..read]);
+            }
+        }
+    }
+    buffer
+}
+
+#[cfg(not(unix))]
+fn capture_output_impl(
+    f: impl FnOnce(),
+    _tee: bool,
+) -> (std::thread::Result<()>, CapturedOutput) {
+    // Windows pipes are handles rather than CRT descriptors; swapping them
+    // reliably needs `_open_osfhandle` juggling that hasn't been worth it so
+    // far. Run the closure uncaptured so the test itself still executes.
+    let result = catch_unwind(AssertUnwindSafe(f));
+    (
+        result,
+        CapturedOutput {
+            stdout: String::new(),
+            stderr: String::new(),
+        },
+    )
+}
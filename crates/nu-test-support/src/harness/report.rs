@@ -0,0 +1,169 @@
+//! Machine-readable reports for CI systems ingesting kitest results.
+
+use super::{Outcome, TestResult};
+use std::fmt::Write;
+
+/// The output format of the harness, chosen with `--format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Format {
+    /// Human-readable, libtest-style lines. The default.
+    Pretty,
+    /// One JSON document describing every result.
+    Json,
+    /// JUnit XML, understood by most CI systems.
+    Junit,
+}
+
+impl Format {
+    pub(super) fn parse(format: &str) -> Format {
+        match format {
+            "pretty" => Format::Pretty,
+            "json" => Format::Json,
+            "junit" => Format::Junit,
+            other => panic!("unknown --format {other:?}, expected pretty, json or junit"),
+        }
+    }
+}
+
+/// The `count` slowest results, slowest first.
+pub(super) fn slowest(results: &[TestResult], count: usize) -> Vec<&TestResult> {
+    let mut sorted: Vec<&TestResult> = results.iter().collect();
+    sorted.sort_by_key(|result| std::cmp::Reverse(result.duration));
+    sorted.truncate(count);
+    sorted
+}
+
+/// How many slowest tests the JSON report lists when `--slowest` wasn't
+/// given.
+const DEFAULT_SLOWEST: usize = 5;
+
+pub(super) fn json(results: &[TestResult], slowest_count: Option<usize>) -> String {
+    let tests: Vec<serde_json::Value> = results
+        .iter()
+        .map(|result| {
+            serde_json::json!({
+                "name": result.test.name,
+                "file": result.test.file,
+                "line": result.test.line,
+                "tags": result.test.extra.tags,
+                "cwd": result.test.extra.cwd,
+                "outcome": match result.outcome {
+                    Outcome::Passed => "passed",
+                    Outcome::Skipped(_) => "skipped",
+                    Outcome::Panicked => "failed",
+                    Outcome::TimedOut(_) => "timed-out",
+                    Outcome::Leaked(_) => "leaked",
+                },
+                "leaked": match &result.outcome {
+                    Outcome::Leaked(leaked) => Some(leaked.as_str()),
+                    _ => None,
+                },
+                "skip_reason": match &result.outcome {
+                    Outcome::Skipped(reason) => Some(reason.as_str()),
+                    _ => None,
+                },
+                "attempts": result.attempts,
+                "duration_ms": result.duration.as_millis() as u64,
+                "peak_memory_bytes": result.peak_memory,
+                "bench": result.bench.as_ref().map(|stats| {
+                    serde_json::json!({
+                        "iterations": stats.iterations,
+                        "min_ns": stats.min.as_nanos() as u64,
+                        "mean_ns": stats.mean.as_nanos() as u64,
+                        "max_ns": stats.max.as_nanos() as u64,
+                    })
+                }),
+            })
+        })
+        .collect();
+
+    let report = serde_json::json!({
+        "passed": results
+            .iter()
+            .filter(|result| matches!(result.outcome, Outcome::Passed))
+            .count(),
+        "skipped": results
+            .iter()
+            .filter(|result| matches!(result.outcome, Outcome::Skipped(_)))
+            .count(),
+        "failed": results.iter().filter(|result| result.is_failed()).count(),
+        "slowest": slowest(results, slowest_count.unwrap_or(DEFAULT_SLOWEST))
+            .iter()
+            .map(|result| {
+                serde_json::json!({
+                    "name": result.test.name,
+                    "duration_ms": result.duration.as_millis() as u64,
+                })
+            })
+            .collect::<Vec<_>>(),
+        "tests": tests,
+    });
+    serde_json::to_string_pretty(&report).expect("report serializes")
+}
+
+pub(super) fn junit(results: &[TestResult]) -> String {
+    let failures = results.iter().filter(|result| result.is_failed()).count();
+    let skipped = results
+        .iter()
+        .filter(|result| matches!(result.outcome, Outcome::Skipped(_)))
+        .count();
+    let time: f64 = results
+        .iter()
+        .map(|result| result.duration.as_secs_f64())
+        .sum();
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        out,
+        "<testsuite name=\"kitest\" tests=\"{}\" failures=\"{failures}\" skipped=\"{skipped}\" time=\"{time:.3}\">",
+        results.len(),
+    );
+    for result in results {
+        let _ = write!(
+            out,
+            "  <testcase name=\"{}\" time=\"{:.3}\"",
+            xml_escape(result.test.name),
+            result.duration.as_secs_f64(),
+        );
+        match &result.outcome {
+            Outcome::Passed => out.push_str("/>\n"),
+            Outcome::Skipped(reason) => {
+                let _ = writeln!(
+                    out,
+                    ">\n    <skipped message=\"{}\"/>\n  </testcase>",
+                    xml_escape(reason),
+                );
+            }
+            Outcome::Panicked => {
+                let _ = writeln!(
+                    out,
+                    ">\n    <failure message=\"panicked (after {} attempts)\"/>\n  </testcase>",
+                    result.attempts,
+                );
+            }
+            Outcome::TimedOut(timeout) => {
+                let _ = writeln!(
+                    out,
+                    ">\n    <failure message=\"timed out after {timeout:?}\"/>\n  </testcase>",
+                );
+            }
+            Outcome::Leaked(leaked) => {
+                let _ = writeln!(
+                    out,
+                    ">\n    <failure message=\"leaked process state: {}\"/>\n  </testcase>",
+                    xml_escape(leaked),
+                );
+            }
+        }
+    }
+    out.push_str("</testsuite>\n");
+    out
+}
+
+fn xml_escape(input: &str) -> String {
+    input
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
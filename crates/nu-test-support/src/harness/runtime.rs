@@ -0,0 +1,36 @@
+//! The minimal future executor behind `async` kitest tests.
+
+use std::{
+    future::Future,
+    pin::pin,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::Thread,
+};
+
+/// A waker unparking the thread that polls the future.
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Drive a future to completion on the current thread.
+///
+/// This is what the wrapper generated for an `async fn` test runs: a plain
+/// poll-and-park loop, not a full runtime with a reactor. Futures from
+/// runtime-agnostic async clients work; futures needing a specific runtime's
+/// I/O driver have to start that runtime themselves inside the test.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut context = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
@@ -0,0 +1,43 @@
+//! Runtime test skipping with a reason.
+
+use std::cell::RefCell;
+
+thread_local! {
+    // The skip reason recorded by the running test body, if any. Thread-local
+    // so parallel tests can't skip each other.
+    static SKIP: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Skip the current test at runtime, with a `format!`-style reason.
+///
+/// For preconditions only known while the test runs, like an optional binary
+/// not being installed:
+///
+/// ```ignore
+/// if std::process::Command::new("sqlite3").spawn().is_err() {
+///     nu_test_support::skip!("sqlite3 is not installed");
+/// }
+/// ```
+///
+/// The harness reports the test as skipped with the reason instead of passed
+/// or failed. Expands to a `return`, so it only works directly in the test
+/// body, not inside closures.
+#[macro_export]
+macro_rules! skip {
+    ($($arg:tt)*) => {{
+        $crate::harness::record_skip(::std::format!($($arg)*));
+        return;
+    }};
+}
+
+/// The implementation behind [`skip!`](crate::skip); use the macro, which
+/// also returns from the test body.
+#[doc(hidden)]
+pub fn record_skip(reason: String) {
+    SKIP.with(|skip| *skip.borrow_mut() = Some(reason));
+}
+
+/// Take the reason recorded by the body that just ran on this thread.
+pub(super) fn take_skip() -> Option<String> {
+    SKIP.with(|skip| skip.borrow_mut().take())
+}
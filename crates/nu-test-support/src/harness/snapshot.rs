@@ -0,0 +1,91 @@
+//! Snapshot assertions backed by files under `tests/snapshots`.
+
+use crate::kitest::diff::diff_by_line;
+use std::{
+    fs,
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+// Whether `--update-snapshots` (or `KITEST_UPDATE_SNAPSHOTS`) rewrites
+// mismatching snapshots instead of failing.
+pub(super) static UPDATE_SNAPSHOTS: AtomicBool = AtomicBool::new(false);
+
+/// Assert a string matches the snapshot stored under `tests/snapshots`.
+///
+/// `assert_snapshot!("table-basic", rendered)` compares against
+/// `tests/snapshots/table-basic.snap` in the calling crate. A missing
+/// snapshot is recorded on the first run; a mismatching one fails with a
+/// diff unless the harness runs with `--update-snapshots` (or the
+/// `KITEST_UPDATE_SNAPSHOTS` env var is set, for libtest binaries).
+///
+/// Volatile content like paths, spans or durations can be normalized away
+/// with regex filters before the comparison (and before recording):
+///
+/// ```ignore
+/// assert_snapshot!("error-render", rendered, filters: [
+///     (r"\d+(\.\d+)?ms", "<duration>"),
+///     (r"/.*/kitest-[^/]+", "<sandbox>"),
+/// ]);
+/// ```
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($name:literal, $actual:expr) => {
+        $crate::harness::check_snapshot(env!("CARGO_MANIFEST_DIR"), $name, $actual.as_ref())
+    };
+    ($name:literal, $actual:expr, filters: [$(($pattern:expr, $replacement:expr)),* $(,)?]) => {
+        $crate::harness::check_snapshot(
+            env!("CARGO_MANIFEST_DIR"),
+            $name,
+            &$crate::harness::normalize($actual.as_ref(), &[$(($pattern, $replacement)),*]),
+        )
+    };
+}
+
+/// Replace every match of the filters' regex patterns with their
+/// replacements.
+///
+/// This is what the `filters:` form of
+/// [`assert_snapshot!`](crate::assert_snapshot) runs on the actual value, so
+/// snapshots never contain volatile content in the first place. Replacements
+/// support the usual `$1` capture-group syntax.
+pub fn normalize(actual: &str, filters: &[(&str, &str)]) -> String {
+    let mut normalized = actual.to_owned();
+    for (pattern, replacement) in filters {
+        let regex = regex::Regex::new(pattern)
+            .unwrap_or_else(|err| panic!("invalid snapshot filter {pattern:?}: {err}"));
+        normalized = regex.replace_all(&normalized, *replacement).into_owned();
+    }
+    normalized
+}
+
+/// The implementation behind [`assert_snapshot!`](crate::assert_snapshot);
+/// use the macro, which fills in the calling crate's root.
+#[track_caller]
+pub fn check_snapshot(manifest_dir: &str, name: &str, actual: &str) {
+    let path = Path::new(manifest_dir)
+        .join("tests/snapshots")
+        .join(format!("{name}.snap"));
+    let update = UPDATE_SNAPSHOTS.load(Ordering::Relaxed)
+        || std::env::var_os("KITEST_UPDATE_SNAPSHOTS").is_some();
+
+    match fs::read_to_string(&path) {
+        Ok(expected) if expected == actual => {}
+        Ok(expected) if !update => panic!(
+            "snapshot {name:?} differs from {}:\n{}\nrerun with --update-snapshots to accept",
+            path.display(),
+            diff_by_line(&expected, actual),
+        ),
+        Ok(_) | Err(_) => {
+            if !update && path.exists() {
+                // Unreadable but existing snapshots shouldn't be overwritten
+                // silently.
+                panic!("snapshot {name:?} at {} is not readable", path.display());
+            }
+            fs::create_dir_all(path.parent().expect("snapshot path has a parent"))
+                .expect("can create tests/snapshots");
+            fs::write(&path, actual).expect("can write snapshot");
+            eprintln!("recorded snapshot {name:?} at {}", path.display());
+        }
+    }
+}
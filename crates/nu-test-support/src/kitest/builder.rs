@@ -0,0 +1,445 @@
+use super::{MockBins, NuTestError, NuTestExecutor, Sandbox};
+use nu_experimental::{test_support::ExperimentalOptionsGuard, ExperimentalOption};
+use nu_plugin::Plugin;
+use nu_protocol::{
+    engine::{EngineState, StateWorkingSet},
+    record, ByteStream, PipelineData, ShellError, Span, Value,
+};
+use std::{fmt, path::PathBuf, sync::Arc};
+
+/// A deferred plugin registration; the working set only exists once the
+/// engine is built in [`NuTestBuilder::execute`].
+type PluginRegistration = Box<dyn FnOnce(&mut StateWorkingSet<'_>) -> Result<(), ShellError>>;
+
+/// A post-processing hook on the built engine state, from
+/// [`NuTestBuilder::with_engine`].
+type EngineHook = Box<dyn FnOnce(&mut EngineState)>;
+
+/// Describes the engine a kitest test runs against.
+///
+/// The builder always starts from the core language context
+/// ([`nu_cmd_lang::create_default_context`]); further contexts like
+/// `nu_command::add_shell_command_context` are layered on with
+/// [`add_context`](Self::add_context) by the crate under test, so this crate
+/// doesn't have to depend on every command crate.
+#[derive(Default)]
+pub struct NuTestBuilder {
+    contexts: Vec<fn(EngineState) -> EngineState>,
+    plugins: Vec<PluginRegistration>,
+    engine_hooks: Vec<EngineHook>,
+    config: Option<Value>,
+    config_toggles: Vec<(String, Value)>,
+    cwd: Option<PathBuf>,
+    envs: Vec<(String, String)>,
+    input: Option<PipelineData>,
+    locale: Option<String>,
+    experimental: Vec<(&'static ExperimentalOption, bool)>,
+    sandbox: Option<Sandbox>,
+}
+
+impl fmt::Debug for NuTestBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NuTestBuilder")
+            .field("contexts", &self.contexts)
+            .field("plugins", &self.plugins.len())
+            .field("engine_hooks", &self.engine_hooks.len())
+            .field("config", &self.config)
+            .field("config_toggles", &self.config_toggles)
+            .field("cwd", &self.cwd)
+            .field("envs", &self.envs)
+            .field("input", &self.input.is_some())
+            .field("locale", &self.locale)
+            .field("experimental", &self.experimental)
+            .field("sandbox", &self.sandbox)
+            .finish()
+    }
+}
+
+impl NuTestBuilder {
+    /// A builder for an engine with only the core language context.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Layer an additional command context onto the engine.
+    pub fn add_context(mut self, context: fn(EngineState) -> EngineState) -> Self {
+        self.contexts.push(context);
+        self
+    }
+
+    /// Register an in-process plugin's commands into the engine.
+    ///
+    /// The plugin runs on a background thread inside the test process (via
+    /// [`nu_plugin_test_support`]), so its custom values and streams behave
+    /// like a real plugin without spawning a separate binary.
+    pub fn add_plugin(mut self, name: impl Into<String>, plugin: impl Plugin + Send + 'static) -> Self {
+        let name = name.into();
+        let plugin = Arc::new(plugin);
+        self.plugins.push(Box::new(move |working_set| {
+            nu_plugin_test_support::fake_register(working_set, &name, plugin).map(|_| ())
+        }));
+        self
+    }
+
+    /// Post-process the built [`EngineState`] before anything executes.
+    ///
+    /// The hook runs after all contexts and plugins are merged but before
+    /// config and environment are applied, so tests can register extra
+    /// declarations, custom commands or tweak signatures without needing
+    /// their own context function. Hooks run in call order.
+    pub fn with_engine(mut self, hook: impl FnOnce(&mut EngineState) + 'static) -> Self {
+        self.engine_hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Layer the plugin management commands (`plugin add`, `plugin list`,
+    /// ...) onto the engine.
+    ///
+    /// Shorthand for [`add_context`](Self::add_context) with
+    /// [`nu_cmd_plugin::add_plugin_command_context`].
+    pub fn add_plugin_command_context(self) -> Self {
+        self.add_context(nu_cmd_plugin::add_plugin_command_context)
+    }
+
+    /// Run with the given `$env.config` record applied on top of the
+    /// defaults.
+    ///
+    /// The record is parsed like an interactive `$env.config` assignment, so
+    /// only the keys it mentions change; invalid settings fail the
+    /// [`execute`](Self::execute) call instead of being silently dropped.
+    pub fn config(mut self, config: Value) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Set a single config setting by its dotted `$env.config` path.
+    ///
+    /// `config_toggle("table.mode", Value::test_string("basic"))` is the
+    /// in-engine equivalent of `$env.config.table.mode = "basic"`. Toggles
+    /// apply after [`config`](Self::config) and in call order.
+    pub fn config_toggle(mut self, path: impl Into<String>, value: Value) -> Self {
+        self.config_toggles.push((path.into(), value));
+        self
+    }
+
+    /// The working directory the source runs in.
+    ///
+    /// Defaults to the process working directory.
+    pub fn cwd(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cwd = Some(path.into());
+        self
+    }
+
+    /// Set an environment variable inside the engine.
+    ///
+    /// This only touches the engine's environment, not the process
+    /// environment, so parallel tests don't race.
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.envs.push((key.into(), value.into()));
+        self
+    }
+
+    /// Freeze the engine's notion of "now" at the given RFC 3339 timestamp.
+    ///
+    /// Shorthand for [`env`](Self::env) with
+    /// [`nu_utils::deterministic::FIXED_NOW_ENV_VAR`]; `date now` returns
+    /// this timestamp instead of the real time, so date-adjacent logic is
+    /// reproducible. Only honored in debug builds, like the locale override.
+    pub fn fixed_now(self, now: impl Into<String>) -> Self {
+        self.env(nu_utils::deterministic::FIXED_NOW_ENV_VAR, now)
+    }
+
+    /// Seed the `random` commands deterministically.
+    ///
+    /// Shorthand for [`env`](Self::env) with
+    /// [`nu_utils::deterministic::RNG_SEED_ENV_VAR`]; every `random`
+    /// invocation re-seeds from this value, so a fixed seed gives a fixed
+    /// result. Only honored in debug builds.
+    pub fn rng_seed(self, seed: u64) -> Self {
+        self.env(nu_utils::deterministic::RNG_SEED_ENV_VAR, seed.to_string())
+    }
+
+    /// Resolve external commands against the given [`MockBins`] first.
+    ///
+    /// Shorthand for [`env`](Self::env) setting the engine's `PATH` to
+    /// [`MockBins::path_env`]; the mocks must outlive the execution.
+    pub fn mock_bins(self, bins: &MockBins) -> Self {
+        self.env("PATH", bins.path_env())
+    }
+
+    /// Set several environment variables inside the engine.
+    pub fn envs(
+        mut self,
+        envs: impl IntoIterator<Item = (impl Into<String>, impl Into<String>)>,
+    ) -> Self {
+        self.envs
+            .extend(envs.into_iter().map(|(key, value)| (key.into(), value.into())));
+        self
+    }
+
+    /// Feed `input` into the first executed source as its pipeline input.
+    ///
+    /// Later executions chained through
+    /// [`NuTestExecutor::execute`](super::NuTestExecutor::execute) start from
+    /// empty input again.
+    pub fn input(mut self, input: PipelineData) -> Self {
+        self.input = Some(input);
+        self
+    }
+
+    /// Feed raw bytes into the first executed source, the way stdin from an
+    /// external command would arrive.
+    ///
+    /// Shorthand for [`input`](Self::input) with a binary
+    /// [`ByteStream`], for commands like `from sqlite` that want streaming
+    /// binary input.
+    pub fn input_bytes(self, bytes: impl Into<Vec<u8>>) -> Self {
+        self.input(PipelineData::ByteStream(
+            ByteStream::read_binary(bytes.into(), Span::unknown(), None),
+            None,
+        ))
+    }
+
+    /// Run the test in a fresh, empty [`Sandbox`] directory.
+    ///
+    /// Shorthand for [`in_sandbox`](Self::in_sandbox) with a new sandbox.
+    pub fn sandbox(self) -> Self {
+        self.in_sandbox(Sandbox::new())
+    }
+
+    /// Run the test inside the given [`Sandbox`].
+    ///
+    /// Unless overridden with [`cwd`](Self::cwd), the source runs with the
+    /// sandbox root as its working directory. The sandbox lives as long as
+    /// the [`NuTestExecutor`] and is cleaned up when it drops.
+    pub fn in_sandbox(mut self, sandbox: Sandbox) -> Self {
+        self.sandbox = Some(sandbox);
+        self
+    }
+
+    /// The locale number/date formatting should follow.
+    ///
+    /// Applied through the [`locale_override`](crate::locale_override)
+    /// machinery, so commands going through `nu_utils::locale` see the given
+    /// locale while the returned [`NuTestExecutor`] lives. The override is a
+    /// process env var behind a mutex; in kitest the `#[locale("...")]`
+    /// attribute is the usual way to get one, since it also keeps the test
+    /// out of the parallel bucket.
+    pub fn locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = Some(locale.into());
+        self
+    }
+
+    /// Run the test with an experimental option toggled.
+    ///
+    /// Applied through [`nu_experimental::test_support`], so the override is
+    /// local to the executing thread and reverts when the returned
+    /// [`NuTestExecutor`] drops.
+    pub fn experimental(mut self, option: &'static ExperimentalOption, value: bool) -> Self {
+        self.experimental.push((option, value));
+        self
+    }
+
+    /// Build the engine and evaluate `source` against it.
+    ///
+    /// Returns the executor holding the resulting
+    /// [`PipelineData`](nu_protocol::PipelineData) along with the engine
+    /// state and stack, so further source can be chained with
+    /// [`NuTestExecutor::execute`].
+    pub fn execute(self, source: &str) -> Result<NuTestExecutor, NuTestError> {
+        let mut engine_state = nu_cmd_lang::create_default_context();
+        for context in self.contexts {
+            engine_state = context(engine_state);
+        }
+
+        if !self.plugins.is_empty() {
+            let mut working_set = StateWorkingSet::new(&engine_state);
+            for register in self.plugins {
+                register(&mut working_set)?;
+            }
+            let delta = working_set.render();
+            engine_state.merge_delta(delta)?;
+        }
+
+        for hook in self.engine_hooks {
+            hook(&mut engine_state);
+        }
+
+        if self.config.is_some() || !self.config_toggles.is_empty() {
+            let mut config = engine_state.get_config().clone();
+            let values = self.config.into_iter().chain(
+                self.config_toggles.into_iter().map(|(path, value)| {
+                    // Wrap the value into nested single-key records, so
+                    // "table.mode" becomes `{table: {mode: value}}`.
+                    path.rsplit('.').fold(value, |value, key| {
+                        Value::record(record! { key => value }, Span::unknown())
+                    })
+                }),
+            );
+            for mut value in values {
+                let (updated, error) = value.parse_as_config(&config);
+                if let Some(error) = error {
+                    return Err(error.into());
+                }
+                config = updated;
+            }
+            engine_state.set_config(config);
+        }
+
+        let cwd = match (self.cwd, &self.sandbox) {
+            (Some(cwd), _) => cwd,
+            (None, Some(sandbox)) => sandbox.path().to_owned(),
+            (None, None) => std::env::current_dir().map_err(|err| {
+                NuTestError::from(nu_protocol::ShellError::IOError {
+                    msg: format!("could not get current directory: {err}"),
+                })
+            })?,
+        };
+        engine_state.add_env_var(
+            "PWD".into(),
+            Value::string(cwd.to_string_lossy(), Span::unknown()),
+        );
+
+        for (key, value) in self.envs {
+            engine_state.add_env_var(key, Value::string(value, Span::unknown()));
+        }
+
+        let experimental = (!self.experimental.is_empty())
+            .then(|| ExperimentalOptionsGuard::with(self.experimental));
+        let locale = self
+            .locale
+            .map(|locale| crate::locale_override::LocaleGuard::new(&locale));
+
+        let input = self.input.unwrap_or(PipelineData::Empty);
+        let mut executor =
+            NuTestExecutor::new(engine_state, input, experimental, locale, self.sandbox);
+        executor.execute(source)?;
+        Ok(executor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+    use nu_protocol::{LabeledError, Signature, Type};
+
+    struct HelloPlugin;
+    struct Hello;
+
+    impl Plugin for HelloPlugin {
+        fn version(&self) -> String {
+            "0.0.0".into()
+        }
+
+        fn commands(&self) -> Vec<Box<dyn PluginCommand<Plugin = Self>>> {
+            vec![Box::new(Hello)]
+        }
+    }
+
+    impl SimplePluginCommand for Hello {
+        type Plugin = HelloPlugin;
+
+        fn name(&self) -> &str {
+            "hello"
+        }
+
+        fn usage(&self) -> &str {
+            "Print a friendly greeting"
+        }
+
+        fn signature(&self) -> Signature {
+            Signature::build(PluginCommand::name(self))
+                .input_output_type(Type::Nothing, Type::String)
+        }
+
+        fn run(
+            &self,
+            _plugin: &HelloPlugin,
+            _engine: &EngineInterface,
+            call: &EvaluatedCall,
+            _input: &Value,
+        ) -> Result<Value, LabeledError> {
+            Ok(Value::string("Hello, World!", call.head))
+        }
+    }
+
+    #[test]
+    fn config_toggles_reach_the_engine() {
+        let executor = NuTestBuilder::new()
+            .config_toggle("table.mode", Value::test_string("basic"))
+            .execute("null")
+            .expect("source runs");
+        assert!(matches!(
+            executor.engine_state().get_config().table_mode,
+            nu_protocol::config::TableMode::Basic,
+        ));
+    }
+
+    #[test]
+    fn invalid_config_settings_fail_the_build() {
+        NuTestBuilder::new()
+            .config_toggle("table.mode", Value::test_int(2))
+            .execute("null")
+            .expect_err("a non-string table mode is rejected");
+    }
+
+    #[test]
+    fn engine_hooks_post_process_the_state() {
+        let value = NuTestBuilder::new()
+            .with_engine(|engine_state| {
+                engine_state.add_env_var("FROM_HOOK".into(), Value::test_string("hooked"));
+            })
+            .execute("$env.FROM_HOOK")
+            .expect("source runs")
+            .into_value()
+            .expect("output collects into a value");
+        assert_eq!(value, Value::test_string("hooked"));
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn locale_override_lives_as_long_as_the_executor() {
+        let executor = NuTestBuilder::new()
+            .locale("de_DE")
+            .execute("null")
+            .expect("source runs");
+        assert_eq!(
+            nu_utils::locale::get_system_locale_string().as_deref(),
+            Some("de_DE"),
+        );
+        drop(executor);
+    }
+
+    #[test]
+    fn determinism_hooks_reach_the_engine_environment() {
+        // The commands honoring these live in nu-command; here the engine
+        // env vars themselves are asserted (see nu-command's tests for the
+        // command-level behavior).
+        let value = NuTestBuilder::new()
+            .fixed_now("2020-01-02T03:04:05+00:00")
+            .rng_seed(7)
+            .execute("[$env.NU_TEST_FIXED_NOW, $env.NU_TEST_RNG_SEED]")
+            .expect("source runs")
+            .into_value()
+            .expect("output collects into a value");
+        assert_eq!(
+            value,
+            Value::test_list(vec![
+                Value::test_string("2020-01-02T03:04:05+00:00"),
+                Value::test_string("7"),
+            ]),
+        );
+    }
+
+    #[test]
+    fn plugin_commands_run_through_the_builder() {
+        let value = NuTestBuilder::new()
+            .add_plugin("hello", HelloPlugin)
+            .execute("hello")
+            .expect("plugin command evaluates")
+            .into_value()
+            .expect("output collects into a value");
+        assert_eq!(value, Value::test_string("Hello, World!"));
+    }
+}
@@ -0,0 +1,136 @@
+use nu_ansi_term::{Color, Style};
+use nu_protocol::{Config, Value};
+use similar::{ChangeTag, TextDiff};
+use std::fmt::Write;
+
+/// Generate a stylized diff of different lines between two strings.
+pub(crate) fn diff_by_line(old: &str, new: &str) -> String {
+    let mut out = String::new();
+
+    let diff = TextDiff::from_lines(old, new);
+
+    for change in diff.iter_all_changes() {
+        let style = tag_style(change.tag());
+        let _ = write!(
+            out,
+            "{}{}",
+            style.paint(change.tag().to_string()),
+            style.paint(change.value()),
+        );
+    }
+
+    out
+}
+
+/// Generate a stylized, structure-aware diff of two values.
+///
+/// Records and lists are walked cell by cell, so a mismatch in a big table
+/// comparison names the differing cells by path (like `$.rows[3].name`)
+/// instead of dumping two whole values. Used by
+/// [`NuTestExecutor::assert_value`](super::NuTestExecutor::assert_value);
+/// removed cells render red, added ones green, like the line diff.
+pub(crate) fn diff_values(expected: &Value, actual: &Value, config: &Config) -> String {
+    let mut out = String::new();
+    diff_value_at(&mut out, "$", expected, actual, config);
+    out
+}
+
+fn diff_value_at(out: &mut String, path: &str, expected: &Value, actual: &Value, config: &Config) {
+    match (expected, actual) {
+        (Value::Record { val: expected, .. }, Value::Record { val: actual, .. }) => {
+            for (key, expected_value) in expected.iter() {
+                let path = format!("{path}.{key}");
+                match actual.get(key) {
+                    Some(actual_value) => {
+                        diff_value_at(out, &path, expected_value, actual_value, config)
+                    }
+                    None => cell_line(out, ChangeTag::Delete, &path, expected_value, config),
+                }
+            }
+            for (key, actual_value) in actual.iter() {
+                if expected.get(key).is_none() {
+                    cell_line(
+                        out,
+                        ChangeTag::Insert,
+                        &format!("{path}.{key}"),
+                        actual_value,
+                        config,
+                    );
+                }
+            }
+        }
+        (Value::List { vals: expected, .. }, Value::List { vals: actual, .. }) => {
+            for index in 0..expected.len().max(actual.len()) {
+                let path = format!("{path}[{index}]");
+                match (expected.get(index), actual.get(index)) {
+                    (Some(expected), Some(actual)) => {
+                        diff_value_at(out, &path, expected, actual, config)
+                    }
+                    (Some(expected), None) => {
+                        cell_line(out, ChangeTag::Delete, &path, expected, config)
+                    }
+                    (None, Some(actual)) => cell_line(out, ChangeTag::Insert, &path, actual, config),
+                    (None, None) => unreachable!("the loop is bounded by the longer list"),
+                }
+            }
+        }
+        _ if expected == actual => {}
+        _ => {
+            cell_line(out, ChangeTag::Delete, path, expected, config);
+            cell_line(out, ChangeTag::Insert, path, actual, config);
+        }
+    }
+}
+
+fn cell_line(out: &mut String, tag: ChangeTag, path: &str, value: &Value, config: &Config) {
+    let style = tag_style(tag);
+    let rendered = value.to_expanded_string(", ", config);
+    let _ = writeln!(out, "{}", style.paint(format!("{tag}{path} = {rendered}")));
+}
+
+fn tag_style(tag: ChangeTag) -> Style {
+    match tag {
+        ChangeTag::Equal => Style::new(),
+        ChangeTag::Delete => Color::Red.into(),
+        ChangeTag::Insert => Color::Green.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nu_protocol::record;
+
+    #[test]
+    fn value_diffs_name_the_changed_cells() {
+        let expected = Value::test_record(record! {
+            "name" => Value::test_string("nu"),
+            "size" => Value::test_int(1),
+        });
+        let actual = Value::test_record(record! {
+            "name" => Value::test_string("nu"),
+            "size" => Value::test_int(2),
+            "extra" => Value::test_bool(true),
+        });
+        let diff = diff_values(&expected, &actual, &Config::default());
+        assert!(diff.contains("-$.size = 1"));
+        assert!(diff.contains("+$.size = 2"));
+        assert!(diff.contains("+$.extra = true"));
+        assert!(!diff.contains("$.name"), "equal cells stay out of the diff");
+    }
+
+    #[test]
+    fn list_diffs_index_into_nested_values() {
+        let expected = Value::test_list(vec![
+            Value::test_record(record! { "a" => Value::test_int(1) }),
+            Value::test_record(record! { "a" => Value::test_int(2) }),
+        ]);
+        let actual = Value::test_list(vec![Value::test_record(
+            record! { "a" => Value::test_int(10) },
+        )]);
+        let diff = diff_values(&expected, &actual, &Config::default());
+        assert!(diff.contains("-$[0].a = 1"));
+        assert!(diff.contains("+$[0].a = 10"));
+        assert!(diff.contains("-$[1]"));
+    }
+}
@@ -0,0 +1,117 @@
+use nu_protocol::{
+    engine::{EngineState, StateWorkingSet},
+    format_error, ParseError, ShellError,
+};
+use std::fmt;
+
+/// An error from executing test source through the kitest harness.
+///
+/// Carries the underlying [`NuTestErrorKind`] plus, when the executor had an
+/// engine state at hand, the full miette report rendered against it. The
+/// `Debug` output — what `expect`/`unwrap` put into the panic message — shows
+/// that report, with source spans into the executed snippet, so test
+/// failures read like the real shell's errors.
+pub struct NuTestError {
+    kind: NuTestErrorKind,
+    report: Option<String>,
+}
+
+/// What went wrong executing test source.
+#[derive(Debug)]
+pub enum NuTestErrorKind {
+    /// The source didn't parse; all parse errors are collected.
+    Parse(Vec<ParseError>),
+    /// The source failed during evaluation. Boxed to keep the `Err` variant
+    /// small.
+    Shell(Box<ShellError>),
+}
+
+impl NuTestError {
+    /// The underlying parse or shell error.
+    pub fn kind(&self) -> &NuTestErrorKind {
+        &self.kind
+    }
+
+    /// The miette error code of the underlying error, if it has one.
+    ///
+    /// For parse errors this is the code of the first error.
+    pub fn code(&self) -> Option<String> {
+        let error: &dyn miette::Diagnostic = match &self.kind {
+            NuTestErrorKind::Parse(errors) => errors.first()?,
+            NuTestErrorKind::Shell(error) => error.as_ref(),
+        };
+        error.code().map(|code| code.to_string())
+    }
+
+    /// Assert the error carries the given miette error code.
+    ///
+    /// Error codes like `nu::shell::type_mismatch` are stabler to assert on
+    /// than rendered messages.
+    #[track_caller]
+    pub fn assert_error_code(&self, expected: &str) -> &Self {
+        match self.code() {
+            Some(code) if code == expected => self,
+            code => panic!("expected error code {expected:?}, got {code:?} from: {self}"),
+        }
+    }
+
+    /// Attach the report rendered against `engine_state`, so the source
+    /// spans point into the executed snippet.
+    pub(super) fn with_source(mut self, engine_state: &EngineState) -> Self {
+        let working_set = StateWorkingSet::new(engine_state);
+        let report = match &self.kind {
+            NuTestErrorKind::Parse(errors) => errors
+                .iter()
+                .map(|error| format_error(&working_set, error))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            NuTestErrorKind::Shell(error) => format_error(&working_set, error.as_ref()),
+        };
+        self.report = Some(report);
+        self
+    }
+}
+
+impl fmt::Display for NuTestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            NuTestErrorKind::Parse(errors) => {
+                write!(f, "test source failed to parse")?;
+                for error in errors {
+                    write!(f, "\n  {error}")?;
+                }
+                Ok(())
+            }
+            NuTestErrorKind::Shell(error) => write!(f, "test source failed to run: {error}"),
+        }
+    }
+}
+
+impl fmt::Debug for NuTestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.report {
+            Some(report) => write!(f, "{self}\n{report}"),
+            None => fmt::Debug::fmt(&self.kind, f),
+        }
+    }
+}
+
+impl std::error::Error for NuTestError {}
+
+impl From<ShellError> for NuTestError {
+    fn from(error: ShellError) -> Self {
+        NuTestError {
+            kind: NuTestErrorKind::Shell(Box::new(error)),
+            report: None,
+        }
+    }
+}
+
+impl From<Vec<ParseError>> for NuTestError {
+    fn from(errors: Vec<ParseError>) -> Self {
+        NuTestError {
+            kind: NuTestErrorKind::Parse(errors),
+            report: None,
+        }
+    }
+}
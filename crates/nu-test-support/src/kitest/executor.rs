@@ -0,0 +1,285 @@
+use super::{diff::diff_values, NuTestError, Sandbox};
+use crate::locale_override::LocaleGuard;
+use nu_engine::eval_block;
+use nu_experimental::test_support::ExperimentalOptionsGuard;
+use nu_parser::parse;
+use nu_protocol::{
+    debugger::WithoutDebug,
+    engine::{EngineState, Stack, StateWorkingSet},
+    PipelineData, Span, Value,
+};
+
+/// A running kitest engine, created by
+/// [`NuTestBuilder::execute`](super::NuTestBuilder::execute).
+///
+/// Holds the engine state, the stack and the output of the last execution, so
+/// tests can evaluate several sources in sequence with definitions and
+/// variables carrying over.
+pub struct NuTestExecutor {
+    engine_state: EngineState,
+    stack: Stack,
+    data: PipelineData,
+    // Pipeline input for the next execution; consumed by the first `execute`
+    // and empty afterwards.
+    input: PipelineData,
+    entry_num: usize,
+    sandbox: Option<Sandbox>,
+    // Keeps the builder's experimental option overrides active for the
+    // executor's lifetime.
+    _experimental: Option<ExperimentalOptionsGuard>,
+    // Keeps the builder's locale override active for the executor's lifetime.
+    _locale: Option<LocaleGuard>,
+}
+
+impl NuTestExecutor {
+    pub(super) fn new(
+        engine_state: EngineState,
+        input: PipelineData,
+        experimental: Option<ExperimentalOptionsGuard>,
+        locale: Option<LocaleGuard>,
+        sandbox: Option<Sandbox>,
+    ) -> Self {
+        NuTestExecutor {
+            engine_state,
+            stack: Stack::new().capture(),
+            data: PipelineData::Empty,
+            input,
+            entry_num: 1,
+            sandbox,
+            _experimental: experimental,
+            _locale: locale,
+        }
+    }
+
+    /// The sandbox the test runs in, if one was requested.
+    pub fn sandbox(&self) -> Option<&Sandbox> {
+        self.sandbox.as_ref()
+    }
+
+    /// Evaluate more source against the same engine state and stack.
+    ///
+    /// Definitions and variables from earlier executions stay in scope. The
+    /// output replaces the data of the previous execution.
+    pub fn execute(&mut self, source: &str) -> Result<&mut Self, NuTestError> {
+        let mut working_set = StateWorkingSet::new(&self.engine_state);
+        let fname = format!("kitest entry #{}", self.entry_num);
+        self.entry_num += 1;
+
+        let block = parse(&mut working_set, Some(&fname), source.as_bytes(), false);
+        let parse_errors = working_set.parse_errors.clone();
+
+        // Merge the delta even on parse errors so that rendering them against
+        // the engine state works.
+        self.engine_state.merge_delta(working_set.render())?;
+        if !parse_errors.is_empty() {
+            return Err(NuTestError::from(parse_errors).with_source(&self.engine_state));
+        }
+
+        let input = std::mem::replace(&mut self.input, PipelineData::Empty);
+        self.data = eval_block::<WithoutDebug>(&self.engine_state, &mut self.stack, &block, input)
+            .map_err(|error| NuTestError::from(error).with_source(&self.engine_state))?;
+        Ok(self)
+    }
+
+    /// The engine state of this test.
+    pub fn engine_state(&self) -> &EngineState {
+        &self.engine_state
+    }
+
+    /// The stack shared by all executions of this test.
+    pub fn stack(&self) -> &Stack {
+        &self.stack
+    }
+
+    /// The output of the last execution.
+    pub fn data(&self) -> &PipelineData {
+        &self.data
+    }
+
+    /// Take the output of the last execution, leaving empty data behind.
+    pub fn take_data(&mut self) -> PipelineData {
+        std::mem::replace(&mut self.data, PipelineData::Empty)
+    }
+
+    /// Consume the executor, returning the output of the last execution.
+    pub fn into_data(self) -> PipelineData {
+        self.data
+    }
+
+    /// Consume the executor, collecting the output into a single [`Value`].
+    pub fn into_value(self) -> Result<Value, NuTestError> {
+        Ok(self.data.into_value(Span::test_data())?)
+    }
+
+    /// Assert the output of the last execution equals `expected`.
+    ///
+    /// Takes the output; on mismatch the test fails with a structural diff
+    /// naming the differing cells by path instead of two interleaved `Debug`
+    /// dumps.
+    #[track_caller]
+    pub fn assert_value(&mut self, expected: Value) -> &mut Self {
+        let config = self.engine_state.get_config().clone();
+        let actual = self
+            .take_data()
+            .into_value(Span::test_data())
+            .expect("output collects into a value");
+        if actual != expected {
+            panic!(
+                "actual value differs from the expected value:\n{}",
+                diff_values(&expected, &actual, &config),
+            );
+        }
+        self
+    }
+
+    /// Assert the rendered output of the last execution contains `needle`.
+    ///
+    /// Takes the output and renders it the way the shell would, so this works
+    /// for byte streams and tables alike.
+    #[track_caller]
+    pub fn assert_output_contains(&mut self, needle: &str) -> &mut Self {
+        let config = self.engine_state.get_config().clone();
+        let output = self
+            .take_data()
+            .collect_string("\n", &config)
+            .expect("output collects into a string");
+        assert!(
+            output.contains(needle),
+            "output does not contain {needle:?}:\n{output}",
+        );
+        self
+    }
+}
+
+impl std::fmt::Debug for NuTestExecutor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // EngineState is neither Debug nor small; show the progress only.
+        f.debug_struct("NuTestExecutor")
+            .field("entry_num", &self.entry_num)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::kitest::{NuTestBuilder, NuTestErrorKind};
+    use nu_protocol::{Span, Value};
+
+    #[test]
+    fn executes_source_to_structured_data() {
+        let data = NuTestBuilder::new()
+            .execute("1 + 2")
+            .expect("source runs")
+            .take_data();
+        let value = data.into_value(Span::test_data()).expect("single value");
+        assert_eq!(value, Value::test_int(3));
+    }
+
+    #[test]
+    fn chained_executions_share_state() {
+        let mut executor = NuTestBuilder::new()
+            .execute("let x = 40")
+            .expect("binding runs");
+        let data = executor
+            .execute("$x + 2")
+            .expect("binding stays in scope")
+            .take_data();
+        let value = data.into_value(Span::test_data()).expect("single value");
+        assert_eq!(value, Value::test_int(42));
+    }
+
+    #[test]
+    fn envs_reach_the_executed_source() {
+        let data = NuTestBuilder::new()
+            .env("KITEST_MARKER", "hello")
+            .execute("$env.KITEST_MARKER")
+            .expect("env is set")
+            .take_data();
+        let value = data.into_value(Span::test_data()).expect("single value");
+        assert_eq!(value, Value::test_string("hello"));
+    }
+
+    #[test]
+    fn input_feeds_the_first_execution() {
+        use nu_protocol::IntoInterruptiblePipelineData;
+
+        let input = vec![Value::test_int(1), Value::test_int(2)]
+            .into_pipeline_data(Span::test_data(), None);
+        NuTestBuilder::new()
+            .input(input)
+            .execute("$in")
+            .expect("source runs")
+            .assert_value(Value::test_list(vec![
+                Value::test_int(1),
+                Value::test_int(2),
+            ]));
+    }
+
+    #[test]
+    fn input_bytes_arrive_as_a_binary_stream() {
+        let value = NuTestBuilder::new()
+            .input_bytes(&b"\x00\x01binary"[..])
+            .execute("$in")
+            .expect("source runs")
+            .into_value()
+            .expect("stream collects into a value");
+        assert_eq!(value, Value::test_binary(b"\x00\x01binary".to_vec()));
+    }
+
+    #[test]
+    fn assertions_work_on_structured_output() {
+        NuTestBuilder::new()
+            .execute("[1 2 3] | describe")
+            .expect("source runs")
+            .assert_value(Value::test_string("list<int>"));
+
+        NuTestBuilder::new()
+            .execute("'hello world'")
+            .expect("source runs")
+            .assert_output_contains("hello");
+    }
+
+    #[test]
+    fn error_codes_are_asserted_structurally() {
+        NuTestBuilder::new()
+            .execute("1 / 0")
+            .expect_err("division by zero fails")
+            .assert_error_code("nu::shell::division_by_zero");
+    }
+
+    #[test]
+    fn sandbox_is_the_working_directory() {
+        let sandbox = crate::sandbox![file "a.txt" => "content"];
+        let root = sandbox.path().to_owned();
+
+        let mut executor = NuTestBuilder::new()
+            .in_sandbox(sandbox)
+            .execute("$env.PWD")
+            .expect("source runs");
+        executor.assert_value(Value::test_string(root.to_string_lossy()));
+
+        let sandbox = executor.sandbox().expect("executor keeps the sandbox");
+        assert!(sandbox.join("a.txt").is_file());
+    }
+
+    #[test]
+    fn parse_errors_are_collected() {
+        let error = NuTestBuilder::new()
+            .execute("let = = =")
+            .expect_err("source doesn't parse");
+        assert!(matches!(error.kind(), NuTestErrorKind::Parse(errors) if !errors.is_empty()));
+    }
+
+    #[test]
+    fn failures_render_the_full_report() {
+        let error = NuTestBuilder::new()
+            .execute("1 / 0")
+            .expect_err("division by zero fails");
+        let report = format!("{error:?}");
+        assert!(report.contains("division_by_zero"));
+        assert!(
+            report.contains("1 / 0"),
+            "the report excerpts the executed snippet:\n{report}",
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use std::{fs, path::Path};
+use tempfile::TempDir;
+
+/// The scripted behavior of one fake executable.
+///
+/// Built fluently and handed to [`MockBins::bin`]:
+///
+/// ```ignore
+/// let mocks = MockBins::new();
+/// mocks.bin("git", MockBin::new().stdout("git version 2.0.0\n"));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct MockBin {
+    stdout: String,
+    stderr: String,
+    exit_code: i32,
+}
+
+impl MockBin {
+    /// A fake that prints nothing and exits with 0.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// What the fake writes to stdout, exactly as given.
+    pub fn stdout(mut self, stdout: impl Into<String>) -> Self {
+        self.stdout = stdout.into();
+        self
+    }
+
+    /// What the fake writes to stderr, exactly as given.
+    pub fn stderr(mut self, stderr: impl Into<String>) -> Self {
+        self.stderr = stderr.into();
+        self
+    }
+
+    /// The exit code the fake terminates with.
+    pub fn exit_code(mut self, exit_code: i32) -> Self {
+        self.exit_code = exit_code;
+        self
+    }
+}
+
+/// A directory of fake executables for testing `^external` handling.
+///
+/// Each binary is a small script with scripted stdout, stderr and exit code,
+/// so tests don't depend on which tools the machine happens to have.
+/// Prepend [`path_env`](Self::path_env) to the `PATH` the lookup uses — for
+/// kitest engines through
+/// [`NuTestBuilder::mock_bins`](super::NuTestBuilder::mock_bins) — and the
+/// fakes shadow any system tool of the same name. The directory is removed
+/// on drop.
+#[derive(Debug)]
+pub struct MockBins {
+    dir: TempDir,
+}
+
+impl MockBins {
+    /// Create an empty mock binary directory.
+    pub fn new() -> Self {
+        MockBins {
+            dir: TempDir::with_prefix("kitest-bin-").expect("can create mock bin temp dir"),
+        }
+    }
+
+    /// The directory the fakes live in.
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// The value `PATH` should take: the mock directory first, then the
+    /// process's current search path.
+    pub fn path_env(&self) -> String {
+        let current = std::env::var_os("PATH").unwrap_or_default();
+        let paths =
+            std::iter::once(self.dir.path().to_owned()).chain(std::env::split_paths(&current));
+        std::env::join_paths(paths)
+            .expect("mock bin path joins into PATH")
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    /// Create a fake executable with the given scripted behavior.
+    #[cfg(unix)]
+    pub fn bin(&self, name: &str, bin: MockBin) -> &Self {
+        use std::os::unix::fs::PermissionsExt;
+
+        let script = format!(
+            "#!/bin/sh\nprintf '%s' '{}'\nprintf '%s' '{}' >&2\nexit {}\n",
+            shell_quote(&bin.stdout),
+            shell_quote(&bin.stderr),
+            bin.exit_code,
+        );
+        let path = self.dir.path().join(name);
+        fs::write(&path, script).expect("can write mock binary");
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755))
+            .expect("can mark mock binary executable");
+        self
+    }
+
+    /// Create a fake executable with the given scripted behavior.
+    ///
+    /// On Windows the fake is a `.cmd` script emitting its output with
+    /// `echo`, so every stdout/stderr line ends in a newline regardless of
+    /// how the scripted text ended.
+    #[cfg(windows)]
+    pub fn bin(&self, name: &str, bin: MockBin) -> &Self {
+        let mut script = String::from("@echo off\r\n");
+        for line in bin.stdout.lines() {
+            script.push_str(&format!("echo {line}\r\n"));
+        }
+        for line in bin.stderr.lines() {
+            script.push_str(&format!("echo {line} 1>&2\r\n"));
+        }
+        script.push_str(&format!("exit /b {}\r\n", bin.exit_code));
+        let path = self.dir.path().join(format!("{name}.cmd"));
+        fs::write(&path, script).expect("can write mock binary");
+        self
+    }
+}
+
+impl Default for MockBins {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Escape `text` for inclusion in a single-quoted `sh` string.
+#[cfg(unix)]
+fn shell_quote(text: &str) -> String {
+    text.replace('\'', "'\\''")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn mocked_binaries_behave_as_scripted() {
+        let mocks = MockBins::new();
+        mocks.bin(
+            "kitest-fake-tool",
+            MockBin::new()
+                .stdout("fake output\n")
+                .stderr("fake warning: don't panic\n")
+                .exit_code(3),
+        );
+        // `Command` resolves the program against the PATH it spawns with, so
+        // this exercises the same lookup an `^external` would do.
+        let output = std::process::Command::new("kitest-fake-tool")
+            .env("PATH", mocks.path_env())
+            .output()
+            .expect("the fake tool spawns");
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "fake output\n");
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "fake warning: don't panic\n"
+        );
+        assert_eq!(output.status.code(), Some(3));
+    }
+
+    #[test]
+    fn path_env_puts_the_mock_directory_first() {
+        let mocks = MockBins::new();
+        let first = std::env::split_paths(&mocks.path_env())
+            .next()
+            .expect("PATH has at least one entry");
+        assert_eq!(first, mocks.path());
+    }
+}
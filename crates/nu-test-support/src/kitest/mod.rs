@@ -0,0 +1,25 @@
+//! The "kitest" in-process test harness.
+//!
+//! Instead of spawning a `nu` binary per test like the [`nu!`](crate::nu)
+//! macro, kitest builds an [`EngineState`](nu_protocol::engine::EngineState)
+//! inside the test process, evaluates source against it and hands back the
+//! structured [`PipelineData`](nu_protocol::PipelineData). That makes
+//! assertions on values instead of rendered strings possible and cuts the
+//! per-test process startup cost.
+//!
+//! Tests describe their engine with [`NuTestBuilder`] and get a
+//! [`NuTestExecutor`] back from [`NuTestBuilder::execute`], which keeps the
+//! engine state and stack alive for chained executions.
+
+mod builder;
+pub(crate) mod diff;
+mod error;
+mod executor;
+mod mock_bin;
+mod sandbox;
+
+pub use builder::NuTestBuilder;
+pub use error::{NuTestError, NuTestErrorKind};
+pub use executor::NuTestExecutor;
+pub use mock_bin::{MockBin, MockBins};
+pub use sandbox::Sandbox;
@@ -0,0 +1,109 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tempfile::TempDir;
+
+/// An isolated temp directory a kitest test runs in.
+///
+/// Unlike the [`Playground`](crate::playground::Playground), the sandbox
+/// doesn't need a closure-based API or knowledge of the repo layout: create
+/// one, describe its contents and hand it to the builder. The directory and
+/// everything in it are removed when the sandbox drops.
+///
+/// The [`sandbox!`](crate::sandbox) macro builds one declaratively:
+///
+/// ```ignore
+/// let sandbox = sandbox![
+///     file "a.txt" => "content",
+///     dir "sub",
+///     file "sub/b.txt" => "more",
+/// ];
+/// ```
+#[derive(Debug)]
+pub struct Sandbox {
+    dir: TempDir,
+}
+
+impl Sandbox {
+    /// Create an empty sandbox directory.
+    pub fn new() -> Self {
+        Sandbox {
+            dir: TempDir::with_prefix("kitest-").expect("can create sandbox temp dir"),
+        }
+    }
+
+    /// The root of the sandbox.
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Resolve a path relative to the sandbox root.
+    pub fn join(&self, path: impl AsRef<Path>) -> PathBuf {
+        self.dir.path().join(path)
+    }
+
+    /// Create a file with the given contents, creating parent directories as
+    /// needed.
+    pub fn file(&self, path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> &Self {
+        let path = self.join(path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("can create sandbox subdirectory");
+        }
+        fs::write(&path, contents).expect("can write sandbox fixture file");
+        self
+    }
+
+    /// Create an empty directory, including parents.
+    pub fn dir(&self, path: impl AsRef<Path>) -> &Self {
+        fs::create_dir_all(self.join(path)).expect("can create sandbox directory");
+        self
+    }
+}
+
+impl Default for Sandbox {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Build a [`Sandbox`] with declarative file fixtures.
+///
+/// Entries are either `file "path" => contents` or `dir "path"`, applied in
+/// order relative to the sandbox root.
+#[macro_export]
+macro_rules! sandbox {
+    ($($kind:ident $path:literal $(=> $contents:expr)?),* $(,)?) => {{
+        let sandbox = $crate::kitest::Sandbox::new();
+        $($crate::sandbox!(@entry sandbox, $kind $path $(=> $contents)?);)*
+        sandbox
+    }};
+    (@entry $sandbox:ident, file $path:literal => $contents:expr) => {
+        $sandbox.file($path, $contents);
+    };
+    (@entry $sandbox:ident, dir $path:literal) => {
+        $sandbox.dir($path);
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn fixtures_are_created_and_cleaned_up() {
+        let root = {
+            let sandbox = sandbox![
+                file "a.txt" => "content",
+                dir "sub",
+                file "sub/b.txt" => "more",
+            ];
+            assert_eq!(
+                std::fs::read_to_string(sandbox.join("a.txt")).unwrap(),
+                "content"
+            );
+            assert!(sandbox.join("sub").is_dir());
+            assert!(sandbox.join("sub/b.txt").is_file());
+            sandbox.path().to_owned()
+        };
+        assert!(!root.exists(), "sandbox is removed on drop");
+    }
+}
@@ -1,5 +1,7 @@
 pub mod commands;
 pub mod fs;
+pub mod harness;
+pub mod kitest;
 pub mod locale_override;
 pub mod macros;
 pub mod playground;
@@ -8,6 +10,11 @@ use std::process::ExitStatus;
 // Needs to be reexported for `nu!` macro
 pub use nu_path;
 
+// The kitest test attribute, used as `#[nu_test_support::test]`.
+pub use nu_test_support_macros::{
+    discover_compile_fail_tests, discover_nu_scripts, group_setup, group_teardown, test,
+};
+
 pub struct Outcome {
     pub out: String,
     pub err: String,
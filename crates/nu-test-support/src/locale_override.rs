@@ -1,6 +1,4 @@
-#![cfg(debug_assertions)]
-
-use std::sync::Mutex;
+use std::sync::{Mutex, MutexGuard};
 
 use nu_utils::locale::LOCALE_OVERRIDE_ENV_VAR;
 
@@ -16,11 +14,12 @@ static LOCALE_OVERRIDE_MUTEX: Mutex<()> = Mutex::new(());
 /// Environment variables are global values. So when they are changed by one
 /// thread they are changed for all others. To prevent a test from overwriting
 /// the environment variable of another test, a mutex is used.
+#[cfg(debug_assertions)]
 pub fn with_locale_override<T>(locale_string: &str, func: fn() -> T) -> T {
     let result = {
         let _lock = LOCALE_OVERRIDE_MUTEX
             .lock()
-            .expect("Failed to get mutex lock for locale override");
+            .unwrap_or_else(|poison| poison.into_inner());
 
         let saved = std::env::var(LOCALE_OVERRIDE_ENV_VAR).ok();
         std::env::set_var(LOCALE_OVERRIDE_ENV_VAR, locale_string);
@@ -37,3 +36,34 @@ pub fn with_locale_override<T>(locale_string: &str, func: fn() -> T) -> T {
     };
     result.unwrap_or_else(|err| std::panic::resume_unwind(err))
 }
+
+/// The RAII form of [`with_locale_override`], for scopes that don't fit a
+/// closure: the override stays active until the guard drops.
+///
+/// Holds the same mutex as the closure form, so concurrent locale overrides
+/// serialize against each other. Like the closure form, the override only has
+/// an effect in debug builds, where `nu_utils::locale` honors the env var.
+pub struct LocaleGuard {
+    _lock: MutexGuard<'static, ()>,
+    saved: Option<String>,
+}
+
+impl LocaleGuard {
+    pub fn new(locale_string: &str) -> Self {
+        let lock = LOCALE_OVERRIDE_MUTEX
+            .lock()
+            .unwrap_or_else(|poison| poison.into_inner());
+        let saved = std::env::var(LOCALE_OVERRIDE_ENV_VAR).ok();
+        std::env::set_var(LOCALE_OVERRIDE_ENV_VAR, locale_string);
+        LocaleGuard { _lock: lock, saved }
+    }
+}
+
+impl Drop for LocaleGuard {
+    fn drop(&mut self) {
+        match self.saved.take() {
+            Some(locale_str) => std::env::set_var(LOCALE_OVERRIDE_ENV_VAR, locale_str),
+            None => std::env::remove_var(LOCALE_OVERRIDE_ENV_VAR),
+        }
+    }
+}
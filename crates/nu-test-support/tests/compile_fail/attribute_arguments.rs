@@ -0,0 +1,4 @@
+// expect-error: #[nu_test_support::test] takes no arguments
+
+#[nu_test_support::test(exact)]
+fn attribute_with_arguments() {}
@@ -0,0 +1,8 @@
+// expect-error: #[experimental_matrix(...)] cannot be combined with #[case(...)]
+
+#[nu_test_support::test]
+#[experimental_matrix(nu_test_support::harness::nu_experimental::DATABASE_CMD_NEXT)]
+#[case(1)]
+fn matrix_and_case(value: i32) {
+    let _ = value;
+}
@@ -0,0 +1,6 @@
+// expect-error: kitest tests without #[case(...)] cannot take arguments
+
+#[nu_test_support::test]
+fn takes_arguments(value: i32) {
+    let _ = value;
+}
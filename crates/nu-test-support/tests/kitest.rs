@@ -0,0 +1,289 @@
+//! Self-test for the kitest harness: runs through `harness::main` instead of
+//! libtest (see the `[[test]]` section in `Cargo.toml`).
+
+#[nu_test_support::test]
+fn registered_tests_run() {
+    assert!(
+        nu_test_support::harness::TESTS
+            .iter()
+            .any(|test| test.name.ends_with("registered_tests_run")),
+        "the attribute registers tests into TESTS",
+    );
+}
+
+#[nu_test_support::test]
+#[cwd("tests")]
+fn cwd_attribute_switches_directory() {
+    let cwd = std::env::current_dir().expect("can read current directory");
+    assert!(
+        cwd.ends_with("tests"),
+        "expected to run in the crate's tests directory, got {}",
+        cwd.display(),
+    );
+}
+
+#[nu_test_support::test]
+#[timeout(60sec)]
+fn timeout_leaves_fast_tests_alone() {
+    std::thread::sleep(std::time::Duration::from_millis(10));
+}
+
+#[nu_test_support::test]
+#[retry(3)]
+fn flaky_tests_are_retried() {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    static ATTEMPTS: AtomicU32 = AtomicU32::new(0);
+    let attempt = ATTEMPTS.fetch_add(1, Ordering::Relaxed) + 1;
+    assert!(attempt >= 2, "first attempt fails on purpose");
+}
+
+#[nu_test_support::test]
+#[tags("self-test", "fast")]
+fn tags_are_recorded_in_metadata() {
+    let me = nu_test_support::harness::TESTS
+        .iter()
+        .find(|test| test.name.ends_with("tags_are_recorded_in_metadata"))
+        .expect("this test is registered");
+    assert_eq!(me.extra.tags, ["self-test", "fast"]);
+    assert!(me.file.ends_with("kitest.rs"));
+    assert!(me.line > 0);
+}
+
+#[nu_test_support::test]
+#[serial]
+fn serial_tests_run_alone() {
+    let me = nu_test_support::harness::TESTS
+        .iter()
+        .find(|test| test.name.ends_with("serial_tests_run_alone"))
+        .expect("this test is registered");
+    assert!(me.extra.serial);
+}
+
+#[nu_test_support::test]
+#[isolated]
+fn isolated_tests_get_their_own_process() {
+    assert!(nu_test_support::harness::is_isolated_child());
+}
+
+#[nu_test_support::test]
+#[case(1, 2, 3)]
+#[case(2, 40, 42)]
+#[case(-5, 5, 0)]
+fn addition_cases(a: i64, b: i64, sum: i64) {
+    assert_eq!(a + b, sum);
+    let cases = nu_test_support::harness::TESTS
+        .iter()
+        .filter(|test| test.name.contains("addition_cases::case_"))
+        .count();
+    assert_eq!(cases, 3, "each #[case] registers its own test");
+}
+
+#[nu_test_support::test]
+#[experimental_matrix(nu_experimental::DATABASE_CMD_NEXT)]
+fn matrix_covers_both_option_states() {
+    // The override guard installed by the matrix wrapper decides the value;
+    // both variants must be registered under their combination's name.
+    let _ = nu_experimental::DATABASE_CMD_NEXT.get();
+    let variants: Vec<_> = nu_test_support::harness::TESTS
+        .iter()
+        .filter(|test| test.name.contains("matrix_covers_both_option_states::"))
+        .map(|test| test.name)
+        .collect();
+    assert_eq!(variants.len(), 2);
+    assert!(variants
+        .iter()
+        .any(|name| name.ends_with("database_cmd_next=on")));
+    assert!(variants
+        .iter()
+        .any(|name| name.ends_with("database_cmd_next=off")));
+}
+
+#[cfg(unix)]
+#[nu_test_support::test]
+#[serial]
+fn captured_output_covers_child_processes() {
+    let captured = nu_test_support::harness::capture_output(|| {
+        println!("from print");
+        let status = std::process::Command::new("echo")
+            .arg("from child")
+            .status()
+            .expect("echo runs");
+        assert!(status.success());
+    });
+    assert!(captured.stdout.contains("from print"));
+    assert!(captured.stdout.contains("from child"));
+    assert_eq!(captured.stderr, "");
+}
+
+#[cfg(unix)]
+#[nu_test_support::test]
+#[stdout_eq("declared output\n")]
+fn stdout_eq_checks_the_captured_output() {
+    println!("declared output");
+}
+
+#[cfg(unix)]
+#[nu_test_support::test]
+#[stderr_contains("warning:")]
+fn stderr_contains_checks_the_captured_output() {
+    eprintln!("warning: something minor happened");
+}
+
+#[nu_test_support::test]
+#[bench(10)]
+fn bench_collecting_a_vec() {
+    let values: Vec<u64> = (0..10_000).collect();
+    std::hint::black_box(values);
+}
+
+#[nu_test_support::test]
+fn snapshots_compare_against_stored_files() {
+    let rendered = String::from("kitest snapshot self-test\nsecond line\n");
+    nu_test_support::assert_snapshot!("harness-self-test", rendered);
+}
+
+#[nu_test_support::test]
+async fn async_bodies_run_on_the_harness_executor() {
+    // A future that returns `Pending` once, exercising the wake path of the
+    // executor rather than just an immediately-ready value.
+    struct YieldOnce(bool);
+
+    impl std::future::Future for YieldOnce {
+        type Output = i64;
+
+        fn poll(
+            mut self: std::pin::Pin<&mut Self>,
+            context: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<i64> {
+            if self.0 {
+                std::task::Poll::Ready(42)
+            } else {
+                self.0 = true;
+                context.waker().wake_by_ref();
+                std::task::Poll::Pending
+            }
+        }
+    }
+
+    assert_eq!(YieldOnce(false).await, 42);
+}
+
+static FAKE_DB_RUNNING: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+#[nu_test_support::group_setup("self-test-db")]
+fn start_fake_db() {
+    FAKE_DB_RUNNING.store(true, std::sync::atomic::Ordering::SeqCst);
+}
+
+#[nu_test_support::group_teardown("self-test-db")]
+fn stop_fake_db() {
+    FAKE_DB_RUNNING.store(false, std::sync::atomic::Ordering::SeqCst);
+}
+
+#[nu_test_support::test]
+#[group("self-test-db")]
+fn grouped_tests_run_after_their_setup() {
+    assert!(FAKE_DB_RUNNING.load(std::sync::atomic::Ordering::SeqCst));
+}
+
+#[nu_test_support::test]
+#[group("self-test-db")]
+#[env(KITEST_STATIC = "fixed", KITEST_DATA = sandbox.join("data").display().to_string())]
+fn env_values_resolve_at_group_setup() {
+    let env = nu_test_support::harness::group_env("self-test-db")
+        .expect("the group's env was resolved at setup time");
+    assert!(env
+        .iter()
+        .any(|(key, value)| key == "KITEST_STATIC" && value == "fixed"));
+    let (_, data) = env
+        .iter()
+        .find(|(key, _)| key == "KITEST_DATA")
+        .expect("the dynamic value was evaluated");
+    assert!(
+        data.ends_with("data") && data.contains("kitest-"),
+        "expected a path into the run's sandbox, got {data:?}",
+    );
+    // The resolved values are also applied to the process environment while
+    // the group's tests run.
+    assert_eq!(std::env::var("KITEST_STATIC").as_deref(), Ok("fixed"));
+}
+
+#[nu_test_support::test]
+#[group("self-test-db")]
+fn group_env_covers_tests_without_own_declarations() {
+    // Declared on another test of the group, but applied around every test
+    // of the group.
+    assert_eq!(std::env::var("KITEST_STATIC").as_deref(), Ok("fixed"));
+}
+
+#[nu_test_support::test]
+#[nocapture]
+#[stdout_eq("teed through\n")]
+fn nocapture_tees_while_still_asserting() {
+    // The line below shows up in the harness's own output *and* satisfies
+    // the `#[stdout_eq]` expectation.
+    println!("teed through");
+}
+
+#[nu_test_support::test]
+#[env(KITEST_SOLO = "just me")]
+fn ungrouped_env_is_scoped_to_the_test() {
+    assert_eq!(std::env::var("KITEST_SOLO").as_deref(), Ok("just me"));
+}
+
+#[cfg(debug_assertions)]
+#[nu_test_support::test]
+#[locale("de_DE")]
+fn locale_attribute_overrides_the_locale() {
+    // `nu_utils::locale` honors this env var in debug builds; the attribute
+    // must have installed it before the body runs.
+    assert_eq!(
+        std::env::var("NU_TEST_LOCALE_OVERRIDE").as_deref(),
+        Ok("de_DE"),
+    );
+}
+
+#[nu_test_support::test]
+#[serial]
+fn restored_env_passes_the_leak_check() {
+    // Under `--check-leaks` this would fail without the remove; a test that
+    // cleans up after itself must stay green.
+    std::env::set_var("KITEST_LEAK_PROBE", "1");
+    std::env::remove_var("KITEST_LEAK_PROBE");
+}
+
+#[nu_test_support::test]
+fn missing_binaries_can_skip_at_runtime() {
+    if std::process::Command::new("kitest-definitely-missing")
+        .spawn()
+        .is_err()
+    {
+        nu_test_support::skip!("kitest-definitely-missing is not installed");
+    }
+    panic!("a missing binary should have skipped this test");
+}
+
+#[nu_test_support::test]
+fn snapshot_filters_normalize_volatile_content() {
+    let rendered = format!(
+        "finished in {}ms at {}\n",
+        std::process::id(),
+        std::env::temp_dir().display(),
+    );
+    nu_test_support::assert_snapshot!("filtered-self-test", rendered, filters: [
+        (r"\d+ms", "<duration>"),
+        (r"at .*", "at <path>"),
+    ]);
+}
+
+// Registers every annotated `.nu` script under `tests/scripts/` as a test.
+nu_test_support::discover_nu_scripts!();
+
+// Registers every fixture under `tests/compile_fail/` as a test asserting
+// its `// expect-error:` diagnostics.
+nu_test_support::discover_compile_fail_tests!();
+
+fn main() {
+    nu_test_support::harness::main();
+}
+
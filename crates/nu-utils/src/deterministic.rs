@@ -0,0 +1,13 @@
+//! Engine env vars that make time and randomness deterministic in tests.
+//!
+//! Like the locale override in [`locale`](crate::locale), these hooks only
+//! take effect in debug builds. They are read from the engine environment
+//! (`$env`), not the process environment, so in-process tests can set them
+//! without racing each other.
+
+/// Freezes `date now` at the given RFC 3339 timestamp.
+pub const FIXED_NOW_ENV_VAR: &str = "NU_TEST_FIXED_NOW";
+
+/// Seeds the RNG of the `random` commands with the given integer; every
+/// invocation re-seeds, so a fixed seed gives a fixed result.
+pub const RNG_SEED_ENV_VAR: &str = "NU_TEST_RNG_SEED";
@@ -1,6 +1,7 @@
 mod casing;
 pub mod ctrl_c;
 mod deansi;
+pub mod deterministic;
 pub mod emoji;
 pub mod filesystem;
 pub mod locale;
@@ -58,6 +58,14 @@ fn main() -> Result<()> {
         miette_hook(x);
     }));
 
+    // Parse experimental options from the environment before the engine
+    // state is built, as building it already depends on their values.
+    let mut experimental_report = nu_experimental::parse_env();
+    experimental_report.merge(nu_experimental::parse_individual_env());
+    for warning in experimental_report.warnings() {
+        eprintln!("Warning: {warning}");
+    }
+
     // Get initial current working directory.
     let init_cwd = get_init_cwd();
     let mut engine_state = get_engine_state();